@@ -7,7 +7,7 @@
 
 ### Usage
 
-Dependency: [Rust](https://www.rust-lang.org/) >= 1.18
+Dependency: [Rust](https://www.rust-lang.org/) >= 1.42
 
 Add this to your `Cargo.toml`:
 
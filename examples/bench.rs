@@ -0,0 +1,61 @@
+extern crate xmlparser as xml;
+
+use std::env;
+use std::fs;
+use std::time::Instant;
+
+// Measures the tokenizer throughput.
+//
+// Uses a generated text-heavy document, similar to a Wikipedia dump page,
+// when no file is provided. Build with `--release`. Compare with
+// `--no-default-features --features std` to measure the `fast-scan` feature.
+fn main() {
+    let text = match env::args().nth(1) {
+        Some(path) => fs::read_to_string(path).unwrap(),
+        None => generate(),
+    };
+
+    const ITERATIONS: u32 = 20;
+
+    let now = Instant::now();
+    let mut count = 0;
+    for _ in 0..ITERATIONS {
+        for token in xml::Tokenizer::from(text.as_str()) {
+            token.unwrap();
+            count += 1;
+        }
+    }
+    let elapsed = now.elapsed();
+
+    let mb = (text.len() as f64 * f64::from(ITERATIONS)) / (1024.0 * 1024.0);
+    println!("{} tokens in {:?}, {:.0} MiB/s", count / ITERATIONS, elapsed / ITERATIONS,
+             mb / elapsed.as_secs_f64());
+}
+
+fn generate() -> String {
+    let paragraph = "'''Lorem ipsum''' dolor sit amet, [[consectetur|adipiscing]] elit, \
+        sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. \
+        Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi \
+        ut aliquip ex ea commodo consequat. {{cite web |url=https://example.com \
+        |title=Duis aute irure dolor}} in reprehenderit in voluptate velit esse \
+        cillum dolore eu fugiat nulla pariatur &amp; excepteur sint occaecat.\n\n";
+
+    let mut text = String::from("<mediawiki xml:lang=\"en\">\n");
+    for i in 0..200 {
+        text.push_str("  <page>\n");
+        text.push_str(&format!("    <title>Page {}</title>\n", i));
+        text.push_str(&format!("    <id>{}</id>\n", i));
+        text.push_str("    <revision>\n");
+        text.push_str("      <contributor><username>Editor</username></contributor>\n");
+        text.push_str("      <comment>Fixed typo in the infobox</comment>\n");
+        text.push_str("      <text bytes=\"32768\" xml:space=\"preserve\">");
+        for _ in 0..50 {
+            text.push_str(paragraph);
+        }
+        text.push_str("</text>\n");
+        text.push_str("    </revision>\n");
+        text.push_str("  </page>\n");
+    }
+    text.push_str("</mediawiki>\n");
+    text
+}
@@ -0,0 +1,57 @@
+extern crate xmlparser as xml;
+
+use std::env;
+use std::fs;
+use std::time::{Duration, Instant};
+
+// Measures the names interning overhead.
+//
+// Uses a generated markup-heavy document, similar to an SVG file,
+// when no file is provided. Build with `--release`.
+fn main() {
+    let text = match env::args().nth(1) {
+        Some(path) => fs::read_to_string(path).unwrap(),
+        None => generate(),
+    };
+
+    let (count, plain) = run(&text, false);
+    let (_, interned) = run(&text, true);
+
+    println!("{} tokens", count);
+    println!("without interning: {:?}", plain);
+    println!("with interning:    {:?}", interned);
+}
+
+fn run(text: &str, is_interning: bool) -> (u32, Duration) {
+    const ITERATIONS: u32 = 20;
+
+    let now = Instant::now();
+    let mut count = 0;
+    for _ in 0..ITERATIONS {
+        let mut p = xml::Tokenizer::from(text);
+        if is_interning {
+            p.enable_interning();
+        }
+
+        for token in p {
+            token.unwrap();
+            count += 1;
+        }
+    }
+
+    (count / ITERATIONS, now.elapsed() / ITERATIONS)
+}
+
+fn generate() -> String {
+    let mut text = String::from("<svg xmlns=\"http://www.w3.org/2000/svg\" \
+                                 xmlns:xlink=\"http://www.w3.org/1999/xlink\">\n");
+    for i in 0..5000 {
+        text.push_str(&format!("  <g id=\"g{}\" transform=\"translate({} 0)\">\n", i, i));
+        text.push_str("    <rect x=\"1\" y=\"2\" width=\"10\" height=\"20\" fill=\"red\"/>\n");
+        text.push_str("    <circle cx=\"5\" cy=\"5\" r=\"3\" stroke=\"blue\" stroke-width=\"2\"/>\n");
+        text.push_str("    <use xlink:href=\"#g0\" x=\"1\" y=\"1\"/>\n");
+        text.push_str("  </g>\n");
+    }
+    text.push_str("</svg>\n");
+    text
+}
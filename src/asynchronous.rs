@@ -0,0 +1,125 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use alloc::boxed::Box;
+
+use {
+    Error,
+    OwnedToken,
+    PushTokenizer,
+    Token,
+};
+
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// A non-blocking source of bytes.
+///
+/// Has the same contract as `AsyncRead::poll_read` from the `futures` and `tokio` crates:
+/// returns the amount of bytes written into the `buf` and `0` at the end of the input.
+/// Since `xmlparser` has no dependencies, the trait should be implemented
+/// by a small wrapper around the actual reader.
+///
+/// Available only with the `async` feature.
+pub trait AsyncSource {
+    /// Attempts to read bytes into the `buf`.
+    ///
+    /// Returns `Poll::Pending` and arranges for the current task to be woken up
+    /// when no data is available yet.
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>>;
+}
+
+
+/// An asynchronous tokenizer.
+///
+/// Reads the input from an [`AsyncSource`] and waits for more bytes
+/// when a token is incomplete. A UTF-8 sequence can be split between reads.
+/// Uses [`PushTokenizer`] internally, so the memory usage is bounded
+/// by the longest token.
+///
+/// Since tokens would borrow the internal buffer, they are returned as `OwnedToken`.
+/// Parsing errors are returned as `io::Error`s of the `InvalidData` kind,
+/// that contain the original [`Error`].
+///
+/// Available only with the `async` feature.
+///
+/// [`AsyncSource`]: trait.AsyncSource.html
+/// [`PushTokenizer`]: struct.PushTokenizer.html
+/// [`Error`]: enum.Error.html
+pub struct AsyncTokenizer<R> {
+    source: R,
+    tokenizer: PushTokenizer,
+    buf: Box<[u8]>,
+}
+
+impl<R: AsyncSource> AsyncTokenizer<R> {
+    /// Creates a new tokenizer.
+    pub fn new(source: R) -> Self {
+        AsyncTokenizer {
+            source,
+            tokenizer: PushTokenizer::new(),
+            buf: vec![0; BUFFER_SIZE].into_boxed_slice(),
+        }
+    }
+
+    /// Returns the underlying source.
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+
+    /// Returns a future that resolves to the next token.
+    ///
+    /// Resolves to `None` at the end of the input or after an error.
+    pub fn next_token(&mut self) -> NextToken<'_, R> {
+        NextToken { tokenizer: self }
+    }
+
+    /// Attempts to return the next token.
+    ///
+    /// Returns `Poll::Ready(None)` at the end of the input or after an error.
+    /// I/O errors are returned as is and reading can be retried after them.
+    pub fn poll_next_token(&mut self, cx: &mut Context<'_>) -> Poll<Option<io::Result<OwnedToken>>> {
+        loop {
+            match self.tokenizer.next_token().map(|res| res.map(Token::into_owned)) {
+                Some(Ok(token)) => return Poll::Ready(Some(Ok(token))),
+                Some(Err(e)) => return Poll::Ready(Some(Err(to_io_error(e)))),
+                None if self.tokenizer.is_finished() => return Poll::Ready(None),
+                None => {}
+            }
+
+            let n = match self.source.poll_read(cx, &mut self.buf) {
+                Poll::Ready(Ok(n)) => n,
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            if n == 0 {
+                self.tokenizer.end_of_input();
+            } else {
+                self.tokenizer.feed(&self.buf[..n]);
+            }
+        }
+    }
+}
+
+
+/// A future returned by [`AsyncTokenizer::next_token`].
+///
+/// [`AsyncTokenizer::next_token`]: struct.AsyncTokenizer.html#method.next_token
+pub struct NextToken<'a, R: 'a> {
+    tokenizer: &'a mut AsyncTokenizer<R>,
+}
+
+impl<'a, R: AsyncSource> Future for NextToken<'a, R> {
+    type Output = Option<io::Result<OwnedToken>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.tokenizer.poll_next_token(cx)
+    }
+}
+
+fn to_io_error(e: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
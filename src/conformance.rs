@@ -0,0 +1,57 @@
+use {
+    validate,
+    Error,
+};
+
+
+/// An outcome of a conformance test case.
+///
+/// Returned by [`run_conformance_case`].
+///
+/// [`run_conformance_case`]: fn.run_conformance_case.html
+#[derive(Debug)]
+pub enum ConformanceResult {
+    /// The outcome matches the expectation.
+    ///
+    /// Contains an error when the document was expected to be rejected.
+    Passed(Option<Error>),
+    /// The document was expected to be well-formed, but was rejected.
+    UnexpectedError(Error),
+    /// The document was expected to be rejected, but was accepted.
+    UnexpectedSuccess,
+}
+
+impl ConformanceResult {
+    /// Checks that the outcome matches the expectation.
+    pub fn is_passed(&self) -> bool {
+        matches!(*self, ConformanceResult::Passed(_))
+    }
+}
+
+
+/// Runs a single conformance test case.
+///
+/// The input is checked using [`validate`]. `expect_valid` should be `true`
+/// for test cases of the `valid` and `invalid` types of the W3C XML
+/// Conformance Test Suite and `false` for the `not-wf` type, since only
+/// well-formedness is checked.
+///
+/// [`validate`]: fn.validate.html
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::run_conformance_case;
+///
+/// assert!(run_conformance_case("<a><b/></a>", true).is_passed());
+/// assert!(run_conformance_case("<a><b></a>", false).is_passed());
+/// assert!(!run_conformance_case("<a><b></a>", true).is_passed());
+/// ```
+pub fn run_conformance_case(input: &str, expect_valid: bool) -> ConformanceResult {
+    match (validate(input), expect_valid) {
+        (Ok(()), true) => ConformanceResult::Passed(None),
+        (Ok(()), false) => ConformanceResult::UnexpectedSuccess,
+        (Err(e), true) => ConformanceResult::UnexpectedError(e),
+        (Err(e), false) => ConformanceResult::Passed(Some(e)),
+    }
+}
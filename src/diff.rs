@@ -0,0 +1,168 @@
+use alloc::vec::Vec;
+
+use {
+    ErrorPos,
+    Stream,
+    Token,
+    Tokenizer,
+};
+
+
+/// Options for the [`diff`] function.
+///
+/// [`diff`]: fn.diff.html
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct DiffOptions {
+    /// Skips `Whitespaces` tokens.
+    pub ignore_whitespaces: bool,
+    /// Compares element attributes regardless of their order.
+    pub ignore_attributes_order: bool,
+}
+
+
+/// Finds the first structural difference between two documents.
+///
+/// Both documents are tokenized in lockstep and tokens are compared by content,
+/// so the formatting inside tokens, like quotes and spaces around `=`, is ignored.
+///
+/// Returns positions of the first differing tokens
+/// or `None` if token streams are equal.
+///
+/// A tokenizing error is always treated as a difference.
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::{diff, DiffOptions, ErrorPos};
+///
+/// let opt = DiffOptions::default();
+/// assert_eq!(diff("<a x='1'/>", "<a x=\"1\"/>", opt), None);
+/// assert_eq!(diff("<a><b/></a>", "<a><c/></a>", opt),
+///            Some((ErrorPos::new(1, 4), ErrorPos::new(1, 4))));
+/// ```
+pub fn diff(a: &str, b: &str, opt: DiffOptions) -> Option<(ErrorPos, ErrorPos)> {
+    let mut iter1 = Items::new(a, opt);
+    let mut iter2 = Items::new(b, opt);
+    loop {
+        match (iter1.next(), iter2.next()) {
+            (Some(t1), Some(t2)) => {
+                if !is_same_item(&t1, &t2) {
+                    return Some((gen_pos(a, t1.pos), gen_pos(b, t2.pos)));
+                }
+            }
+            (Some(t1), None) => {
+                return Some((gen_pos(a, t1.pos), gen_pos(b, b.len())));
+            }
+            (None, Some(t2)) => {
+                return Some((gen_pos(a, a.len()), gen_pos(b, t2.pos)));
+            }
+            (None, None) => {
+                return None;
+            }
+        }
+    }
+}
+
+struct Item<'a> {
+    // `None` indicates an error.
+    token: Option<Token<'a>>,
+    pos: usize,
+}
+
+// Tokens to compare.
+//
+// Only attributes of the current element are buffered,
+// when they have to be sorted.
+struct Items<'a> {
+    tokenizer: Tokenizer<'a>,
+    opt: DiffOptions,
+    // Sorted attributes in the reverse order.
+    attributes: Vec<Item<'a>>,
+    // An item after the buffered attributes.
+    next: Option<Item<'a>>,
+}
+
+impl<'a> Items<'a> {
+    fn new(text: &'a str, opt: DiffOptions) -> Self {
+        Items {
+            tokenizer: Tokenizer::from(text),
+            opt,
+            attributes: Vec::new(),
+            next: None,
+        }
+    }
+
+    fn next_item(&mut self) -> Option<Item<'a>> {
+        loop {
+            let token = self.tokenizer.next()?;
+            let pos = self.tokenizer.token_start();
+            match token {
+                Ok(Token::Whitespaces(_)) if self.opt.ignore_whitespaces => {}
+                Ok(token) => return Some(Item { token: Some(token), pos }),
+                Err(_) => return Some(Item { token: None, pos }),
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Items<'a> {
+    type Item = Item<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.attributes.pop() {
+            return Some(item);
+        }
+
+        if let Some(item) = self.next.take() {
+            return Some(item);
+        }
+
+        let item = self.next_item()?;
+        if !self.opt.ignore_attributes_order || !is_attribute(&item) {
+            return Some(item);
+        }
+
+        self.attributes.push(item);
+        loop {
+            match self.next_item() {
+                Some(item) if is_attribute(&item) => self.attributes.push(item),
+                item => {
+                    self.next = item;
+                    break;
+                }
+            }
+        }
+
+        self.attributes.sort_by_key(|item| match item.token {
+            Some(Token::Attribute((prefix, local), _, _)) => (prefix.to_str(), local.to_str()),
+            _ => unreachable!(),
+        });
+        self.attributes.reverse();
+        self.attributes.pop()
+    }
+}
+
+fn is_attribute(item: &Item) -> bool {
+    matches!(item.token, Some(Token::Attribute(..)))
+}
+
+fn is_same_item(item1: &Item, item2: &Item) -> bool {
+    match (item1.token, item2.token) {
+        (Some(t1), Some(t2)) => is_same_token(&t1, &t2),
+        _ => false,
+    }
+}
+
+fn is_same_token(t1: &Token, t2: &Token) -> bool {
+    // Spans are compared by content, so only the attribute index
+    // and the end of document offset have to be ignored.
+    match (*t1, *t2) {
+        (Token::Attribute(n1, v1, _), Token::Attribute(n2, v2, _)) => n1 == n2 && v1 == v2,
+        (Token::EndOfDocument(_), Token::EndOfDocument(_)) => true,
+        _ => t1 == t2,
+    }
+}
+
+fn gen_pos(text: &str, offset: usize) -> ErrorPos {
+    Stream::from(text).gen_error_pos_from(offset)
+}
@@ -0,0 +1,229 @@
+use alloc::borrow::Cow;
+use core::slice;
+use core::str;
+use alloc::string::{String, ToString};
+
+use {
+    Error,
+    ErrorPos,
+    StrSpan,
+    Token,
+    Tokenizer,
+};
+
+
+/// A document encoding.
+///
+/// Detected by [`Encoding::detect`].
+///
+/// [`Encoding::detect`]: #method.detect
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Encoding {
+    /// UTF-8, with or without a BOM.
+    Utf8,
+    /// UTF-16 Little Endian.
+    Utf16Le,
+    /// UTF-16 Big Endian.
+    Utf16Be,
+    /// ISO-8859-1. US-ASCII is decoded as ISO-8859-1 too.
+    Latin1,
+}
+
+impl Encoding {
+    /// Detects an encoding of the raw document.
+    ///
+    /// Uses the BOM, the first bytes of the document and the `encoding`
+    /// pseudo-attribute of the XML declaration, as described in the
+    /// [Appendix F](https://www.w3.org/TR/xml/#sec-guessing).
+    /// A document without any of them is UTF-8.
+    ///
+    /// UTF-16 without a BOM is detected only when the document starts
+    /// with an XML declaration. When the encoding is detected by the BOM
+    /// or the first bytes, the declared encoding must match it.
+    ///
+    /// # Errors
+    ///
+    /// - `UnsupportedEncoding` when the declared encoding is unknown
+    ///   or doesn't match the BOM or the first bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::Encoding;
+    ///
+    /// assert_eq!(Encoding::detect(b"\xFF\xFE<\0a\0/\0>\0").unwrap(), Encoding::Utf16Le);
+    /// assert_eq!(Encoding::detect(b"<?xml version='1.0' encoding='latin1'?>").unwrap(),
+    ///            Encoding::Latin1);
+    /// assert_eq!(Encoding::detect(b"<a/>").unwrap(), Encoding::Utf8);
+    /// assert!(Encoding::detect(b"\xEF\xBB\xBF<?xml version='1.0' encoding='latin1'?>").is_err());
+    /// ```
+    pub fn detect(bytes: &[u8]) -> Result<Encoding, Error> {
+        let detected = if bytes.starts_with(b"\xEF\xBB\xBF") {
+            Some(Encoding::Utf8)
+        } else if bytes.starts_with(b"\xFF\xFE") || bytes.starts_with(b"<\0?\0") {
+            Some(Encoding::Utf16Le)
+        } else if bytes.starts_with(b"\xFE\xFF") || bytes.starts_with(b"\0<\0?") {
+            Some(Encoding::Utf16Be)
+        } else {
+            None
+        };
+
+        let text = detected.unwrap_or(Encoding::Utf8).ascii_prefix(bytes);
+        let name = match declared_encoding(&text) {
+            Some(name) => name,
+            None => return Ok(detected.unwrap_or(Encoding::Utf8)),
+        };
+
+        // Without a BOM or UTF-16 first bytes, the document
+        // is in one of the ASCII-compatible encodings.
+        let candidates = match detected {
+            Some(ref encoding) => slice::from_ref(encoding),
+            None => &[Encoding::Utf8, Encoding::Latin1],
+        };
+
+        let lowercase = name.to_str().to_ascii_lowercase();
+        match candidates.iter().find(|e| e.names().contains(&lowercase.as_str())) {
+            Some(encoding) => Ok(*encoding),
+            None => {
+                let pos = ErrorPos::from_offset(&text, name.start());
+                Err(Error::UnsupportedEncoding(name.to_str().to_string(), pos))
+            }
+        }
+    }
+
+    /// Returns lowercase names of the encoding.
+    fn names(&self) -> &'static [&'static str] {
+        match *self {
+            Encoding::Utf8 => &["utf-8", "utf8"],
+            Encoding::Utf16Le => &["utf-16", "utf16", "utf-16le"],
+            Encoding::Utf16Be => &["utf-16", "utf16", "utf-16be"],
+            Encoding::Latin1 => {
+                &["iso-8859-1", "iso_8859-1", "latin1", "latin-1", "l1", "us-ascii", "ascii"]
+            }
+        }
+    }
+
+    /// Decodes leading ASCII characters up to the first `>`,
+    /// which is enough to read the XML declaration.
+    ///
+    /// Like `decode_bytes`, keeps the UTF-8 BOM, so positions are the same.
+    fn ascii_prefix(&self, bytes: &[u8]) -> String {
+        let bom_len = self.bom_len(bytes);
+        let mut text = String::new();
+        if *self == Encoding::Utf8 && bom_len != 0 {
+            text.push('\u{FEFF}');
+        }
+
+        let unit_len = match *self {
+            Encoding::Utf16Le | Encoding::Utf16Be => 2,
+            Encoding::Utf8 | Encoding::Latin1 => 1,
+        };
+
+        for unit in bytes[bom_len..].chunks(unit_len) {
+            let c = match (*self, unit) {
+                (Encoding::Utf16Le, &[c, 0]) => c,
+                (Encoding::Utf16Be, &[0, c]) => c,
+                (Encoding::Utf8, &[c]) | (Encoding::Latin1, &[c]) => c,
+                _ => break,
+            };
+
+            if !c.is_ascii() {
+                break;
+            }
+
+            text.push(char::from(c));
+            if c == b'>' {
+                break;
+            }
+        }
+
+        text
+    }
+
+    /// Returns the byte order mark length of the encoding, if present.
+    fn bom_len(&self, bytes: &[u8]) -> usize {
+        match *self {
+            Encoding::Utf8 if bytes.starts_with(b"\xEF\xBB\xBF") => 3,
+            Encoding::Utf16Le if bytes.starts_with(b"\xFF\xFE") => 2,
+            Encoding::Utf16Be if bytes.starts_with(b"\xFE\xFF") => 2,
+            _ => 0,
+        }
+    }
+}
+
+// Returns the `encoding` value of the XML declaration.
+fn declared_encoding(text: &str) -> Option<StrSpan<'_>> {
+    match Tokenizer::from(text).next() {
+        Some(Ok(Token::Declaration(_, encoding, _))) => encoding,
+        _ => None,
+    }
+}
+
+/// Decodes a raw document into a string.
+///
+/// The encoding is detected using [`Encoding::detect`].
+/// UTF-8 input is borrowed as is, including the BOM.
+/// Other encodings are decoded into a new string without the BOM.
+///
+/// # Errors
+///
+/// - `UnsupportedEncoding` when the encoding is not supported.
+/// - `InvalidUtf8` and `InvalidUtf16` on malformed byte sequences.
+///   Contain a position of the first invalid character in the decoded text.
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::decode_bytes;
+///
+/// assert_eq!(decode_bytes(b"\xFE\xFF\0<\0a\0/\0>").unwrap(), "<a/>");
+/// assert_eq!(decode_bytes(b"<?xml version='1.0' encoding='ISO-8859-1'?><a>\xE9</a>").unwrap(),
+///            "<?xml version='1.0' encoding='ISO-8859-1'?><a>\u{E9}</a>");
+/// ```
+///
+/// [`Encoding::detect`]: enum.Encoding.html#method.detect
+pub fn decode_bytes(bytes: &[u8]) -> Result<Cow<'_, str>, Error> {
+    let encoding = Encoding::detect(bytes)?;
+    let data = &bytes[encoding.bom_len(bytes)..];
+    match encoding {
+        Encoding::Utf8 => {
+            match str::from_utf8(bytes) {
+                Ok(text) => Ok(Cow::Borrowed(text)),
+                Err(e) => {
+                    // Already validated.
+                    let text = str::from_utf8(&bytes[..e.valid_up_to()]).unwrap();
+                    Err(Error::InvalidUtf8(ErrorPos::from_offset(text, text.len())))
+                }
+            }
+        }
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let is_le = encoding == Encoding::Utf16Le;
+            let units = data.chunks(2).map(|c| {
+                if c.len() == 1 {
+                    // A truncated code unit is always invalid.
+                    0xD800
+                } else if is_le {
+                    u16::from(c[0]) | u16::from(c[1]) << 8
+                } else {
+                    u16::from(c[0]) << 8 | u16::from(c[1])
+                }
+            });
+
+            let mut text = String::with_capacity(data.len() / 2);
+            for c in ::core::char::decode_utf16(units) {
+                match c {
+                    Ok(c) => text.push(c),
+                    Err(_) => {
+                        let pos = ErrorPos::from_offset(&text, text.len());
+                        return Err(Error::InvalidUtf16(pos));
+                    }
+                }
+            }
+
+            Ok(Cow::Owned(text))
+        }
+        Encoding::Latin1 => {
+            Ok(Cow::Owned(data.iter().map(|b| char::from(*b)).collect()))
+        }
+    }
+}
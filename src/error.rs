@@ -1,13 +1,29 @@
-use std::fmt;
+use core::fmt;
+#[cfg(feature = "std")]
 use std::error;
 
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp;
+
 use {
+    Limit,
+    Stream,
     TokenType,
 };
 
 
 /// An XML parser errors.
+///
+/// Besides the `Display` output, the error details are available via
+/// [`kind`], [`pos`], [`expected`] and `std::error::Error::source`.
+///
+/// [`kind`]: #method.kind
+/// [`pos`]: #method.pos
+/// [`expected`]: #method.expected
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// An invalid token with an optional cause.
     InvalidToken(TokenType, ErrorPos, Option<StreamError>),
@@ -17,6 +33,80 @@ pub enum Error {
 
     /// An unknown token.
     UnknownToken(ErrorPos),
+
+    /// An error inside an element's start tag.
+    ///
+    /// Contains the qualified name of the element and the actual error.
+    InElement(String, Box<Error>),
+
+    /// A close tag doesn't match the open one.
+    ///
+    /// Reported only in the strict mode.
+    UnexpectedCloseTag(ErrorPos),
+
+    /// An element is not closed.
+    ///
+    /// Reported only in the strict mode.
+    UnclosedElement(ErrorPos),
+
+    /// An attribute with the same name is already defined on the element.
+    ///
+    /// Reported only in the strict mode.
+    DuplicatedAttribute(ErrorPos),
+
+    /// An ID value is already used by another attribute.
+    ///
+    /// Contains positions of the current and the previous attributes.
+    ///
+    /// Reported only when IDs checking is enabled.
+    DuplicatedId(ErrorPos, ErrorPos),
+
+    /// An attribute value doesn't match the type declared in the DTD.
+    ///
+    /// Reported only when attribute types checking is enabled.
+    InvalidAttributeValue(ErrorPos),
+
+    /// A standalone document references an entity
+    /// that is not declared in the internal subset.
+    ///
+    /// Reported only when standalone documents checking is enabled.
+    UndeclaredEntity(ErrorPos),
+
+    /// A namespace declaration violates the reserved prefixes or namespaces rules.
+    ///
+    /// Reported only in the namespace well-formed mode.
+    InvalidNamespaceDeclaration(ErrorPos),
+
+    /// A namespace prefix is not bound to a namespace.
+    UnknownNamespacePrefix(ErrorPos),
+
+    /// An offset passed to `Tokenizer::seek` doesn't point to a token start.
+    InvalidSeekOffset(ErrorPos),
+
+    /// An input is not a valid UTF-8.
+    ///
+    /// Contains a position of the first invalid byte.
+    InvalidUtf8(ErrorPos),
+
+    /// An input is not a valid UTF-16.
+    ///
+    /// Contains a position of the first invalid character.
+    InvalidUtf16(ErrorPos),
+
+    /// An unknown or inconsistent document encoding.
+    ///
+    /// Contains the declared encoding name.
+    UnsupportedEncoding(String, ErrorPos),
+
+    /// A tokenizer limit is exceeded.
+    ///
+    /// Reported only when limits are set.
+    LimitExceeded(Limit, ErrorPos),
+
+    /// The document doesn't have the root element.
+    ///
+    /// Reported only in the strict mode.
+    NoRootElement(ErrorPos),
 }
 
 impl fmt::Display for Error {
@@ -38,19 +128,256 @@ impl fmt::Display for Error {
             Error::UnknownToken(pos) => {
                 write!(f, "unknown token at {}", pos)
             }
+            Error::InElement(ref name, ref e) => {
+                write!(f, "{} while parsing element '{}'", e, name)
+            }
+            Error::UnexpectedCloseTag(pos) => {
+                write!(f, "unexpected close tag at {}", pos)
+            }
+            Error::UnclosedElement(pos) => {
+                write!(f, "unclosed element at {}", pos)
+            }
+            Error::DuplicatedAttribute(pos) => {
+                write!(f, "duplicated attribute at {}", pos)
+            }
+            Error::DuplicatedId(pos, prev_pos) => {
+                write!(f, "duplicated id at {}, previously defined at {}", pos, prev_pos)
+            }
+            Error::InvalidAttributeValue(pos) => {
+                write!(f, "invalid attribute value at {}", pos)
+            }
+            Error::UndeclaredEntity(pos) => {
+                write!(f, "undeclared entity in a standalone document at {}", pos)
+            }
+            Error::InvalidNamespaceDeclaration(pos) => {
+                write!(f, "invalid namespace declaration at {}", pos)
+            }
+            Error::UnknownNamespacePrefix(pos) => {
+                write!(f, "unknown namespace prefix at {}", pos)
+            }
+            Error::InvalidSeekOffset(pos) => {
+                write!(f, "invalid seek offset at {}", pos)
+            }
+            Error::InvalidUtf8(pos) => {
+                write!(f, "invalid UTF-8 at {}", pos)
+            }
+            Error::InvalidUtf16(pos) => {
+                write!(f, "invalid UTF-16 at {}", pos)
+            }
+            Error::UnsupportedEncoding(ref name, pos) => {
+                write!(f, "unsupported encoding '{}' at {}", name, pos)
+            }
+            Error::LimitExceeded(limit, pos) => {
+                write!(f, "{} limit exceeded at {}", limit, pos)
+            }
+            Error::NoRootElement(pos) => {
+                write!(f, "the root element is missing at {}", pos)
+            }
+        }
+    }
+}
+
+impl Error {
+    /// Returns the error kind.
+    ///
+    /// `InElement` errors return the kind of the actual error.
+    pub fn kind(&self) -> ErrorKind {
+        match *self {
+            Error::InvalidToken(..) => ErrorKind::InvalidToken,
+            Error::UnexpectedToken(..) => ErrorKind::UnexpectedToken,
+            Error::UnknownToken(..) => ErrorKind::UnknownToken,
+            Error::InElement(_, ref e) => e.kind(),
+            Error::UnexpectedCloseTag(..) => ErrorKind::UnexpectedCloseTag,
+            Error::UnclosedElement(..) => ErrorKind::UnclosedElement,
+            Error::DuplicatedAttribute(..) => ErrorKind::DuplicatedAttribute,
+            Error::DuplicatedId(..) => ErrorKind::DuplicatedId,
+            Error::InvalidAttributeValue(..) => ErrorKind::InvalidAttributeValue,
+            Error::UndeclaredEntity(..) => ErrorKind::UndeclaredEntity,
+            Error::InvalidNamespaceDeclaration(..) => ErrorKind::InvalidNamespaceDeclaration,
+            Error::UnknownNamespacePrefix(..) => ErrorKind::UnknownNamespacePrefix,
+            Error::InvalidSeekOffset(..) => ErrorKind::InvalidSeekOffset,
+            Error::InvalidUtf8(..) => ErrorKind::InvalidUtf8,
+            Error::InvalidUtf16(..) => ErrorKind::InvalidUtf16,
+            Error::UnsupportedEncoding(..) => ErrorKind::UnsupportedEncoding,
+            Error::LimitExceeded(..) => ErrorKind::LimitExceeded,
+            Error::NoRootElement(..) => ErrorKind::NoRootElement,
+        }
+    }
+
+    /// Returns the error position.
+    ///
+    /// For `InvalidToken`, this is the token start. The exact position
+    /// is available via [`StreamError::pos`] of the cause.
+    ///
+    /// For `DuplicatedId`, this is the current attribute position.
+    ///
+    /// [`StreamError::pos`]: enum.StreamError.html#method.pos
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, ErrorPos};
+    ///
+    /// let e = Tokenizer::from("<a b=1/>").nth(1).unwrap().unwrap_err();
+    /// assert_eq!(e.pos(), ErrorPos::new(1, 3));
+    /// assert_eq!(e.cause().unwrap().pos(), Some(ErrorPos::new(1, 6)));
+    /// ```
+    pub fn pos(&self) -> ErrorPos {
+        match *self {
+            Error::InElement(_, ref e) => e.pos(),
+            Error::InvalidToken(_, pos, _)
+            | Error::UnexpectedToken(_, pos)
+            | Error::UnknownToken(pos)
+            | Error::UnexpectedCloseTag(pos)
+            | Error::UnclosedElement(pos)
+            | Error::DuplicatedAttribute(pos)
+            | Error::DuplicatedId(pos, _)
+            | Error::InvalidAttributeValue(pos)
+            | Error::UndeclaredEntity(pos)
+            | Error::InvalidNamespaceDeclaration(pos)
+            | Error::UnknownNamespacePrefix(pos)
+            | Error::InvalidSeekOffset(pos)
+            | Error::InvalidUtf8(pos)
+            | Error::InvalidUtf16(pos)
+            | Error::UnsupportedEncoding(_, pos)
+            | Error::LimitExceeded(_, pos)
+            | Error::NoRootElement(pos) => pos,
+        }
+    }
+
+    /// Returns the stream error that caused an `InvalidToken` error.
+    pub fn cause(&self) -> Option<&StreamError> {
+        match *self {
+            Error::InvalidToken(_, _, ref cause) => cause.as_ref(),
+            Error::InElement(_, ref e) => e.cause(),
+            _ => None,
+        }
+    }
+
+    /// Returns what was expected instead of the invalid input.
+    ///
+    /// The list is empty when unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Expectation};
+    ///
+    /// let e = Tokenizer::from("<?xml version='1.0' standalone='maybe'?>").next().unwrap().unwrap_err();
+    /// assert_eq!(e.expected(), &[Expectation::String("yes".to_string()),
+    ///                            Expectation::String("no".to_string())]);
+    /// ```
+    pub fn expected(&self) -> &[Expectation] {
+        match self.cause() {
+            Some(cause) => cause.expected(),
+            None => &[],
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl error::Error for Error {
     fn description(&self) -> &str {
         "an XML parsing error"
     }
+
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::InvalidToken(_, _, Some(ref cause)) => Some(cause),
+            Error::InElement(_, ref e) => Some(&**e),
+            _ => None,
+        }
+    }
+}
+
+
+/// A kind of the [`Error`].
+///
+/// [`Error`]: enum.Error.html
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// An invalid token.
+    InvalidToken,
+    /// An unexpected token.
+    UnexpectedToken,
+    /// An unknown token.
+    UnknownToken,
+    /// A close tag doesn't match the open one.
+    UnexpectedCloseTag,
+    /// An element is not closed.
+    UnclosedElement,
+    /// A duplicated attribute.
+    DuplicatedAttribute,
+    /// A duplicated ID value.
+    DuplicatedId,
+    /// An attribute value doesn't match its declared type.
+    InvalidAttributeValue,
+    /// An undeclared entity in a standalone document.
+    UndeclaredEntity,
+    /// An invalid namespace declaration.
+    InvalidNamespaceDeclaration,
+    /// An unbound namespace prefix.
+    UnknownNamespacePrefix,
+    /// An invalid seek offset.
+    InvalidSeekOffset,
+    /// An invalid UTF-8 input.
+    InvalidUtf8,
+    /// An invalid UTF-16 input.
+    InvalidUtf16,
+    /// An unknown or inconsistent encoding.
+    UnsupportedEncoding,
+    /// An exceeded limit.
+    LimitExceeded,
+    /// A missing root element.
+    NoRootElement,
+}
+
+
+/// An expected input.
+///
+/// Returned by [`Error::expected`] and [`StreamError::expected`].
+///
+/// [`Error::expected`]: enum.Error.html#method.expected
+/// [`StreamError::expected`]: enum.StreamError.html#method.expected
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum Expectation {
+    /// A character.
+    Char(char),
+    /// A string.
+    String(String),
+    /// An XML space.
+    Space,
+    /// An XML name.
+    Name,
+}
+
+impl Expectation {
+    pub(crate) fn chars(list: &[u8]) -> Box<[Expectation]> {
+        list.iter().map(|c| Expectation::Char(*c as char)).collect()
+    }
+}
+
+impl fmt::Display for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Expectation::Char(c) => write!(f, "{:?}", c),
+            Expectation::String(ref s) => write!(f, "'{}'", s),
+            Expectation::Space => write!(f, "space"),
+            Expectation::Name => write!(f, "name"),
+        }
+    }
 }
 
+static QUOTES: [Expectation; 2] = [Expectation::Char('"'), Expectation::Char('\'')];
+static SPACE: [Expectation; 1] = [Expectation::Space];
+static NAME: [Expectation; 1] = [Expectation::Name];
+
 
 /// A stream parser errors.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum StreamError {
     /// The steam ended earlier than we expected.
     ///
@@ -63,10 +390,9 @@ pub enum StreamError {
 
     /// An invalid/unexpected character.
     ///
-    /// The first byte is an actual one, others - expected.
-    ///
-    /// We are using a single value to reduce the struct size.
-    InvalidChar(Vec<u8>, ErrorPos),
+    /// Contains an actual byte and a list of expected values.
+    /// The list is empty when the character is not allowed at all.
+    InvalidChar(u8, Box<[Expectation]>, ErrorPos),
 
     /// An unexpected character instead of `"` or `'`.
     InvalidQuote(char, ErrorPos),
@@ -74,20 +400,35 @@ pub enum StreamError {
     /// An unexpected character instead of an XML space.
     ///
     /// Includes: `' ' \n \r \t &#x20; &#x9; &#xD; &#xA;`.
+    ///
+    /// The same set as accepted by `Stream::skip_spaces`.
     InvalidSpace(char, ErrorPos),
 
-    /// An unexpected character instead of an XML space.
-    ///
-    /// The first string is an actual one, others - expected.
+    /// An unexpected string.
     ///
-    /// We are using a single value to reduce the struct size.
-    InvalidString(Vec<String>, ErrorPos),
+    /// Contains an actual string and a list of expected values.
+    InvalidString(String, Box<[Expectation]>, ErrorPos),
 
     /// An invalid reference.
-    InvalidReference,
+    ///
+    /// Contains a position of the `&`.
+    InvalidReference(ErrorPos),
+
+    /// An unescaped `<` or `&` in the general entity value.
+    ///
+    /// Such value will break the content after the expansion.
+    InvalidEntityValue(char, ErrorPos),
 
     /// An invalid ExternalID in the DTD.
     InvalidExternalID,
+
+    /// An unknown or misplaced pseudo-attribute in the XML declaration.
+    ///
+    /// Only `version`, `encoding` and `standalone` are allowed, in that order.
+    UnexpectedPseudoAttribute(String, ErrorPos),
+
+    /// A repeated pseudo-attribute in the XML declaration.
+    DuplicatedPseudoAttribute(String, ErrorPos),
 }
 
 impl fmt::Display for StreamError {
@@ -99,13 +440,16 @@ impl fmt::Display for StreamError {
             StreamError::InvalidName => {
                 write!(f, "invalid name token")
             }
-            StreamError::InvalidChar(ref chars, pos) => {
-                // Vec<u8> -> Vec<String>
-                let list: Vec<String> =
-                    chars.iter().skip(1).map(|c| String::from_utf8(vec![*c]).unwrap()).collect();
-
-                write!(f, "expected '{}' not '{}' at {}",
-                       list.join("', '"), chars[0] as char, pos)
+            StreamError::InvalidChar(actual, ref expected, pos) => {
+                if expected.is_empty() {
+                    write!(f, "unexpected character {:?} at {}", actual as char, pos)
+                } else if expected.len() == 1 {
+                    write!(f, "expected {} but found {:?} at {}",
+                           expected[0], actual as char, pos)
+                } else {
+                    write!(f, "expected one of [{}] but found {:?} at {}",
+                           join(expected), actual as char, pos)
+                }
             }
             StreamError::InvalidQuote(c, pos) => {
                 write!(f, "expected quote mark not '{}' at {}", c, pos)
@@ -113,31 +457,108 @@ impl fmt::Display for StreamError {
             StreamError::InvalidSpace(c, pos) => {
                 write!(f, "expected space not '{}' at {}", c, pos)
             }
-            StreamError::InvalidString(ref strings, pos) => {
-                write!(f, "expected '{}' not '{}' at {}",
-                       strings[1..].join("', '"), strings[0], pos)
+            StreamError::InvalidString(ref actual, ref expected, pos) => {
+                write!(f, "expected {} not '{}' at {}", join(expected), actual, pos)
+            }
+            StreamError::InvalidReference(pos) => {
+                write!(f, "invalid reference at {}", pos)
             }
-            StreamError::InvalidReference => {
-                write!(f, "invalid reference")
+            StreamError::InvalidEntityValue(c, pos) => {
+                write!(f, "unescaped '{}' in entity value at {}", c, pos)
             }
             StreamError::InvalidExternalID => {
                 write!(f, "invalid ExternalID")
             }
+            StreamError::UnexpectedPseudoAttribute(ref name, pos) => {
+                write!(f, "unexpected pseudo-attribute '{}' at {}", name, pos)
+            }
+            StreamError::DuplicatedPseudoAttribute(ref name, pos) => {
+                write!(f, "duplicated pseudo-attribute '{}' at {}", name, pos)
+            }
+        }
+    }
+}
+
+impl StreamError {
+    /// Returns the error position, if known.
+    pub fn pos(&self) -> Option<ErrorPos> {
+        match *self {
+            StreamError::InvalidChar(_, _, pos)
+            | StreamError::InvalidQuote(_, pos)
+            | StreamError::InvalidSpace(_, pos)
+            | StreamError::InvalidString(_, _, pos)
+            | StreamError::InvalidReference(pos)
+            | StreamError::InvalidEntityValue(_, pos)
+            | StreamError::UnexpectedPseudoAttribute(_, pos)
+            | StreamError::DuplicatedPseudoAttribute(_, pos) => Some(pos),
+            StreamError::UnexpectedEndOfStream
+            | StreamError::InvalidName
+            | StreamError::InvalidExternalID => None,
+        }
+    }
+
+    /// Returns what was expected instead of the invalid input.
+    ///
+    /// The list is empty when unknown.
+    pub fn expected(&self) -> &[Expectation] {
+        match *self {
+            StreamError::InvalidChar(_, ref expected, _)
+            | StreamError::InvalidString(_, ref expected, _) => expected,
+            StreamError::InvalidQuote(..) => &QUOTES,
+            StreamError::InvalidSpace(..) => &SPACE,
+            StreamError::InvalidName => &NAME,
+            _ => &[],
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl error::Error for StreamError {
     fn description(&self) -> &str {
         "an XML stream parsing error"
     }
 }
 
+fn join(list: &[Expectation]) -> String {
+    let list: Vec<String> = list.iter().map(|e| e.to_string()).collect();
+    list.join(", ")
+}
+
+
+/// A non-fatal parsing diagnostic.
+///
+/// Warnings do not stop the parsing.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum Warning {
+    /// A byte order mark was skipped.
+    BomStripped(ErrorPos),
+    /// A prefix is not bound to a namespace.
+    ///
+    /// Recorded only in the lenient namespaces mode.
+    UnknownNamespacePrefix(ErrorPos),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Warning::BomStripped(pos) => {
+                write!(f, "byte order mark stripped at {}", pos)
+            }
+            Warning::UnknownNamespacePrefix(pos) => {
+                write!(f, "unknown namespace prefix at {}", pos)
+            }
+        }
+    }
+}
+
 
 /// Position of the error.
 ///
 /// Position indicates row/line and column. Starting positions is 1:1.
-#[derive(Clone, Copy, PartialEq, Debug)]
+///
+/// Positions are ordered by row and then by column.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[allow(missing_docs)]
 pub struct ErrorPos {
     pub row: u32,
@@ -149,6 +570,23 @@ impl ErrorPos {
     pub fn new(row: u32, col: u32) -> ErrorPos {
         ErrorPos { row, col }
     }
+
+    /// Calculates a position of the byte offset in the text.
+    ///
+    /// Rows and columns are counted the same way as in the errors.
+    /// Offsets past the end are clamped to the text length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::ErrorPos;
+    ///
+    /// assert_eq!(ErrorPos::from_offset("<a>\n<b/></a>", 5), ErrorPos::new(2, 2));
+    /// ```
+    pub fn from_offset(text: &str, offset: usize) -> ErrorPos {
+        let offset = cmp::min(offset, text.len());
+        Stream::from(text).gen_error_pos_from(offset)
+    }
 }
 
 impl fmt::Display for ErrorPos {
@@ -167,3 +605,29 @@ fn err_size_2() {
     println!("{}", ::std::mem::size_of::<StreamError>());
     assert!(::std::mem::size_of::<StreamError>() <= 64);
 }
+
+#[test]
+fn invalid_char_1() {
+    let e = StreamError::InvalidChar(b'x', Expectation::chars(b";"), ErrorPos::new(3, 5));
+    assert_eq!(e.to_string(), "expected ';' but found 'x' at 3:5");
+}
+
+#[test]
+fn invalid_char_2() {
+    let e = StreamError::InvalidChar(b'x', Expectation::chars(b";&"), ErrorPos::new(3, 5));
+    assert_eq!(e.to_string(), "expected one of [';', '&'] but found 'x' at 3:5");
+}
+
+#[test]
+fn pos_ord_1() {
+    assert!(ErrorPos::new(1, 5) < ErrorPos::new(2, 1));
+    assert!(ErrorPos::new(2, 1) < ErrorPos::new(2, 3));
+    assert_eq!(ErrorPos::new(2, 3).cmp(&ErrorPos::new(2, 3)), ::std::cmp::Ordering::Equal);
+}
+
+#[test]
+fn pos_ord_2() {
+    let mut list = vec![ErrorPos::new(3, 1), ErrorPos::new(1, 10), ErrorPos::new(1, 2)];
+    list.sort();
+    assert_eq!(list, vec![ErrorPos::new(1, 2), ErrorPos::new(1, 10), ErrorPos::new(3, 1)]);
+}
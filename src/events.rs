@@ -0,0 +1,225 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use ns::Scopes;
+use token::expand_references;
+use {
+    ElementEnd,
+    Error,
+    ExpandedName,
+    StrSpan,
+    Token,
+    Tokenizer,
+    Warning,
+    XmlByteExt,
+};
+
+
+/// A high-level parsing event.
+///
+/// Returned by [`parse_into_events`].
+///
+/// [`parse_into_events`]: fn.parse_into_events.html
+#[derive(Clone, PartialEq, Debug)]
+pub enum Event<'a> {
+    /// An element start with all its attributes.
+    ///
+    /// Attribute values have references expanded.
+    /// Namespace declarations are listed too.
+    StartElement(ExpandedName<'a>, Vec<(ExpandedName<'a>, Cow<'a, str>)>),
+    /// An element end. Emitted for empty elements too.
+    EndElement(ExpandedName<'a>),
+    /// Text, whitespaces and CDATA.
+    ///
+    /// References are expanded, except for CDATA.
+    Text(Cow<'a, str>),
+    /// A comment.
+    Comment(StrSpan<'a>),
+    /// A processing instruction.
+    ProcessingInstruction(StrSpan<'a>, Option<StrSpan<'a>>),
+}
+
+
+/// Options for the [`parse_into_events_with`] function.
+///
+/// [`parse_into_events_with`]: fn.parse_into_events_with.html
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct EventOptions {
+    /// Resolves prefixes that are not bound to a namespace to `None`
+    /// and records a warning instead of an error.
+    ///
+    /// Useful for fragments that rely on bindings from an enclosing document.
+    pub lenient_namespaces: bool,
+
+    /// Merges adjacent text, whitespaces and CDATA into a single `Text` event.
+    ///
+    /// Comments and processing instructions are not skipped, so they still split the text.
+    ///
+    /// There is no such option for `Tokenizer`, since the merged text
+    /// is not a single region of the original text and can't be a `StrSpan`.
+    pub coalesce_text: bool,
+
+    /// Skips `Text` events that contain only XML spaces.
+    ///
+    /// Text with CDATA or character references, like `&#x20;`, is never skipped,
+    /// since such spaces are a part of the content. When combined with `coalesce_text`,
+    /// the merged text is checked.
+    ///
+    /// Use `Tokenizer::skip_whitespace_text` to skip whitespaces without events.
+    pub skip_whitespace_text: bool,
+}
+
+
+/// Parses a document into a list of namespace-resolved events.
+///
+/// The document is parsed in the strict and namespace well-formed modes.
+/// The XML declaration and the DTD are not included.
+/// Only the predefined entity and character references are expanded.
+///
+/// # Errors
+///
+/// Returns the first error. A prefix that is not bound to a namespace
+/// results in the `UnknownNamespacePrefix` error.
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::{parse_into_events, Event};
+///
+/// let events = parse_into_events("<svg xmlns='http://www.w3.org/2000/svg'/>").unwrap();
+/// match events[0] {
+///     Event::StartElement(ref name, _) => {
+///         assert_eq!(name.namespace, Some("http://www.w3.org/2000/svg"));
+///         assert_eq!(name.local.to_str(), "svg");
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+pub fn parse_into_events(text: &str) -> Result<Vec<Event<'_>>, Error> {
+    parse_into_events_with(text, EventOptions::default()).map(|(events, _)| events)
+}
+
+
+/// Parses a document into a list of namespace-resolved events using the specified options.
+///
+/// Same as [`parse_into_events`], but also returns recorded warnings.
+///
+/// [`parse_into_events`]: fn.parse_into_events.html
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::{parse_into_events_with, ErrorPos, Event, EventOptions, Warning};
+///
+/// let opt = EventOptions { lenient_namespaces: true, ..EventOptions::default() };
+/// let (events, warnings) = parse_into_events_with("<x:a/>", opt).unwrap();
+/// match events[0] {
+///     Event::StartElement(ref name, _) => assert_eq!(name.namespace, None),
+///     _ => unreachable!(),
+/// }
+/// assert_eq!(warnings, vec![Warning::UnknownNamespacePrefix(ErrorPos::new(1, 2))]);
+/// ```
+pub fn parse_into_events_with(
+    text: &str,
+    opt: EventOptions,
+) -> Result<(Vec<Event<'_>>, Vec<Warning>), Error> {
+    let mut p = Tokenizer::from(text);
+    p.set_strict_mode();
+    p.set_namespace_well_formed_mode();
+
+    let mut events = Vec::new();
+    let mut warnings = Vec::new();
+    let mut scopes = Scopes::default();
+    let mut open_elements = Vec::new();
+    let mut element = None;
+    let mut attributes = Vec::new();
+    // The text content and whether it contains only XML spaces.
+    let mut text: Option<(Cow<str>, bool)> = None;
+
+    for token in p {
+        let token = token?;
+        let is_text = matches!(token, Token::Text(_) | Token::Whitespaces(_) | Token::Cdata(_));
+        if !is_text || !opt.coalesce_text {
+            flush_text(&mut text, opt, &mut events);
+        }
+
+        match token {
+            Token::ElementStart(prefix, local) => {
+                element = Some((prefix, local));
+                attributes.clear();
+                scopes.push();
+            }
+            Token::Attribute((prefix, local), value, _) => {
+                scopes.bind(prefix, local, value);
+                attributes.push((prefix, local, value));
+            }
+            Token::ElementEnd(end) => {
+                if let Some((prefix, local)) = element.take() {
+                    let name = scopes.resolve(prefix, local, true,
+                                              opt.lenient_namespaces, &mut warnings)?;
+
+                    let mut attrs = Vec::with_capacity(attributes.len());
+                    for &(prefix, local, value) in &attributes {
+                        let attr_name = scopes.resolve(prefix, local, false,
+                                                   opt.lenient_namespaces, &mut warnings)?;
+                        attrs.push((attr_name, expand_references(value)));
+                    }
+
+                    events.push(Event::StartElement(name, attrs));
+                    open_elements.push(name);
+                }
+
+                match end {
+                    ElementEnd::Open => {}
+                    ElementEnd::Empty | ElementEnd::Close(_, _) => {
+                        // Tags are already matched by the strict mode.
+                        if let Some(name) = open_elements.pop() {
+                            events.push(Event::EndElement(name));
+                        }
+
+                        scopes.pop();
+                    }
+                }
+            }
+            token @ Token::Text(_) | token @ Token::Whitespaces(_) | token @ Token::Cdata(_) => {
+                if let Some(content) = token.text_content() {
+                    // Spaces from character references are not ignorable.
+                    let is_space = match token {
+                        Token::Whitespaces(text) | Token::Text(text) => {
+                            text.as_bytes().iter().all(|c| c.is_xml_space())
+                        }
+                        _ => false,
+                    };
+
+                    match text {
+                        Some((ref mut prev, ref mut prev_is_space)) => {
+                            prev.to_mut().push_str(&content);
+                            *prev_is_space &= is_space;
+                        }
+                        None => text = Some((content, is_space)),
+                    }
+                }
+            }
+            Token::Comment(text) => {
+                events.push(Event::Comment(text));
+            }
+            Token::ProcessingInstruction(target, content) => {
+                events.push(Event::ProcessingInstruction(target, content));
+            }
+            _ => {}
+        }
+    }
+
+    flush_text(&mut text, opt, &mut events);
+
+    Ok((events, warnings))
+}
+
+fn flush_text<'a>(text: &mut Option<(Cow<'a, str>, bool)>, opt: EventOptions,
+                  events: &mut Vec<Event<'a>>) {
+    if let Some((text, is_space)) = text.take() {
+        if !(is_space && opt.skip_whitespace_text) {
+            events.push(Event::Text(text));
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use Map;
+
+
+/// An interned name handle.
+///
+/// Can be resolved back to a string via [`Interner::resolve`].
+///
+/// [`Interner::resolve`]: struct.Interner.html#method.resolve
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct NameId(u32);
+
+impl NameId {
+    /// Returns a raw id value.
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+
+/// A names interner.
+///
+/// Stores each unique name only once. Names are not copied,
+/// since they are borrowed from the original document.
+#[derive(Clone, Default, Debug)]
+pub struct Interner<'a> {
+    ids: Map<&'a str, NameId>,
+    names: Vec<&'a str>,
+}
+
+impl<'a> Interner<'a> {
+    /// Constructs a new, empty `Interner`.
+    pub fn new() -> Self {
+        Interner::default()
+    }
+
+    /// Interns a name and returns its id.
+    ///
+    /// Identical names will have the same id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than `u32::MAX` unique names.
+    pub fn intern(&mut self, name: &'a str) -> NameId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+
+        let id = match u32::try_from(self.names.len()) {
+            Ok(id) => NameId(id),
+            Err(_) => panic!("the names interner supports up to u32::MAX unique names"),
+        };
+        self.names.push(name);
+        self.ids.insert(name, id);
+        id
+    }
+
+    /// Returns a name by id.
+    ///
+    /// Returns `None` if the id was not produced by this interner.
+    pub fn resolve(&self, id: NameId) -> Option<&'a str> {
+        self.names.get(id.0 as usize).cloned()
+    }
+
+    /// Returns an amount of unique names.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Checks that interner is empty.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
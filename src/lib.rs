@@ -34,17 +34,21 @@ token positions.
 - Good error processing. All error types contain position (line:column) where it occurred.
 - No heap allocations.
 - No dependencies.
-- Tiny. ~1500 LOC and ~35KiB in the release build according to the `cargo-bloat`.
+- `no_std` support. Disable the default `std` feature to use only `core` and `alloc`.
+- Optional `async` feature with a dependency-free `AsyncTokenizer`.
+- Small. ~9k LOC, most of the optional checks are disabled by default.
 
 ## Limitations
 
-- Currently, only ENTITY objects are parsed from the DOCTYPE. Other ignored.
-- No tree structure validation. So an XML like `<root><child></root></child>`
-  will be parsed without errors. You should check for this manually.
-  On the other hand `<a/><a/>` will lead to an error.
-- Duplicated attributes is not an error. So an XML like `<item a="v1" a="v2"/>`
-  will be parsed without errors. You should check for this manually.
-- UTF-8 only.
+- ELEMENT, ATTLIST and NOTATION declarations are skipped by default.
+  Use `Tokenizer::enable_dtd_declaration_tokens` to get them.
+  An ELEMENT content model is returned as is, without parsing.
+- No tree structure validation by default. So an XML like `<root><child></root></child>`
+  will be parsed without errors. Use `Tokenizer::set_strict_mode` or `validate`
+  to check for this. On the other hand `<a/><a/>` will always lead to an error.
+- Duplicated attributes is not an error by default. So an XML like `<item a="v1" a="v2"/>`
+  will be parsed without errors, unless the strict mode is enabled.
+- UTF-8 only. Other encodings should be decoded first, see `decode_bytes`.
 
 ## Safety
 
@@ -53,7 +57,9 @@ token positions.
 - The library forbids the unsafe code.
 */
 
-#![cfg_attr(feature = "cargo-clippy", allow(unreadable_literal))]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#![allow(clippy::unreadable_literal)]
 
 #![doc(html_root_url = "https://docs.rs/xmlparser/0.5.0")]
 
@@ -61,19 +67,56 @@ token positions.
 #![warn(missing_docs)]
 
 
+#[cfg(any(feature = "std", test))]
+extern crate core;
+extern crate alloc;
+
+
+#[cfg(feature = "async")]
+mod asynchronous;
+mod conformance;
+mod diff;
+mod encoding;
 mod error;
+mod events;
+mod interner;
+mod multi;
+mod ns;
+mod owned;
+mod push;
+mod sax;
+mod scan;
 mod stream;
 mod strspan;
 mod text;
 mod token;
+mod writer;
 mod xml;
 mod xmlchar;
 
 
+#[cfg(feature = "async")]
+pub use asynchronous::*;
+pub use conformance::*;
+pub use diff::*;
+pub use encoding::*;
 pub use error::*;
+pub use events::*;
+pub use interner::{Interner, NameId};
+pub use multi::MultiTokenizer;
+pub use ns::*;
+pub use owned::*;
+pub use push::PushTokenizer;
+pub use sax::*;
 pub use stream::*;
 pub use text::*;
 pub use strspan::*;
 pub use token::*;
+pub use writer::*;
 pub use xml::*;
 pub use xmlchar::*;
+
+#[cfg(feature = "std")]
+use std::collections::HashMap as Map;
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap as Map;
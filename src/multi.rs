@@ -0,0 +1,97 @@
+use {
+    Error,
+    Token,
+    Tokenizer,
+};
+
+
+/// A tokenizer over multiple independent documents.
+///
+/// Each source is parsed by its own `Tokenizer` and each token or error
+/// is tagged with an index of its source.
+///
+/// By default, parsing stops after the first error.
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::{MultiTokenizer, Token};
+///
+/// let sources = vec!["<a/>", "<b/>"];
+/// let mut p = MultiTokenizer::new(sources.into_iter());
+/// assert!(matches!(p.next(), Some((0, Ok(Token::ElementStart(_, _))))));
+/// assert!(matches!(p.next(), Some((0, Ok(Token::ElementEnd(_))))));
+/// assert!(matches!(p.next(), Some((1, Ok(Token::ElementStart(_, _))))));
+/// ```
+pub struct MultiTokenizer<'a, I> {
+    sources: I,
+    tokenizer: Option<Tokenizer<'a>>,
+    index: usize,
+    continue_on_error: bool,
+    is_finished: bool,
+}
+
+impl<'a, I> MultiTokenizer<'a, I>
+    where I: Iterator<Item = &'a str>
+{
+    /// Creates a new tokenizer from an iterator of sources.
+    pub fn new(sources: I) -> Self {
+        MultiTokenizer {
+            sources,
+            tokenizer: None,
+            index: 0,
+            continue_on_error: false,
+            is_finished: false,
+        }
+    }
+
+    /// Continues with the next source after an error.
+    ///
+    /// The rest of the source that contains an error is skipped.
+    pub fn set_continue_on_error(&mut self) {
+        self.continue_on_error = true;
+    }
+}
+
+impl<'a, I> Iterator for MultiTokenizer<'a, I>
+    where I: Iterator<Item = &'a str>
+{
+    type Item = (usize, Result<Token<'a>, Error>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.is_finished {
+            if self.tokenizer.is_none() {
+                match self.sources.next() {
+                    Some(text) => self.tokenizer = Some(Tokenizer::from(text)),
+                    None => {
+                        self.is_finished = true;
+                        break;
+                    }
+                }
+            }
+
+            match self.tokenizer.as_mut().and_then(|t| t.next()) {
+                Some(Ok(token)) => {
+                    return Some((self.index, Ok(token)));
+                }
+                Some(Err(e)) => {
+                    let index = self.index;
+                    if self.continue_on_error {
+                        self.tokenizer = None;
+                        self.index += 1;
+                    } else {
+                        self.is_finished = true;
+                    }
+
+                    return Some((index, Err(e)));
+                }
+                None => {
+                    self.tokenizer = None;
+                    self.index += 1;
+                }
+            }
+        }
+
+        None
+    }
+}
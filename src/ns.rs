@@ -0,0 +1,223 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use xml::{XML_NS, XMLNS_NS};
+use {
+    ElementEnd,
+    Error,
+    ErrorPos,
+    StrSpan,
+    Token,
+    Tokenizer,
+    Warning,
+};
+
+
+/// A namespace-resolved name.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ExpandedName<'a> {
+    /// A namespace name, as is.
+    ///
+    /// `None` for unprefixed names outside of a default namespace
+    /// and for unprefixed attributes.
+    pub namespace: Option<&'a str>,
+    /// A local name.
+    pub local: StrSpan<'a>,
+    /// A prefix. Can be empty.
+    pub prefix: StrSpan<'a>,
+}
+
+
+/// A namespace-resolving tokenizer.
+///
+/// A wrapper around `Tokenizer` that tracks namespace declarations
+/// and returns tokens along with resolved names for `ElementStart`,
+/// `Attribute` and `ElementEnd::Close` tokens.
+///
+/// Since namespaces can be declared after the element name,
+/// all attributes of an element are parsed before its `ElementStart` token is returned.
+/// Buffers are reused, so no allocations are made for documents without namespaces,
+/// except for the per-element scopes stack.
+///
+/// Namespace declarations are not visible in the lazy attributes mode.
+///
+/// # Errors
+///
+/// A prefix that is not bound to a namespace results in the `UnknownNamespacePrefix` error.
+/// Parsing is stopped after the first error.
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::{NsTokenizer, Tokenizer};
+///
+/// let mut p = NsTokenizer::from(Tokenizer::from("<svg xmlns='http://www.w3.org/2000/svg'/>"));
+/// let (_, name) = p.next().unwrap().unwrap(); // ElementStart
+/// assert_eq!(name.unwrap().namespace, Some("http://www.w3.org/2000/svg"));
+/// ```
+pub struct NsTokenizer<'a> {
+    tokenizer: Tokenizer<'a>,
+    scopes: Scopes<'a>,
+    // Attributes and the end of the current element start tag.
+    buffer: VecDeque<Result<Token<'a>, Error>>,
+    is_finished: bool,
+}
+
+impl<'a> From<Tokenizer<'a>> for NsTokenizer<'a> {
+    fn from(tokenizer: Tokenizer<'a>) -> Self {
+        NsTokenizer {
+            tokenizer,
+            scopes: Scopes::default(),
+            buffer: VecDeque::new(),
+            is_finished: false,
+        }
+    }
+}
+
+impl<'a> NsTokenizer<'a> {
+    /// Returns the underlying tokenizer.
+    ///
+    /// Its state is ahead of the returned tokens while attributes are buffered.
+    pub fn tokenizer(&self) -> &Tokenizer<'a> {
+        &self.tokenizer
+    }
+
+    fn read_attributes(&mut self) {
+        for token in self.tokenizer.by_ref() {
+            let is_end = match token {
+                Ok(Token::Attribute((prefix, local), value, _)) => {
+                    self.scopes.bind(prefix, local, value);
+                    false
+                }
+                _ => true,
+            };
+
+            self.buffer.push_back(token);
+
+            if is_end {
+                break;
+            }
+        }
+    }
+
+    fn resolve(&mut self, token: Token<'a>) -> Result<(Token<'a>, Option<ExpandedName<'a>>), Error> {
+        let mut warnings = Vec::new();
+        let name = match token {
+            Token::ElementStart(prefix, local) => {
+                Some(self.scopes.resolve(prefix, local, true, false, &mut warnings)?)
+            }
+            Token::Attribute((prefix, local), _, _) => {
+                Some(self.scopes.resolve(prefix, local, false, false, &mut warnings)?)
+            }
+            Token::ElementEnd(ElementEnd::Close(prefix, local)) => {
+                let name = self.scopes.resolve(prefix, local, true, false, &mut warnings)?;
+                self.scopes.pop();
+                Some(name)
+            }
+            Token::ElementEnd(ElementEnd::Empty) => {
+                self.scopes.pop();
+                None
+            }
+            _ => None,
+        };
+
+        Ok((token, name))
+    }
+}
+
+impl<'a> Iterator for NsTokenizer<'a> {
+    type Item = Result<(Token<'a>, Option<ExpandedName<'a>>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_finished {
+            return None;
+        }
+
+        let token = match self.buffer.pop_front() {
+            Some(token) => token,
+            None => {
+                let token = self.tokenizer.next()?;
+                if let Ok(Token::ElementStart(_, _)) = token {
+                    self.scopes.push();
+                    self.read_attributes();
+                }
+
+                token
+            }
+        };
+
+        let res = token.and_then(|token| self.resolve(token));
+        if res.is_err() {
+            self.is_finished = true;
+        }
+
+        Some(res)
+    }
+}
+
+
+/// Namespace scopes of the open elements.
+#[derive(Default)]
+pub(crate) struct Scopes<'a> {
+    // Prefix and namespace bindings. An empty prefix is used for the default namespace.
+    bindings: Vec<(&'a str, &'a str)>,
+    // Bindings count before each open element.
+    starts: Vec<usize>,
+}
+
+impl<'a> Scopes<'a> {
+    pub(crate) fn push(&mut self) {
+        self.starts.push(self.bindings.len());
+    }
+
+    pub(crate) fn pop(&mut self) {
+        if let Some(start) = self.starts.pop() {
+            self.bindings.truncate(start);
+        }
+    }
+
+    /// Binds a prefix if the attribute is a namespace declaration.
+    pub(crate) fn bind(&mut self, prefix: StrSpan<'a>, local: StrSpan<'a>, value: StrSpan<'a>) {
+        match (prefix.to_str(), local.to_str()) {
+            ("", "xmlns") => self.bindings.push(("", value.to_str())),
+            ("xmlns", local) => self.bindings.push((local, value.to_str())),
+            _ => {}
+        }
+    }
+
+    pub(crate) fn resolve(
+        &self,
+        prefix: StrSpan<'a>,
+        local: StrSpan<'a>,
+        is_element: bool,
+        is_lenient: bool,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<ExpandedName<'a>, Error> {
+        let namespace = match prefix.to_str() {
+            // Unprefixed attributes have no namespace, except for `xmlns`.
+            "" if !is_element && local.to_str() == "xmlns" => Some(XMLNS_NS),
+            "" if !is_element => None,
+            "xml" => Some(XML_NS),
+            "xmlns" => Some(XMLNS_NS),
+            p => {
+                let uri = self.bindings.iter().rev().find(|&&(p2, _)| p2 == p).map(|&(_, uri)| uri);
+                match uri {
+                    // An empty default namespace undeclares it.
+                    Some("") | None if p.is_empty() => None,
+                    Some(uri) => Some(uri),
+                    None => {
+                        let pos = ErrorPos::from_offset(prefix.full_str(), prefix.start());
+                        if !is_lenient {
+                            return Err(Error::UnknownNamespacePrefix(pos));
+                        }
+
+                        warnings.push(Warning::UnknownNamespacePrefix(pos));
+                        None
+                    }
+                }
+            }
+        };
+
+        Ok(ExpandedName { namespace, local, prefix })
+    }
+}
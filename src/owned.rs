@@ -0,0 +1,388 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use {
+    AttributeDefault,
+    AttributeDefinition,
+    ElementEnd,
+    EntityDefinition,
+    ExternalId,
+    StrSpan,
+    Token,
+};
+
+
+/// An owned version of the [`Token`].
+///
+/// Unlike `Token`, doesn't borrow the original text, so it can be stored
+/// past the text lifetime or sent to another thread.
+///
+/// Values are stored as `String`s by default, which doesn't preserve positions.
+/// `OwnedToken<OwnedStrSpan>` stores [`OwnedStrSpan`]s instead.
+///
+/// Created by [`Token::into_owned`] and [`Token::into_owned_spans`].
+///
+/// [`Token`]: enum.Token.html
+/// [`OwnedStrSpan`]: struct.OwnedStrSpan.html
+/// [`Token::into_owned`]: enum.Token.html#method.into_owned
+/// [`Token::into_owned_spans`]: enum.Token.html#method.into_owned_spans
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum OwnedToken<S = String> {
+    Declaration(S, Option<S>, Option<S>),
+    ProcessingInstruction(S, Option<S>),
+    Comment(S),
+    DtdStart(S, Option<OwnedExternalId<S>>),
+    EmptyDtd(S, Option<OwnedExternalId<S>>),
+    EntityDeclaration(S, OwnedEntityDefinition<S>),
+    ElementDeclaration(S, S),
+    AttlistDeclaration(S, Vec<OwnedAttributeDefinition<S>>),
+    NotationDeclaration(S, Option<S>, Option<S>),
+    DtdEnd,
+    ElementStart(S, S),
+    Attribute((S, S), S, usize),
+    ElementEnd(OwnedElementEnd<S>),
+    Text(S),
+    Whitespaces(S),
+    DocumentBoundary,
+    EndOfDocument(usize),
+    Cdata(S),
+}
+
+
+/// An owned version of the [`ElementEnd`].
+///
+/// [`ElementEnd`]: enum.ElementEnd.html
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum OwnedElementEnd<S = String> {
+    Open,
+    Close(S, S),
+    Empty,
+}
+
+
+/// An owned version of the [`ExternalId`].
+///
+/// [`ExternalId`]: enum.ExternalId.html
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum OwnedExternalId<S = String> {
+    System(S),
+    Public(S, S),
+}
+
+
+/// An owned version of the [`EntityDefinition`].
+///
+/// [`EntityDefinition`]: enum.EntityDefinition.html
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum OwnedEntityDefinition<S = String> {
+    EntityValue(S),
+    ExternalId(OwnedExternalId<S>),
+}
+
+/// An owned version of the [`AttributeDefinition`].
+///
+/// [`AttributeDefinition`]: struct.AttributeDefinition.html
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct OwnedAttributeDefinition<S = String> {
+    pub name: S,
+    pub att_type: S,
+    pub default: OwnedAttributeDefault<S>,
+}
+
+
+/// An owned version of the [`AttributeDefault`].
+///
+/// [`AttributeDefault`]: enum.AttributeDefault.html
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum OwnedAttributeDefault<S = String> {
+    Required,
+    Implied,
+    Value(S),
+    Fixed(S),
+}
+
+
+
+/// An owned version of the [`StrSpan`].
+///
+/// Stores a copy of the span text and the span position in the original text.
+///
+/// **Note:** like `StrSpan`, spans are compared and hashed by content only.
+///
+/// [`StrSpan`]: struct.StrSpan.html
+#[derive(Clone)]
+pub struct OwnedStrSpan {
+    text: String,
+    start: usize,
+}
+
+impl<'a> From<StrSpan<'a>> for OwnedStrSpan {
+    fn from(span: StrSpan<'a>) -> Self {
+        OwnedStrSpan {
+            text: span.to_str().to_string(),
+            start: span.start(),
+        }
+    }
+}
+
+impl OwnedStrSpan {
+    /// Returns a start position of the span.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns a end position of the span.
+    pub fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+
+    /// Returns a length of the span.
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Returns a length of the span.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Returns a span slice.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the span text.
+    pub fn into_string(self) -> String {
+        self.text
+    }
+}
+
+impl PartialEq for OwnedStrSpan {
+    fn eq(&self, other: &OwnedStrSpan) -> bool {
+        self.text == other.text
+    }
+}
+
+impl Eq for OwnedStrSpan {}
+
+// Must be consistent with `PartialEq`.
+impl Hash for OwnedStrSpan {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+    }
+}
+
+impl fmt::Debug for OwnedStrSpan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OwnedStrSpan({:?} {}..{})", self.text, self.start, self.end())
+    }
+}
+
+impl fmt::Display for OwnedStrSpan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+
+// A value type of the owned tokens.
+trait FromSpan {
+    fn from_span(span: StrSpan) -> Self;
+}
+
+impl FromSpan for String {
+    fn from_span(span: StrSpan) -> Self {
+        span.to_str().to_string()
+    }
+}
+
+impl FromSpan for OwnedStrSpan {
+    fn from_span(span: StrSpan) -> Self {
+        OwnedStrSpan::from(span)
+    }
+}
+
+impl<'a> Token<'a> {
+    /// Converts the token into an owned one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, OwnedToken};
+    ///
+    /// let token = {
+    ///     let text = String::from("<svg:rect/>");
+    ///     let token = Tokenizer::from(text.as_str()).next().unwrap().unwrap();
+    ///     token.into_owned()
+    /// };
+    ///
+    /// assert_eq!(token, OwnedToken::ElementStart("svg".to_string(), "rect".to_string()));
+    /// ```
+    pub fn into_owned(self) -> OwnedToken {
+        token(self)
+    }
+
+    /// Converts the token into an owned one, preserving positions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, OwnedToken};
+    ///
+    /// let token = {
+    ///     let text = String::from("<svg:rect/>");
+    ///     let token = Tokenizer::from(text.as_str()).next().unwrap().unwrap();
+    ///     token.into_owned_spans()
+    /// };
+    ///
+    /// match token {
+    ///     OwnedToken::ElementStart(_, local) => {
+    ///         assert_eq!(local.as_str(), "rect");
+    ///         assert_eq!(local.start(), 5);
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn into_owned_spans(self) -> OwnedToken<OwnedStrSpan> {
+        token(self)
+    }
+}
+
+impl<'a> ElementEnd<'a> {
+    /// Converts the value into an owned one.
+    pub fn into_owned(self) -> OwnedElementEnd {
+        element_end(self)
+    }
+}
+
+impl<'a> ExternalId<'a> {
+    /// Converts the value into an owned one.
+    pub fn into_owned(self) -> OwnedExternalId {
+        external_id(self)
+    }
+}
+
+impl<'a> EntityDefinition<'a> {
+    /// Converts the value into an owned one.
+    pub fn into_owned(self) -> OwnedEntityDefinition {
+        entity_definition(self)
+    }
+}
+
+impl<'a> AttributeDefinition<'a> {
+    /// Converts the value into an owned one.
+    pub fn into_owned(self) -> OwnedAttributeDefinition {
+        attribute_definition(self)
+    }
+}
+
+fn token<S: FromSpan>(token: Token) -> OwnedToken<S> {
+    let s = S::from_span;
+    match token {
+        Token::Declaration(version, encoding, standalone) => {
+            OwnedToken::Declaration(s(version), encoding.map(s), standalone.map(s))
+        }
+        Token::ProcessingInstruction(target, content) => {
+            OwnedToken::ProcessingInstruction(s(target), content.map(s))
+        }
+        Token::Comment(text) => {
+            OwnedToken::Comment(s(text))
+        }
+        Token::DtdStart(name, id) => {
+            OwnedToken::DtdStart(s(name), id.map(external_id))
+        }
+        Token::EmptyDtd(name, id) => {
+            OwnedToken::EmptyDtd(s(name), id.map(external_id))
+        }
+        Token::EntityDeclaration(name, def) => {
+            OwnedToken::EntityDeclaration(s(name), entity_definition(def))
+        }
+        Token::ElementDeclaration(name, spec) => {
+            OwnedToken::ElementDeclaration(s(name), s(spec))
+        }
+        Token::AttlistDeclaration(element, defs) => {
+            OwnedToken::AttlistDeclaration(s(element), defs.map(attribute_definition).collect())
+        }
+        Token::NotationDeclaration(name, public_id, system_id) => {
+            OwnedToken::NotationDeclaration(s(name), public_id.map(s), system_id.map(s))
+        }
+        Token::DtdEnd => {
+            OwnedToken::DtdEnd
+        }
+        Token::ElementStart(prefix, local) => {
+            OwnedToken::ElementStart(s(prefix), s(local))
+        }
+        Token::Attribute((prefix, local), value, index) => {
+            OwnedToken::Attribute((s(prefix), s(local)), s(value), index)
+        }
+        Token::ElementEnd(end) => {
+            OwnedToken::ElementEnd(element_end(end))
+        }
+        Token::Text(text) => {
+            OwnedToken::Text(s(text))
+        }
+        Token::Whitespaces(text) => {
+            OwnedToken::Whitespaces(s(text))
+        }
+        Token::DocumentBoundary => {
+            OwnedToken::DocumentBoundary
+        }
+        Token::EndOfDocument(offset) => {
+            OwnedToken::EndOfDocument(offset)
+        }
+        Token::Cdata(text) => {
+            OwnedToken::Cdata(s(text))
+        }
+    }
+}
+
+fn element_end<S: FromSpan>(end: ElementEnd) -> OwnedElementEnd<S> {
+    match end {
+        ElementEnd::Open => OwnedElementEnd::Open,
+        ElementEnd::Close(prefix, local) => {
+            OwnedElementEnd::Close(S::from_span(prefix), S::from_span(local))
+        }
+        ElementEnd::Empty => OwnedElementEnd::Empty,
+    }
+}
+
+fn external_id<S: FromSpan>(id: ExternalId) -> OwnedExternalId<S> {
+    match id {
+        ExternalId::System(system) => OwnedExternalId::System(S::from_span(system)),
+        ExternalId::Public(public, system) => {
+            OwnedExternalId::Public(S::from_span(public), S::from_span(system))
+        }
+    }
+}
+
+fn entity_definition<S: FromSpan>(def: EntityDefinition) -> OwnedEntityDefinition<S> {
+    match def {
+        EntityDefinition::EntityValue(value) => {
+            OwnedEntityDefinition::EntityValue(S::from_span(value))
+        }
+        EntityDefinition::ExternalId(id) => {
+            OwnedEntityDefinition::ExternalId(external_id(id))
+        }
+    }
+}
+
+fn attribute_definition<S: FromSpan>(def: AttributeDefinition) -> OwnedAttributeDefinition<S> {
+    OwnedAttributeDefinition {
+        name: S::from_span(def.name),
+        att_type: S::from_span(def.att_type),
+        default: match def.default {
+            AttributeDefault::Required => OwnedAttributeDefault::Required,
+            AttributeDefault::Implied => OwnedAttributeDefault::Implied,
+            AttributeDefault::Value(value) => OwnedAttributeDefault::Value(S::from_span(value)),
+            AttributeDefault::Fixed(value) => OwnedAttributeDefault::Fixed(S::from_span(value)),
+        },
+    }
+}
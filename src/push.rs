@@ -0,0 +1,397 @@
+use core::mem;
+use core::str;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use xml::Checkpoint;
+use {
+    Error,
+    ErrorPos,
+    Limits,
+    Strictness,
+    Token,
+    Tokenizer,
+    XmlSpace,
+};
+
+
+// Bytes that can complete a token split across chunks, like the end of a text,
+// a tag, a quoted value or the DOCTYPE internal subset start.
+const DELIMITERS: &[u8] = b"<>'\"[";
+
+/// A push-based tokenizer.
+///
+/// Unlike `Tokenizer`, doesn't require the whole text upfront.
+/// The input is pushed via [`feed()`] in chunks of any size
+/// and tokens are returned via [`next_token()`] as soon as they are complete.
+///
+/// The already tokenized input is discarded on the next `feed()` call,
+/// so only the incomplete token is kept in memory.
+/// Since tokens borrow the internal buffer, their spans are relative
+/// to [`buffer_offset()`]. Error positions are relative to the document start.
+///
+/// Since an invalid token cannot be distinguished from a truncated one,
+/// parsing errors are returned only after [`end_of_input()`].
+/// Errors of the enabled checks are returned as soon as the token is complete.
+///
+/// An incomplete token is parsed again only when the appended data contains
+/// a markup delimiter, like `<`, `>` or a quote, or is at least as large as
+/// the incomplete token, so a large text is not rescanned on each `feed()`.
+///
+/// All the `Tokenizer` options are supported, except the lazy attributes parsing
+/// and the checks that have to keep the text: the strict mode, interning,
+/// attribute types, standalone documents and namespace declarations recording.
+///
+/// [`feed()`]: #method.feed
+/// [`next_token()`]: #method.next_token
+/// [`end_of_input()`]: #method.end_of_input
+/// [`buffer_offset()`]: #method.buffer_offset
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::{PushTokenizer, Token};
+///
+/// let mut p = PushTokenizer::new();
+/// p.feed(b"<svg wid");
+/// assert!(matches!(p.next_token(), Some(Ok(Token::ElementStart(_, _)))));
+/// assert!(p.next_token().is_none());
+/// assert!(!p.is_finished()); // needs more data
+///
+/// p.feed(b"th='10'/>");
+/// p.end_of_input();
+/// let offset = p.buffer_offset();
+/// match p.next_token() {
+///     Some(Ok(Token::Attribute(_, value, _))) => assert_eq!(offset + value.start(), 12),
+///     _ => unreachable!(),
+/// }
+/// assert!(matches!(p.next_token(), Some(Ok(Token::ElementEnd(_)))));
+/// assert!(p.next_token().is_none());
+/// assert!(p.is_finished());
+/// ```
+pub struct PushTokenizer {
+    text: String,
+    // An incomplete UTF-8 sequence at the end of the input.
+    pending: Vec<u8>,
+    checkpoint: Checkpoint,
+    // An absolute offset of the `text` start.
+    offset: usize,
+    // The `text` length at the last attempt to parse an incomplete token.
+    incomplete_len: Option<usize>,
+    // The `text` length up to which no delimiters were found.
+    checked_len: usize,
+    utf8_error: bool,
+    is_end_of_input: bool,
+    is_finished: bool,
+}
+
+impl PushTokenizer {
+    /// Creates a new tokenizer.
+    pub fn new() -> Self {
+        PushTokenizer {
+            text: String::new(),
+            pending: Vec::new(),
+            checkpoint: Checkpoint::default(),
+            offset: 0,
+            incomplete_len: None,
+            checked_len: 0,
+            utf8_error: false,
+            is_end_of_input: false,
+            is_finished: false,
+        }
+    }
+
+    /// Enables document fragment parsing.
+    ///
+    /// See [`Tokenizer::set_fragment_mode`].
+    ///
+    /// [`Tokenizer::set_fragment_mode`]: struct.Tokenizer.html#method.set_fragment_mode
+    pub fn set_fragment_mode(&mut self) {
+        self.configure(|p| p.set_fragment_mode());
+    }
+
+    /// Allows an XML declaration at the start of a fragment.
+    ///
+    /// See [`Tokenizer::enable_fragment_declaration`].
+    ///
+    /// [`Tokenizer::enable_fragment_declaration`]:
+    /// struct.Tokenizer.html#method.enable_fragment_declaration
+    pub fn enable_fragment_declaration(&mut self) {
+        self.configure(|p| p.enable_fragment_declaration());
+    }
+
+    /// Enables multiple documents parsing.
+    ///
+    /// See [`Tokenizer::set_multi_document_mode`].
+    ///
+    /// [`Tokenizer::set_multi_document_mode`]: struct.Tokenizer.html#method.set_multi_document_mode
+    pub fn set_multi_document_mode(&mut self) {
+        self.configure(|p| p.set_multi_document_mode());
+    }
+
+    /// Enables namespace well-formedness checks.
+    ///
+    /// See [`Tokenizer::set_namespace_well_formed_mode`].
+    ///
+    /// [`Tokenizer::set_namespace_well_formed_mode`]:
+    /// struct.Tokenizer.html#method.set_namespace_well_formed_mode
+    pub fn set_namespace_well_formed_mode(&mut self) {
+        self.configure(|p| p.set_namespace_well_formed_mode());
+    }
+
+    /// Enables the `EndOfDocument` token.
+    ///
+    /// Its offset is relative to [`buffer_offset()`], like the token spans.
+    ///
+    /// See [`Tokenizer::enable_end_of_document_token`].
+    ///
+    /// [`buffer_offset()`]: #method.buffer_offset
+    /// [`Tokenizer::enable_end_of_document_token`]:
+    /// struct.Tokenizer.html#method.enable_end_of_document_token
+    pub fn enable_end_of_document_token(&mut self) {
+        self.configure(|p| p.enable_end_of_document_token());
+    }
+
+    /// Enables DTD declaration tokens.
+    ///
+    /// See [`Tokenizer::enable_dtd_declaration_tokens`].
+    ///
+    /// [`Tokenizer::enable_dtd_declaration_tokens`]:
+    /// struct.Tokenizer.html#method.enable_dtd_declaration_tokens
+    pub fn enable_dtd_declaration_tokens(&mut self) {
+        self.configure(|p| p.enable_dtd_declaration_tokens());
+    }
+
+    /// Enables the error recovery.
+    ///
+    /// See [`Tokenizer::enable_error_recovery`].
+    ///
+    /// [`Tokenizer::enable_error_recovery`]: struct.Tokenizer.html#method.enable_error_recovery
+    pub fn enable_error_recovery(&mut self) {
+        self.configure(|p| p.enable_error_recovery());
+    }
+
+    /// Skips `Whitespaces` tokens.
+    ///
+    /// See [`Tokenizer::skip_whitespace_text`].
+    ///
+    /// [`Tokenizer::skip_whitespace_text`]: struct.Tokenizer.html#method.skip_whitespace_text
+    pub fn skip_whitespace_text(&mut self) {
+        self.configure(|p| p.skip_whitespace_text());
+    }
+
+    /// Sets well-formedness relaxations.
+    ///
+    /// See [`Tokenizer::set_strictness`].
+    ///
+    /// [`Tokenizer::set_strictness`]: struct.Tokenizer.html#method.set_strictness
+    pub fn set_strictness(&mut self, strictness: Strictness) {
+        self.configure(|p| p.set_strictness(strictness));
+    }
+
+    /// Sets tokenizer limits.
+    ///
+    /// See [`Tokenizer::set_limits`].
+    ///
+    /// [`Tokenizer::set_limits`]: struct.Tokenizer.html#method.set_limits
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.configure(|p| p.set_limits(limits));
+    }
+
+    /// Enables IDs uniqueness checks.
+    ///
+    /// See [`Tokenizer::enable_id_checking`].
+    ///
+    /// [`Tokenizer::enable_id_checking`]: struct.Tokenizer.html#method.enable_id_checking
+    pub fn enable_id_checking(&mut self) {
+        self.configure(|p| p.enable_id_checking());
+    }
+
+    /// Enables `xml:space` scopes tracking.
+    ///
+    /// Required by [`xml_space()`].
+    ///
+    /// [`xml_space()`]: #method.xml_space
+    pub fn enable_xml_space_tracking(&mut self) {
+        self.configure(|p| p.enable_xml_space_tracking());
+    }
+
+    /// Returns the `xml:space` value of the current element.
+    ///
+    /// See [`Tokenizer::xml_space`].
+    ///
+    /// [`Tokenizer::xml_space`]: struct.Tokenizer.html#method.xml_space
+    pub fn xml_space(&self) -> XmlSpace {
+        self.checkpoint.xml_space()
+    }
+
+    /// Enables incremental position tracking.
+    ///
+    /// Makes [`current_pos()`] cheap.
+    ///
+    /// [`current_pos()`]: #method.current_pos
+    pub fn enable_position_tracking(&mut self) {
+        self.configure(|p| p.enable_position_tracking());
+    }
+
+    /// Returns a position after the last returned token.
+    ///
+    /// See [`Tokenizer::current_pos`].
+    ///
+    /// [`Tokenizer::current_pos`]: struct.Tokenizer.html#method.current_pos
+    pub fn current_pos(&self) -> ErrorPos {
+        self.checkpoint.current_pos(&self.text)
+    }
+
+    /// Appends a chunk of the input.
+    ///
+    /// A chunk can end anywhere, even in the middle of a UTF-8 sequence.
+    /// Data after an invalid UTF-8 sequence is ignored.
+    ///
+    /// The input of the already returned tokens is discarded.
+    pub fn feed(&mut self, chunk: &[u8]) {
+        if self.utf8_error || self.is_end_of_input || self.is_finished {
+            return;
+        }
+
+        self.discard_consumed();
+        self.pending.extend_from_slice(chunk);
+
+        let valid_len = match str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            Err(e) => {
+                if e.error_len().is_some() {
+                    self.utf8_error = true;
+                }
+
+                e.valid_up_to()
+            }
+        };
+
+        // Already validated.
+        self.text.push_str(str::from_utf8(&self.pending[..valid_len]).unwrap());
+        self.pending.drain(..valid_len);
+    }
+
+    /// Marks the end of the input.
+    ///
+    /// The rest of the tokens and a truncation error, if any,
+    /// will be returned by the following `next_token()` calls.
+    pub fn end_of_input(&mut self) {
+        self.is_end_of_input = true;
+        if !self.pending.is_empty() {
+            self.utf8_error = true;
+        }
+    }
+
+    /// Returns the next complete token.
+    ///
+    /// Returns `None` when more data is needed or when parsing is finished.
+    /// Use [`is_finished()`] to distinguish them.
+    ///
+    /// [`is_finished()`]: #method.is_finished
+    pub fn next_token(&mut self) -> Option<Result<Token<'_>, Error>> {
+        if self.is_finished {
+            return None;
+        }
+
+        if !self.is_end_of_input && !self.utf8_error && !self.may_complete() {
+            return None;
+        }
+
+        let text = self.text.as_str();
+        let mut p = Tokenizer::from_checkpoint(text, mem::take(&mut self.checkpoint));
+        p.set_partial_input(!self.is_end_of_input && !self.utf8_error);
+        let res = p.next();
+
+        // The state is unchanged when the token is incomplete.
+        let is_incomplete = res.is_none() && !p.is_finished();
+        self.is_finished = p.is_finished();
+        self.checkpoint = p.into_checkpoint();
+
+        if self.utf8_error {
+            // No more data will be appended, so an incomplete token
+            // is truncated by the invalid sequence.
+            match res {
+                Some(Ok(_)) => {}
+                _ => {
+                    self.is_finished = true;
+                    let pos = self.checkpoint.pos_at(text, text.len());
+                    return Some(Err(Error::InvalidUtf8(pos)));
+                }
+            }
+        } else if is_incomplete {
+            self.incomplete_len = Some(text.len());
+            self.checked_len = text.len();
+            return None;
+        }
+
+        self.incomplete_len = None;
+        res
+    }
+
+    /// Checks that all tokens were returned.
+    pub fn is_finished(&self) -> bool {
+        self.is_finished
+    }
+
+    /// Returns an absolute offset of the internal buffer start.
+    ///
+    /// Should be added to the token spans offsets to get offsets in the document.
+    pub fn buffer_offset(&self) -> usize {
+        self.offset
+    }
+
+    // Applies tokenizer options to the current state.
+    fn configure<F: FnOnce(&mut Tokenizer)>(&mut self, f: F) {
+        let mut p = Tokenizer::from_checkpoint(&self.text, mem::take(&mut self.checkpoint));
+        f(&mut p);
+        self.checkpoint = p.into_checkpoint();
+    }
+
+    // Checks that the data appended after the last attempt
+    // to parse an incomplete token can complete it.
+    fn may_complete(&mut self) -> bool {
+        let incomplete_len = match self.incomplete_len {
+            Some(len) => len,
+            None => return true,
+        };
+
+        // Parsing the token again is not more expensive than appending the data.
+        let token_len = incomplete_len - self.checkpoint.retain_from();
+        if self.text.len() - incomplete_len >= token_len {
+            return true;
+        }
+
+        let bytes = &self.text.as_bytes()[self.checked_len..];
+        self.checked_len = self.text.len();
+        bytes.iter().any(|c| DELIMITERS.contains(c))
+    }
+
+    fn discard_consumed(&mut self) {
+        let mut n = self.checkpoint.retain_from();
+
+        // Keep CR, so CRLF will still be treated as a single line break.
+        if n > 0 && self.text.as_bytes()[n - 1] == b'\r' {
+            n -= 1;
+        }
+
+        if n == 0 {
+            return;
+        }
+
+        self.checkpoint.shift_back(&self.text, n);
+        self.text.drain(..n);
+        self.offset += n;
+        if let Some(ref mut len) = self.incomplete_len {
+            *len -= n;
+            self.checked_len -= n;
+        }
+    }
+}
+
+impl Default for PushTokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,146 @@
+use {
+    ElementEnd,
+    Error,
+    StrSpan,
+    Token,
+    Tokenizer,
+};
+
+
+/// A handler return value.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Control {
+    /// Continue parsing.
+    Continue,
+    /// Stop parsing.
+    Stop,
+}
+
+
+/// A push-style tokens handler.
+///
+/// Used by [`parse_with`]. All methods do nothing by default.
+///
+/// [`parse_with`]: fn.parse_with.html
+pub trait Handler<'a> {
+    /// Called on `ElementStart`.
+    fn start_element(&mut self, prefix: StrSpan<'a>, local: StrSpan<'a>) -> Control {
+        let _ = (prefix, local);
+        Control::Continue
+    }
+
+    /// Called on `Attribute`.
+    fn attribute(&mut self, prefix: StrSpan<'a>, local: StrSpan<'a>, value: StrSpan<'a>) -> Control {
+        let _ = (prefix, local, value);
+        Control::Continue
+    }
+
+    /// Called on `ElementEnd`, i.e. on `>`, `/>` and `</name>`.
+    fn end_element(&mut self, end: ElementEnd<'a>) -> Control {
+        let _ = end;
+        Control::Continue
+    }
+
+    /// Called on `Text` and `Whitespaces`.
+    fn characters(&mut self, text: StrSpan<'a>) -> Control {
+        let _ = text;
+        Control::Continue
+    }
+
+    /// Called on `Cdata`.
+    fn cdata(&mut self, text: StrSpan<'a>) -> Control {
+        let _ = text;
+        Control::Continue
+    }
+
+    /// Called on `Comment`.
+    fn comment(&mut self, text: StrSpan<'a>) -> Control {
+        let _ = text;
+        Control::Continue
+    }
+
+    /// Called on `ProcessingInstruction`.
+    fn pi(&mut self, target: StrSpan<'a>, content: Option<StrSpan<'a>>) -> Control {
+        let _ = (target, content);
+        Control::Continue
+    }
+
+    /// Called on all other tokens, like `Declaration` and DTD ones.
+    fn other(&mut self, token: Token<'a>) -> Control {
+        let _ = token;
+        Control::Continue
+    }
+
+    /// Called on an error.
+    ///
+    /// The parsing is stopped after an error.
+    fn error(&mut self, error: Error) {
+        let _ = error;
+    }
+}
+
+
+/// Parses the text and passes tokens to the handler.
+///
+/// A wrapper around `Tokenizer`.
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::{parse_with, Handler, Control, StrSpan};
+///
+/// struct Names(Vec<String>);
+///
+/// impl<'a> Handler<'a> for Names {
+///     fn start_element(&mut self, _: StrSpan<'a>, local: StrSpan<'a>) -> Control {
+///         self.0.push(local.to_str().to_string());
+///         Control::Continue
+///     }
+/// }
+///
+/// let mut names = Names(Vec::new());
+/// parse_with("<a><b/><c/></a>", &mut names);
+/// assert_eq!(names.0, vec!["a", "b", "c"]);
+/// ```
+pub fn parse_with<'a>(text: &'a str, handler: &mut dyn Handler<'a>) {
+    for token in Tokenizer::from(text) {
+        let token = match token {
+            Ok(token) => token,
+            Err(e) => {
+                handler.error(e);
+                return;
+            }
+        };
+
+        let control = match token {
+            Token::ElementStart(prefix, local) => {
+                handler.start_element(prefix, local)
+            }
+            Token::Attribute((prefix, local), value, _) => {
+                handler.attribute(prefix, local, value)
+            }
+            Token::ElementEnd(end) => {
+                handler.end_element(end)
+            }
+            Token::Text(text) | Token::Whitespaces(text) => {
+                handler.characters(text)
+            }
+            Token::Cdata(text) => {
+                handler.cdata(text)
+            }
+            Token::Comment(text) => {
+                handler.comment(text)
+            }
+            Token::ProcessingInstruction(target, content) => {
+                handler.pi(target, content)
+            }
+            _ => {
+                handler.other(token)
+            }
+        };
+
+        if control == Control::Stop {
+            return;
+        }
+    }
+}
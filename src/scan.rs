@@ -0,0 +1,129 @@
+// Byte searching used by the `Stream` scanning methods.
+//
+// With the `fast-scan` feature, the input is checked eight bytes at a time
+// using the SWAR technique, which doesn't require `unsafe` or dependencies.
+
+/// Returns an index of the first `needle` in the `haystack`.
+#[cfg(feature = "fast-scan")]
+#[inline]
+pub fn find_byte(needle: u8, haystack: &[u8]) -> Option<usize> {
+    let pattern = repeat(needle);
+    let mut chunks = haystack.chunks_exact(WORD_SIZE);
+    let mut offset = 0;
+    for chunk in &mut chunks {
+        let word = read_word(chunk);
+        let found = zero_bytes(word ^ pattern);
+        if found != 0 {
+            return Some(offset + first_byte(found));
+        }
+
+        offset += WORD_SIZE;
+    }
+
+    chunks.remainder().iter().position(|c| *c == needle).map(|i| offset + i)
+}
+
+/// Returns an index of the first `needle` in the `haystack`.
+#[cfg(not(feature = "fast-scan"))]
+#[inline]
+pub fn find_byte(needle: u8, haystack: &[u8]) -> Option<usize> {
+    haystack.iter().position(|c| *c == needle)
+}
+
+/// Returns an index of the first `needle1` or `needle2` in the `haystack`.
+#[cfg(feature = "fast-scan")]
+#[inline]
+pub fn find_byte2(needle1: u8, needle2: u8, haystack: &[u8]) -> Option<usize> {
+    let pattern1 = repeat(needle1);
+    let pattern2 = repeat(needle2);
+    let mut chunks = haystack.chunks_exact(WORD_SIZE);
+    let mut offset = 0;
+    for chunk in &mut chunks {
+        let word = read_word(chunk);
+        let found = zero_bytes(word ^ pattern1) | zero_bytes(word ^ pattern2);
+        if found != 0 {
+            return Some(offset + first_byte(found));
+        }
+
+        offset += WORD_SIZE;
+    }
+
+    chunks.remainder().iter()
+        .position(|c| *c == needle1 || *c == needle2)
+        .map(|i| offset + i)
+}
+
+/// Returns an index of the first `needle1` or `needle2` in the `haystack`.
+#[cfg(not(feature = "fast-scan"))]
+#[inline]
+pub fn find_byte2(needle1: u8, needle2: u8, haystack: &[u8]) -> Option<usize> {
+    haystack.iter().position(|c| *c == needle1 || *c == needle2)
+}
+
+#[cfg(feature = "fast-scan")]
+const WORD_SIZE: usize = 8;
+
+#[cfg(feature = "fast-scan")]
+const LO: u64 = 0x0101_0101_0101_0101;
+
+#[cfg(feature = "fast-scan")]
+const HI: u64 = 0x8080_8080_8080_8080;
+
+#[cfg(feature = "fast-scan")]
+#[inline]
+fn repeat(c: u8) -> u64 {
+    LO * u64::from(c)
+}
+
+#[cfg(feature = "fast-scan")]
+#[inline]
+fn read_word(chunk: &[u8]) -> u64 {
+    let mut bytes = [0; WORD_SIZE];
+    bytes.copy_from_slice(chunk);
+    u64::from_le_bytes(bytes)
+}
+
+// Sets the high bit of each zero byte.
+//
+// Bytes after the first zero byte can be marked falsely,
+// but the lowest marked byte is always correct.
+#[cfg(feature = "fast-scan")]
+#[inline]
+fn zero_bytes(word: u64) -> u64 {
+    word.wrapping_sub(LO) & !word & HI
+}
+
+#[cfg(feature = "fast-scan")]
+#[inline]
+fn first_byte(mask: u64) -> usize {
+    (mask.trailing_zeros() / 8) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_byte_1() {
+        let text = b"0123456789abcdef<ghi";
+        for i in 0..text.len() {
+            assert_eq!(find_byte(text[i], text), Some(i));
+            assert_eq!(find_byte(text[i], &text[..i]), None);
+        }
+    }
+
+    #[test]
+    fn find_byte_2() {
+        // Bytes with the high bit set next to a match.
+        assert_eq!(find_byte(b'<', "\u{1000}\u{100}<".as_bytes()), Some(5));
+        assert_eq!(find_byte(0x80, &[0x81, 0x00, 0x01, 0x7F, 0xFF, 0x80]), Some(5));
+        assert_eq!(find_byte(0, &[1, 1, 1, 1, 1, 1, 1, 1, 0]), Some(8));
+    }
+
+    #[test]
+    fn find_byte2_1() {
+        assert_eq!(find_byte2(b'<', b'&', b"text text &amp; <"), Some(10));
+        assert_eq!(find_byte2(b'<', b'&', b"text text <"), Some(10));
+        assert_eq!(find_byte2(b'<', b'&', b"text text text"), None);
+    }
+}
@@ -1,16 +1,20 @@
-use std::char;
-use std::str;
-use std::cmp;
+use core::char;
+use core::str;
+use core::cmp;
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
 
+use scan;
 use {
     ErrorPos,
+    Expectation,
     StreamError,
     StrSpan,
     XmlByteExt,
     XmlCharExt,
 };
 
-type Result<T> = ::std::result::Result<T, StreamError>;
+type Result<T> = ::core::result::Result<T, StreamError>;
 
 
 /// Representation of the [Reference](https://www.w3.org/TR/xml/#NT-Reference) value.
@@ -28,12 +32,62 @@ pub enum Reference<'a> {
 
 
 /// A streaming text parsing interface.
+///
+/// A cursor over a text, used by the `Tokenizer` internally.
+/// Can be used to write parsers for other grammars, like attribute values
+/// of XML-based formats, with the same error types and positions.
+///
+/// Methods that consume the text either advance the position on success
+/// or return an error. The position after an error is unspecified,
+/// so save it with [`pos()`] and restore with [`set_pos()`] to backtrack.
+///
+/// When created from a `StrSpan`, errors positions are relative to the
+/// original text of the span.
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::{Stream, StreamError};
+///
+/// // Parses a `name=value; name=value` list.
+/// fn parse(text: &str) -> Result<Vec<(&str, &str)>, StreamError> {
+///     let mut s = Stream::from(text);
+///     let mut list = Vec::new();
+///     s.skip_ascii_spaces();
+///     while !s.at_end() {
+///         let name = s.consume_name()?;
+///         s.skip_ascii_spaces();
+///         s.consume_byte(b'=')?;
+///         s.skip_ascii_spaces();
+///         let value = s.consume_bytes(|_, c| c != b';');
+///         list.push((name.to_str(), value.trim().to_str()));
+///
+///         if !s.at_end() {
+///             s.consume_byte(b';')?;
+///         }
+///         s.skip_ascii_spaces();
+///     }
+///
+///     Ok(list)
+/// }
+///
+/// assert_eq!(parse("fill=red; stroke = none").unwrap(),
+///            vec![("fill", "red"), ("stroke", "none")]);
+/// assert_eq!(parse("fill red").unwrap_err().to_string(),
+///            "expected '=' but found 'r' at 1:6");
+/// ```
+///
+/// [`pos()`]: #method.pos
+/// [`set_pos()`]: #method.set_pos
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub struct Stream<'a> {
     bytes: &'a [u8],
     pos: usize,
     end: usize,
     span: StrSpan<'a>,
+    is_xml_1_1: bool,
+    // A position of the text start.
+    origin: ErrorPos,
 }
 
 impl<'a> From<&'a str> for Stream<'a> {
@@ -43,6 +97,8 @@ impl<'a> From<&'a str> for Stream<'a> {
             pos: 0,
             end: text.len(),
             span: text.into(),
+            is_xml_1_1: false,
+            origin: ErrorPos::new(1, 1),
         }
     }
 }
@@ -54,6 +110,8 @@ impl<'a> From<StrSpan<'a>> for Stream<'a> {
             pos: 0,
             end: span.len(),
             span,
+            is_xml_1_1: false,
+            origin: ErrorPos::new(1, 1),
         }
     }
 }
@@ -69,6 +127,51 @@ impl<'a> Stream<'a> {
         self.pos
     }
 
+    /// Sets current position.
+    ///
+    /// The position must be on a char boundary.
+    ///
+    /// Setting the position past the end is a bug and will panic in debug builds.
+    /// In release builds, the position is clamped to the end instead.
+    pub fn set_pos(&mut self, pos: usize) {
+        debug_assert!(pos <= self.end);
+        self.pos = cmp::min(pos, self.end);
+    }
+
+    // Allows character references to the C0 control characters
+    // and enables XML 1.1 line breaks in positions.
+    pub(crate) fn set_xml_1_1(&mut self, flag: bool) {
+        self.is_xml_1_1 = flag;
+    }
+
+    pub(crate) fn is_xml_1_1(&self) -> bool {
+        self.is_xml_1_1
+    }
+
+    // Sets a position of the text start, when the text is a part of a bigger document.
+    pub(crate) fn set_origin(&mut self, pos: ErrorPos) {
+        self.origin = pos;
+    }
+
+    pub(crate) fn origin(&self) -> ErrorPos {
+        self.origin
+    }
+
+    // Creates a stream of a `span` of the same text with the same settings.
+    pub(crate) fn sub_stream(&self, span: StrSpan<'a>) -> Stream<'a> {
+        let mut s = Stream::from(span);
+        s.is_xml_1_1 = self.is_xml_1_1;
+        s.origin = self.origin;
+        s
+    }
+
+    // Creates a position tracker at the text start.
+    pub(crate) fn line_tracker(&self) -> LineTracker {
+        let mut tracker = LineTracker::with_origin(self.origin);
+        tracker.is_xml_1_1 = self.is_xml_1_1;
+        tracker
+    }
+
     /// Sets current position equal to the end.
     ///
     /// Used to indicate end of parsing on error.
@@ -155,16 +258,29 @@ impl<'a> Stream<'a> {
             return Err(StreamError::UnexpectedEndOfStream);
         }
 
-        Ok(self.curr_char_unchecked())
+        match self.tail_str().chars().next() {
+            Some(c) => Ok(c),
+            None => {
+                let c = self.curr_byte_unchecked();
+                Err(StreamError::InvalidChar(c, Box::new([]), self.gen_error_pos()))
+            }
+        }
     }
 
+    // Returns the unparsed text.
+    //
+    // Returns an empty string when the position is not on a char boundary,
+    // which is possible only after an invalid `advance()`.
     #[inline]
-    fn curr_char_unchecked(&self) -> char {
-        self.span.to_str()[self.pos..].chars().next().unwrap()
+    fn tail_str(&self) -> &'a str {
+        self.span.to_str().get(self.pos..self.end).unwrap_or("")
     }
 
     /// Advances by `n` bytes.
     ///
+    /// Advancing past the end is a bug and will panic in debug builds.
+    /// In release builds, the position is clamped to the end instead.
+    ///
     /// # Examples
     ///
     /// ```rust,should_panic
@@ -177,13 +293,19 @@ impl<'a> Stream<'a> {
     #[inline]
     pub fn advance(&mut self, n: usize) {
         debug_assert!(self.pos + n <= self.end);
-        self.pos += n;
+        self.pos = cmp::min(self.pos + n, self.end);
     }
 
     /// Skips whitespaces.
     ///
     /// Accepted values: `' ' \n \r \t &#x20; &#x9; &#xD; &#xA;`.
     ///
+    /// Character references are accepted in places where `xmlparser` treats them
+    /// as spaces: between top-level tokens, inside the DTD and in the `Whitespaces` token.
+    /// Inside a markup, like between attributes, use [`skip_ascii_spaces()`] instead.
+    ///
+    /// [`skip_ascii_spaces()`]: #method.skip_ascii_spaces
+    ///
     /// # Examples
     ///
     /// ```
@@ -200,19 +322,14 @@ impl<'a> Stream<'a> {
             if c.is_xml_space() {
                 self.advance(1);
             } else if c == b'&' {
-                // Check for (#x20 | #x9 | #xD | #xA).
                 let start = self.pos();
-                let mut is_space = false;
-                if let Ok(Reference::CharRef(ch)) = self.consume_reference() {
-                    if (ch as u32) < 255 && (ch as u8).is_xml_space() {
-                        is_space = true;
+                match self.try_consume_char_reference() {
+                    Some(ch) if is_space_char_ref(ch) => {}
+                    _ => {
+                        self.pos = start;
+                        break;
                     }
                 }
-
-                if !is_space {
-                    self.pos = start;
-                    break;
-                }
             } else {
                 break;
             }
@@ -222,6 +339,11 @@ impl<'a> Stream<'a> {
     /// Skips ASCII whitespaces.
     ///
     /// Accepted values: `' ' \n \r \t`.
+    ///
+    /// Unlike [`skip_spaces()`], character references are not treated as spaces,
+    /// since they are not allowed inside a markup.
+    ///
+    /// [`skip_spaces()`]: #method.skip_spaces
     pub fn skip_ascii_spaces(&mut self) {
         while !self.at_end() {
             if self.curr_byte_unchecked().is_xml_space() {
@@ -253,40 +375,37 @@ impl<'a> Stream<'a> {
 
     /// Checks if the stream is starts with a space.
     ///
-    /// Uses [`skip_spaces()`](#method.curr_byte) internally.
+    /// Accepts the same values as [`skip_spaces()`](#method.skip_spaces).
     pub fn starts_with_space(&self) -> bool {
         if self.at_end() {
             return false;
         }
 
-        let mut is_space = false;
-
         let c = self.curr_byte_unchecked();
 
         if c.is_xml_space() {
-            is_space = true;
+            true
         } else if c == b'&' {
-            // Check for (#x20 | #x9 | #xD | #xA).
             let mut s = *self;
-            if let Some(v) = s.try_consume_char_reference() {
-                if (v as u32) < 255 && (v as u8).is_xml_space() {
-                    is_space = true;
-                }
+            match s.try_consume_char_reference() {
+                Some(ch) => is_space_char_ref(ch),
+                None => false,
             }
+        } else {
+            false
         }
-
-        is_space
     }
 
     /// Consumes whitespaces.
     ///
     /// Like [`skip_spaces()`], but checks that first char is actually a space.
+    /// So the error is returned only for values that `skip_spaces()` will not skip.
     ///
     /// [`skip_spaces()`]: #method.skip_spaces
     ///
     /// # Errors
     ///
-    /// - `InvalidChar`
+    /// - `InvalidSpace`
     pub fn consume_spaces(&mut self) -> Result<()> {
         if !self.at_end() && !self.starts_with_space() {
             let c = self.curr_byte_unchecked() as char;
@@ -320,7 +439,8 @@ impl<'a> Stream<'a> {
         if self.curr_byte()? != c {
             return Err(
                 StreamError::InvalidChar(
-                    vec![self.curr_byte_unchecked(), c],
+                    self.curr_byte_unchecked(),
+                    Box::new([Expectation::Char(c as char)]),
                     self.gen_error_pos(),
                 )
             );
@@ -343,9 +463,7 @@ impl<'a> Stream<'a> {
 
         let c = self.curr_byte()?;
         if !list.contains(&c) {
-            let mut v = list.to_vec();
-            v.insert(0, c);
-            return Err(StreamError::InvalidChar(v, self.gen_error_pos()));
+            return Err(StreamError::InvalidChar(c, Expectation::chars(list), self.gen_error_pos()));
         }
 
         self.advance(1);
@@ -363,14 +481,14 @@ impl<'a> Stream<'a> {
             // Collect chars and do not slice a string,
             // because the `len` can be on the char boundary.
             // Which lead to a panic.
-            let actual = self.span.to_str()[self.pos..].chars().take(len).collect();
+            let actual = self.tail_str().chars().take(len).collect();
 
             // Assume that all input `text` are valid UTF-8 strings, so unwrap is safe.
             let expected = str::from_utf8(text).unwrap().to_owned();
 
             let pos = self.gen_error_pos();
 
-            return Err(StreamError::InvalidString(vec![actual, expected], pos));
+            return Err(StreamError::InvalidString(actual, Box::new([Expectation::String(expected)]), pos));
         }
 
         self.advance(text.len());
@@ -389,12 +507,11 @@ impl<'a> Stream<'a> {
         let start = self.pos();
         self.skip_name()?;
 
-        let name = self.slice_back(start);
-        if name.is_empty() {
+        if self.pos() == start {
             return Err(StreamError::InvalidName);
         }
 
-        Ok(name)
+        Ok(self.slice_back(start))
     }
 
     /// Skips an XML name.
@@ -406,7 +523,7 @@ impl<'a> Stream<'a> {
     /// - `InvalidNameToken` - if name is empty or starts with an invalid char
     /// - `UnexpectedEndOfStream`
     pub fn skip_name(&mut self) -> Result<()> {
-        let mut iter = self.span.to_str()[self.pos..self.end].chars();
+        let mut iter = self.tail_str().chars();
         if let Some(c) = iter.next() {
             if c.is_xml_name_start() {
                 self.advance(c.len_utf8());
@@ -430,6 +547,9 @@ impl<'a> Stream<'a> {
     ///
     /// Consumes according to: <https://www.w3.org/TR/xml-names/#ns-qualnames>
     ///
+    /// The name itself is consumed according to the `Name` rule, so it can contain
+    /// multiple colons. In this case, the name will be split at the last one.
+    ///
     /// # Errors
     ///
     /// - `InvalidNameToken` - if name is empty or starts with an invalid char
@@ -438,8 +558,12 @@ impl<'a> Stream<'a> {
         let start = self.pos();
 
         let mut splitter = None;
-        let iter = self.span.to_str()[self.pos..self.end].chars();
+        let iter = self.tail_str().chars();
         for c in iter {
+            if self.pos() == start && !c.is_xml_name_start() {
+                break;
+            }
+
             if c == ':' {
                 splitter = Some(self.pos());
                 self.advance(1);
@@ -461,7 +585,8 @@ impl<'a> Stream<'a> {
             (prefix, local)
         } else {
             let local = self.slice_back(start);
-            ("".into(), local)
+            // An empty prefix still points to the name start.
+            (self.span.slice_region(start, start), local)
         };
 
         if local.is_empty() {
@@ -504,6 +629,31 @@ impl<'a> Stream<'a> {
         }
     }
 
+    /// Consumes a quoted value and returns it without quotes.
+    ///
+    /// The value can be quoted by `'` or `"` and can't contain the same quote.
+    ///
+    /// # Errors
+    ///
+    /// - `InvalidQuote`
+    /// - `UnexpectedEndOfStream`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::Stream;
+    ///
+    /// let mut s = Stream::from("'a \"b\"' tail");
+    /// assert_eq!(s.consume_quoted().unwrap().to_str(), "a \"b\"");
+    /// assert_eq!(s.pos(), 7);
+    /// ```
+    pub fn consume_quoted(&mut self) -> Result<StrSpan<'a>> {
+        let quote = self.consume_quote()?;
+        let value = self.consume_until(quote);
+        self.consume_byte(quote)?;
+        Ok(value)
+    }
+
     /// Consumes bytes by the predicate and returns them.
     ///
     /// The result can be empty.
@@ -529,6 +679,45 @@ impl<'a> Stream<'a> {
         }
     }
 
+    /// Consumes bytes until `c` and returns them.
+    ///
+    /// Stops at the end when there is no `c`. The result can be empty.
+    ///
+    /// Same as `consume_bytes(|_, b| b != c)`, but faster on long texts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::Stream;
+    ///
+    /// let mut s = Stream::from("some text<a/>");
+    /// assert_eq!(s.consume_until(b'<').to_str(), "some text");
+    /// assert_eq!(s.pos(), 9);
+    /// ```
+    pub fn consume_until(&mut self, c: u8) -> StrSpan<'a> {
+        let start = self.pos();
+        self.skip_until(c);
+        self.slice_back(start)
+    }
+
+    /// Skips bytes until `c`.
+    ///
+    /// Stops at the end when there is no `c`.
+    pub fn skip_until(&mut self, c: u8) {
+        self.pos = match scan::find_byte(c, &self.bytes[self.pos..self.end]) {
+            Some(i) => self.pos + i,
+            None => self.end,
+        };
+    }
+
+    // Skips bytes until `c1` or `c2`.
+    pub(crate) fn skip_until_either(&mut self, c1: u8, c2: u8) {
+        self.pos = match scan::find_byte2(c1, c2, &self.bytes[self.pos..self.end]) {
+            Some(i) => self.pos + i,
+            None => self.end,
+        };
+    }
+
     /// Consumes chars by the predicate and returns them.
     ///
     /// The result can be empty.
@@ -544,7 +733,7 @@ impl<'a> Stream<'a> {
     pub fn skip_chars<F>(&mut self, f: F)
         where F: Fn(&Stream, char) -> bool
     {
-        let t = &self.span.to_str()[self.pos..self.end];
+        let t = self.tail_str();
         for c in t.chars() {
             if f(self, c) {
                 self.advance(c.len_utf8());
@@ -560,7 +749,7 @@ impl<'a> Stream<'a> {
     pub fn try_consume_char_reference(&mut self) -> Option<char> {
         let start = self.pos();
 
-        if let Ok(Reference::CharRef(ch)) = self.consume_reference() {
+        if let Some(Reference::CharRef(ch)) = self._consume_reference() {
             Some(ch)
         } else {
             self.pos = start;
@@ -577,34 +766,42 @@ impl<'a> Stream<'a> {
     /// - `InvalidReference`
     /// - `UnexpectedEndOfStream`
     pub fn consume_reference(&mut self) -> Result<Reference<'a>> {
-        self._consume_reference().map_err(|_| StreamError::InvalidReference)
+        let start = self.pos();
+        match self._consume_reference() {
+            Some(reference) => Ok(reference),
+            None => Err(StreamError::InvalidReference(self.gen_error_pos_from(start))),
+        }
     }
 
-    fn _consume_reference(&mut self) -> Result<Reference<'a>> {
-        if self.curr_byte()? != b'&' {
-            return Err(StreamError::InvalidReference);
+    fn _consume_reference(&mut self) -> Option<Reference<'a>> {
+        if self.curr_byte().ok()? != b'&' {
+            return None;
         }
 
         self.advance(1);
-        let reference = if self.curr_byte()? == b'#' {
+        let reference = if self.curr_byte().ok()? == b'#' {
             self.advance(1);
-            let n = if self.curr_byte()? == b'x' {
+            // Parsing fails on empty values and overflows.
+            let n = if self.curr_byte().ok()? == b'x' {
                 self.advance(1);
                 let value = self.consume_bytes(|_, c| c.is_xml_hex_digit()).to_str();
-                u32::from_str_radix(value, 16).map_err(|_| StreamError::InvalidReference)
+                u32::from_str_radix(value, 16).ok()?
             } else {
                 let value = self.consume_bytes(|_, c| c.is_xml_digit()).to_str();
-                u32::from_str_radix(value, 10).map_err(|_| StreamError::InvalidReference)
-            }?;
-
-            let c = char::from_u32(n).unwrap_or('\u{FFFD}');
-            if !c.is_xml_char() {
-                return Err(StreamError::InvalidReference);
+                value.parse::<u32>().ok()?
+            };
+
+            // Surrogates and out of range values are not chars.
+            let c = char::from_u32(n)?;
+            // XML 1.1 allows references to control characters, except NUL.
+            let is_restricted = self.is_xml_1_1 && c != '\0' && (c as u32) < 0x20;
+            if !c.is_xml_char() && !is_restricted {
+                return None;
             }
 
             Reference::CharRef(c)
         } else {
-            let name = self.consume_name()?;
+            let name = self.consume_name().ok()?;
             match name.to_str() {
                 "quot" => Reference::CharRef('"'),
                 "amp"  => Reference::CharRef('&'),
@@ -615,9 +812,9 @@ impl<'a> Stream<'a> {
             }
         };
 
-        self.consume_byte(b';')?;
+        self.consume_byte(b';').ok()?;
 
-        Ok(reference)
+        Some(reference)
     }
 
     /// Slices data from `pos` to the current position.
@@ -635,9 +832,9 @@ impl<'a> Stream<'a> {
     /// This operation is very expensive. Use only for errors.
     #[inline(never)]
     pub fn gen_error_pos(&self) -> ErrorPos {
-        let row = self.calc_current_row();
-        let col = self.calc_current_col();
-        ErrorPos::new(row, col)
+        let mut tracker = self.line_tracker();
+        tracker.advance_to(self.span.full_str(), self.pos + self.span.start());
+        tracker.pos()
     }
 
     /// Calculates an absolute position at `pos`.
@@ -646,37 +843,112 @@ impl<'a> Stream<'a> {
     #[inline(never)]
     pub fn gen_error_pos_from(&self, pos: usize) -> ErrorPos {
         let mut s = *self;
-        let old_pos = s.pos;
         s.pos = pos;
-        let e = s.gen_error_pos();
-        s.pos = old_pos;
-        e
-    }
-
-    fn calc_current_row(&self) -> u32 {
-        let text = self.span.full_str();
-        let mut row = 1;
-        let end = self.pos + self.span.start();
-        row += text.bytes()
-                   .take(end)
-                   .filter(|c| *c == b'\n')
-                   .count();
-        row as u32
-    }
-
-    fn calc_current_col(&self) -> u32 {
-        let text = self.span.full_str();
-        let bytes = text.as_bytes();
-        let end = self.pos + self.span.start();
-        let mut col = 1;
-        for n in 0..end {
-            if n > 0 && bytes[n - 1] == b'\n' {
-                col = 2;
-            } else {
-                col += 1;
+        s.gen_error_pos()
+    }
+
+}
+
+
+/// An incremental row and column calculator.
+///
+/// Columns are counted in characters. CRLF and a lone CR are treated
+/// as a single line break, like after the end-of-line normalization.
+/// In the XML 1.1 mode, NEL, CR NEL and LS are line breaks too.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LineTracker {
+    offset: usize,
+    pos: ErrorPos,
+    // A position at the zero offset.
+    origin: ErrorPos,
+    is_after_cr: bool,
+    pub is_xml_1_1: bool,
+}
+
+impl LineTracker {
+    pub fn new() -> Self {
+        LineTracker {
+            offset: 0,
+            pos: ErrorPos::new(1, 1),
+            origin: ErrorPos::new(1, 1),
+            is_after_cr: false,
+            is_xml_1_1: false,
+        }
+    }
+
+    /// Creates a tracker that starts at the `pos` instead of 1:1.
+    pub fn with_origin(pos: ErrorPos) -> Self {
+        LineTracker { pos, origin: pos, ..LineTracker::new() }
+    }
+
+    /// Returns a position at the current offset.
+    pub fn pos(&self) -> ErrorPos {
+        self.pos
+    }
+
+    /// Advances to an absolute `offset` in the `text`.
+    ///
+    /// Starts from the beginning when `offset` is before the current one.
+    pub fn advance_to(&mut self, text: &str, offset: usize) {
+        if offset < self.offset {
+            *self = self.restart();
+        }
+
+        let mut offset = cmp::min(offset, text.len());
+        while !text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        self.consume(&text[self.offset..offset]);
+        self.offset = offset;
+    }
+
+    /// Moves the tracker `n` bytes back after the `text` start was removed.
+    ///
+    /// The `text` is the text before the removal.
+    pub fn shift_back(&mut self, text: &str, n: usize) {
+        let mut start = self.restart();
+        start.advance_to(text, n);
+        if self.offset < n {
+            *self = start;
+        }
+
+        self.origin = start.pos;
+        self.offset -= n;
+    }
+
+    /// Advances over the `text` that directly follows the already processed one.
+    ///
+    /// Doesn't update the current offset.
+    pub fn consume(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\n' if self.is_after_cr => {}
+                '\u{85}' if self.is_after_cr && self.is_xml_1_1 => {}
+                '\n' | '\r' => self.new_line(),
+                '\u{85}' | '\u{2028}' if self.is_xml_1_1 => self.new_line(),
+                _ => self.pos.col += 1,
             }
+
+            self.is_after_cr = c == '\r';
         }
+    }
+
+    // Returns a tracker at the zero offset.
+    fn restart(&self) -> LineTracker {
+        LineTracker { is_xml_1_1: self.is_xml_1_1, ..LineTracker::with_origin(self.origin) }
+    }
 
-        col
+    fn new_line(&mut self) {
+        self.pos.row += 1;
+        self.pos.col = 1;
     }
 }
+
+/// Checks that a character reference represents an XML space.
+///
+/// `&#x20; &#x9; &#xD; &#xA;`
+#[inline]
+fn is_space_char_ref(c: char) -> bool {
+    (c as u32) < 255 && (c as u8).is_xml_space()
+}
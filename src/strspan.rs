@@ -1,4 +1,5 @@
-use std::fmt;
+use core::fmt;
+use core::hash::{Hash, Hasher};
 
 use {
     Stream,
@@ -9,8 +10,13 @@ use {
 ///
 /// Unlike `&str` contains a reference to the original string
 /// and a span position.
+///
+/// **Note:** spans are compared and hashed by content only.
+/// Spans with the same text at different positions or from different
+/// strings are equal. Compare `start()` and `end()` explicitly
+/// if positions matter.
 #[must_use]
-#[derive(PartialEq, Clone, Copy)]
+#[derive(Clone, Copy)]
 pub struct StrSpan<'a> {
     text: &'a str,
     start: usize,
@@ -29,7 +35,7 @@ impl<'a> From<&'a str> for StrSpan<'a> {
 
 impl<'a> StrSpan<'a> {
     /// Constructs a new `StrSpan` from substring.
-    pub fn from_substr(text: &str, start: usize, end: usize) -> StrSpan {
+    pub fn from_substr(text: &str, start: usize, end: usize) -> StrSpan<'_> {
         debug_assert!(start <= end);
         debug_assert!(text.is_char_boundary(start));
         debug_assert!(text.is_char_boundary(end));
@@ -57,7 +63,20 @@ impl<'a> StrSpan<'a> {
         self.len() == 0
     }
 
-    /// Returns a length of the span underling string.
+    /// Returns a length of the original text the span points into.
+    ///
+    /// Unlike `len()`, doesn't depend on the span position. Useful for validating
+    /// offsets derived from `start()` and `end()`, which are offsets in this text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::StrSpan;
+    ///
+    /// let span = StrSpan::from_substr("<a>text</a>", 3, 7);
+    /// assert_eq!(span.len(), 4);
+    /// assert_eq!(span.full_len(), 11);
+    /// ```
     pub fn full_len(&self) -> usize {
         self.text.len()
     }
@@ -84,11 +103,63 @@ impl<'a> StrSpan<'a> {
         StrSpan::from_substr(self.text, start, end)
     }
 
+    /// Returns an underling string region as `StrSpan`, if it's valid.
+    ///
+    /// Unlike `slice_region`, returns `None` when `start` is bigger than `end`,
+    /// `end` is bigger than the span length or the region doesn't lie on char boundaries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::StrSpan;
+    ///
+    /// let span = StrSpan::from_substr("<a>тест</a>", 3, 11);
+    /// assert_eq!(span.checked_slice_region(2, 6).unwrap().to_str(), "ес");
+    /// assert_eq!(span.checked_slice_region(1, 2), None);
+    /// assert_eq!(span.checked_slice_region(0, 9), None);
+    /// ```
+    pub fn checked_slice_region(&self, start: usize, end: usize) -> Option<StrSpan<'a>> {
+        if start > end || end > self.len() {
+            return None;
+        }
+
+        let start = self.start + start;
+        let end = self.start + end;
+        if !self.text.is_char_boundary(start) || !self.text.is_char_boundary(end) {
+            return None;
+        }
+
+        Some(StrSpan { text: self.text, start, end })
+    }
+
     /// Returns an underling string.
     pub fn full_str(&self) -> &'a str {
         self.text
     }
 
+    /// Returns a span between the end of `a` and the start of `b`.
+    ///
+    /// Returns `None` if `b` starts before the end of `a`
+    /// or if spans are from different strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::StrSpan;
+    ///
+    /// let text = "<a>text</a>";
+    /// let a = StrSpan::from_substr(text, 0, 3);
+    /// let b = StrSpan::from_substr(text, 7, 11);
+    /// assert_eq!(StrSpan::between(a, b).unwrap().to_str(), "text");
+    /// ```
+    pub fn between(a: StrSpan<'a>, b: StrSpan<'a>) -> Option<StrSpan<'a>> {
+        if !::core::ptr::eq(a.text, b.text) || a.end > b.start {
+            return None;
+        }
+
+        Some(StrSpan::from_substr(a.text, a.end, b.start))
+    }
+
     /// Returns a trimmed version of this `StrSpan`.
     ///
     /// Removes only leading and trailing spaces.
@@ -112,6 +183,49 @@ impl<'a> StrSpan<'a> {
 
         self.slice_region(start, end)
     }
+
+    /// Splits a qualified name into prefix and local part.
+    ///
+    /// Doesn't validate the name itself. If the name has more than one colon
+    /// or one of the parts is empty, then the whole name is returned as a local part.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::StrSpan;
+    ///
+    /// let (prefix, local) = StrSpan::from("svg:rect").split_qname();
+    /// assert_eq!(prefix.unwrap().to_str(), "svg");
+    /// assert_eq!(local.to_str(), "rect");
+    /// ```
+    pub fn split_qname(&self) -> (Option<StrSpan<'a>>, StrSpan<'a>) {
+        let bytes = self.as_bytes();
+        let mut iter = bytes.iter().enumerate().filter(|&(_, c)| *c == b':');
+
+        match (iter.next(), iter.next()) {
+            (Some((idx, _)), None) if idx != 0 && idx != bytes.len() - 1 => {
+                let prefix = self.slice_region(0, idx);
+                let local = self.slice_region(idx + 1, self.len());
+                (Some(prefix), local)
+            }
+            _ => (None, *self),
+        }
+    }
+}
+
+impl<'a> PartialEq for StrSpan<'a> {
+    fn eq(&self, other: &StrSpan<'a>) -> bool {
+        self.to_str() == other.to_str()
+    }
+}
+
+impl<'a> Eq for StrSpan<'a> {}
+
+// Must be consistent with `PartialEq`.
+impl<'a> Hash for StrSpan<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_str().hash(state);
+    }
 }
 
 impl<'a> fmt::Debug for StrSpan<'a> {
@@ -159,4 +273,119 @@ mod tests {
     fn do_not_trim_2() {
         assert_eq!(StrSpan::from("&ref;text&apos;").trim().to_str(), "&ref;text&apos;");
     }
+
+    fn split(text: &str) -> (Option<&str>, &str) {
+        let (prefix, local) = StrSpan::from(text).split_qname();
+        (prefix.map(|v| v.to_str()), local.to_str())
+    }
+
+    #[test]
+    fn split_qname_1() {
+        assert_eq!(split("foo:bar"), (Some("foo"), "bar"));
+    }
+
+    #[test]
+    fn split_qname_2() {
+        assert_eq!(split("bar"), (None, "bar"));
+    }
+
+    #[test]
+    fn split_qname_3() {
+        assert_eq!(split("foo:"), (None, "foo:"));
+    }
+
+    #[test]
+    fn split_qname_4() {
+        assert_eq!(split(":bar"), (None, ":bar"));
+    }
+
+    #[test]
+    fn split_qname_5() {
+        assert_eq!(split("a:b:c"), (None, "a:b:c"));
+    }
+
+    #[test]
+    fn split_qname_6() {
+        let span = StrSpan::from_substr("<svg:rect/>", 1, 9);
+        let (prefix, local) = span.split_qname();
+        assert_eq!(prefix.unwrap().start(), 1);
+        assert_eq!(local.start(), 5);
+    }
+
+    #[test]
+    fn between_1() {
+        let text = "<a></a>";
+        let a = StrSpan::from_substr(text, 0, 3);
+        let b = StrSpan::from_substr(text, 3, 7);
+        let span = StrSpan::between(a, b).unwrap();
+        assert!(span.is_empty());
+        assert_eq!(span.start(), 3);
+    }
+
+    #[test]
+    fn between_2() {
+        let text = "<a> text </a>";
+        let a = StrSpan::from_substr(text, 0, 3);
+        let b = StrSpan::from_substr(text, 9, 13);
+        assert_eq!(StrSpan::between(a, b).unwrap().to_str(), " text ");
+    }
+
+    #[test]
+    fn between_3() {
+        let text = "<a> text </a>";
+        let a = StrSpan::from_substr(text, 0, 3);
+        let b = StrSpan::from_substr(text, 9, 13);
+        assert_eq!(StrSpan::between(b, a), None);
+    }
+
+    #[test]
+    fn between_4() {
+        let text1 = String::from("<a></a>");
+        let text2 = text1.clone();
+        let a = StrSpan::from_substr(&text1, 0, 3);
+        let b = StrSpan::from_substr(&text2, 3, 7);
+        assert_eq!(StrSpan::between(a, b), None);
+    }
+
+    #[test]
+    fn full_len_1() {
+        let text = "<a>text</a>";
+        assert_eq!(StrSpan::from(text).full_len(), text.len());
+        assert_eq!(StrSpan::from_substr(text, 0, 0).full_len(), text.len());
+        assert_eq!(StrSpan::from_substr(text, 3, 7).full_len(), text.len());
+        assert_eq!(StrSpan::from_substr(text, 11, 11).full_len(), text.len());
+        assert_eq!(StrSpan::from_substr(text, 3, 7).slice_region(1, 2).full_len(), text.len());
+    }
+
+    #[test]
+    fn checked_slice_region_1() {
+        let span = StrSpan::from_substr("<a>text</a>", 3, 7);
+        assert_eq!(span.checked_slice_region(0, 4).unwrap().start(), 3);
+        assert!(span.checked_slice_region(4, 4).unwrap().is_empty());
+        assert_eq!(span.checked_slice_region(3, 2), None);
+        assert_eq!(span.checked_slice_region(0, 5), None);
+        assert_eq!(span.checked_slice_region(5, 5), None);
+    }
+
+    #[test]
+    fn eq_1() {
+        let text = "<a><a/></a>";
+        assert_eq!(StrSpan::from_substr(text, 1, 2), StrSpan::from_substr(text, 4, 5));
+        assert_ne!(StrSpan::from_substr(text, 1, 2), StrSpan::from_substr(text, 0, 1));
+    }
+
+    #[test]
+    fn hash_1() {
+        use std::collections::HashMap;
+
+        let text = "<a><b/></a>";
+        let mut map = HashMap::new();
+        map.insert(StrSpan::from_substr(text, 1, 2), 1);
+
+        // Different offset.
+        assert_eq!(map.get(&StrSpan::from_substr(text, 9, 10)), Some(&1));
+        // Different string.
+        assert_eq!(map.get(&StrSpan::from("a")), Some(&1));
+        assert_eq!(map.get(&StrSpan::from("b")), None);
+    }
 }
@@ -1,9 +1,15 @@
-use std::str;
-use std::io::Write;
+use alloc::borrow::Cow;
+use core::str;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 use {
+    Reference,
     Stream,
+    StreamError,
     StrSpan,
+    XmlByteExt,
 };
 
 
@@ -18,6 +24,124 @@ pub enum XmlSpace {
 }
 
 
+/// A quote character used for an attribute value.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub enum QuoteChar {
+    Single,
+    Double,
+}
+
+
+/// Escapes a text for inclusion as an XML character data.
+///
+/// `&`, `<` and `>` are replaced with the predefined entity references.
+/// The text is returned as is when there is nothing to escape.
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::escape_text;
+///
+/// assert_eq!(escape_text("a < b"), "a &lt; b");
+/// ```
+pub fn escape_text(text: &str) -> Cow<'_, str> {
+    escape(text, |c| match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        _ => None,
+    })
+}
+
+/// Escapes a text for inclusion as an XML attribute value.
+///
+/// The same as [`escape_text`], but the `quote` character is also escaped.
+/// The text is returned as is when there is nothing to escape.
+///
+/// [`escape_text`]: fn.escape_text.html
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::{escape_attribute, QuoteChar};
+///
+/// assert_eq!(escape_attribute("'a' & \"b\"", QuoteChar::Double), "'a' &amp; &quot;b&quot;");
+/// ```
+pub fn escape_attribute(text: &str, quote: QuoteChar) -> Cow<'_, str> {
+    escape(text, |c| match (c, quote) {
+        ('"', QuoteChar::Double) => Some("&quot;"),
+        ('\'', QuoteChar::Single) => Some("&apos;"),
+        ('&', _) => Some("&amp;"),
+        ('<', _) => Some("&lt;"),
+        ('>', _) => Some("&gt;"),
+        _ => None,
+    })
+}
+
+fn escape<F>(text: &str, f: F) -> Cow<'_, str>
+    where F: Fn(char) -> Option<&'static str>
+{
+    let idx = match text.find(|c| f(c).is_some()) {
+        Some(idx) => idx,
+        None => return Cow::Borrowed(text),
+    };
+
+    let mut s = String::with_capacity(text.len() + 8);
+    s.push_str(&text[..idx]);
+    for c in text[idx..].chars() {
+        match f(c) {
+            Some(v) => s.push_str(v),
+            None => s.push(c),
+        }
+    }
+
+    Cow::Owned(s)
+}
+
+
+/// Collapses whitespaces in a text.
+///
+/// Leading and trailing whitespaces are removed and each run of whitespaces
+/// is replaced with a single space. References and line endings are not processed.
+/// The text is returned as is when there is nothing to change.
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::collapse_whitespace;
+///
+/// assert_eq!(collapse_whitespace(" a \n\t b "), "a b");
+/// ```
+pub fn collapse_whitespace(text: &str) -> Cow<'_, str> {
+    let trimmed = text.trim_matches(is_xml_space);
+
+    // Check that each whitespace is a single space.
+    let is_collapsed = !trimmed.as_bytes().windows(2).any(|w| {
+        w[0].is_xml_space() && (w[0] != b' ' || w[1].is_xml_space())
+    });
+
+    if is_collapsed {
+        return Cow::Borrowed(trimmed);
+    }
+
+    let mut s = String::with_capacity(trimmed.len());
+    for part in trimmed.split(is_xml_space).filter(|part| !part.is_empty()) {
+        if !s.is_empty() {
+            s.push(' ');
+        }
+
+        s.push_str(part);
+    }
+
+    Cow::Owned(s)
+}
+
+fn is_xml_space(c: char) -> bool {
+    c.is_ascii() && (c as u8).is_xml_space()
+}
+
+
 /// An XML escaped text to plain text converter.
 ///
 /// Processing is done as described in: <https://www.w3.org/TR/SVG11/text.html#WhiteSpace>
@@ -86,6 +210,101 @@ impl<'a> TextUnescape<'a> {
     pub fn set_xml_space(&mut self, kind: XmlSpace) {
         self.preserve_spaces = kind == XmlSpace::Preserve;
     }
+
+    /// Decodes references and line endings in a text or an attribute value.
+    ///
+    /// Unlike the iterator, follows the XML rules instead of the SVG ones.
+    ///
+    /// Character references and the five predefined entity references are resolved.
+    /// Other entity references and malformed references result in an error.
+    /// Line endings, CRLF and CR, are replaced with LF.
+    ///
+    /// With `XmlSpace::Default`, the attribute value normalization is applied:
+    /// each literal space, LF, CR, tab and CRLF is replaced with a single space.
+    /// Spaces produced by character references are preserved.
+    ///
+    /// The text is returned as is when there is nothing to change.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StreamError::InvalidReference` with a position of the `&`
+    /// in the original text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{TextUnescape, StrSpan, XmlSpace};
+    ///
+    /// let text = StrSpan::from("a &lt;\tb&#x9;&#128512;");
+    /// assert_eq!(TextUnescape::decode(text, XmlSpace::Preserve).unwrap(), "a <\tb\t\u{1F600}");
+    /// assert_eq!(TextUnescape::decode(text, XmlSpace::Default).unwrap(), "a < b\t\u{1F600}");
+    ///
+    /// let text = StrSpan::from("a &unknown;");
+    /// assert_eq!(TextUnescape::decode(text, XmlSpace::Preserve).unwrap_err().to_string(),
+    ///            "invalid reference at 1:3");
+    /// ```
+    pub fn decode(text: StrSpan<'a>, space: XmlSpace) -> Result<Cow<'a, str>, StreamError> {
+        decode(text, space == XmlSpace::Default, false)
+    }
+
+    /// Decodes references and line endings like `decode`,
+    /// but keeps unknown and malformed references as is.
+    pub(crate) fn decode_lenient(text: StrSpan<'a>) -> Cow<'a, str> {
+        match decode(text, false, true) {
+            Ok(text) => text,
+            Err(_) => unreachable!("lenient decoding never fails"),
+        }
+    }
+}
+
+fn decode(text: StrSpan, normalize_spaces: bool, keep_unresolved: bool)
+    -> Result<Cow<str>, StreamError>
+{
+    let is_special = |c: u8| {
+        c == b'&' || c == b'\r' || (normalize_spaces && (c == b'\n' || c == b'\t'))
+    };
+
+    if !text.as_bytes().iter().any(|c| is_special(*c)) {
+        return Ok(Cow::Borrowed(text.to_str()));
+    }
+
+    let mut s = Stream::from(text);
+    let mut out = String::with_capacity(text.len());
+    while !s.at_end() {
+        out.push_str(s.consume_bytes(|_, c| !is_special(c)).to_str());
+
+        let start = s.pos();
+        match s.curr_byte() {
+            Ok(b'&') => {
+                match s.consume_reference() {
+                    Ok(Reference::CharRef(c)) => out.push(c),
+                    _ if keep_unresolved => {
+                        s.set_pos(start);
+                        s.advance(1);
+                        out.push('&');
+                    }
+                    _ => {
+                        return Err(StreamError::InvalidReference(s.gen_error_pos_from(start)));
+                    }
+                }
+            }
+            Ok(b'\r') => {
+                s.advance(1);
+                if s.curr_byte().ok() == Some(b'\n') {
+                    s.advance(1);
+                }
+
+                out.push(if normalize_spaces { ' ' } else { '\n' });
+            }
+            Ok(_) => {
+                s.advance(1);
+                out.push(' ');
+            }
+            Err(_) => {}
+        }
+    }
+
+    Ok(Cow::Owned(out))
 }
 
 impl<'a> Iterator for TextUnescape<'a> {
@@ -114,7 +333,7 @@ impl<'a> Iterator for TextUnescape<'a> {
             if let Some(ch) = self.stream.try_consume_char_reference() {
                 self.buf = [0xFF; 4];
 
-                write!(&mut self.buf[..], "{}", ch).unwrap();
+                ch.encode_utf8(&mut self.buf);
 
                 c = self.buf[0];
                 self.buf_idx = 1;
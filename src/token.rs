@@ -1,5 +1,9 @@
+use alloc::borrow::Cow;
+
 use {
+    AttributeDefinitions,
     StrSpan,
+    TextUnescape,
 };
 
 
@@ -22,11 +26,16 @@ pub enum Token<'a> {
     Comment(StrSpan<'a>),
     /// DOCTYPE start token.
     ///
-    /// Example: `<!DOCTYPE note [`
+    /// Emitted only when the DOCTYPE has an internal subset.
+    /// Always followed by `DtdEnd`.
+    ///
+    /// Example: `<!DOCTYPE note [`, `<!DOCTYPE note SYSTEM "note.dtd" [`
     DtdStart(StrSpan<'a>, Option<ExternalId<'a>>),
     /// Empty DOCTYPE token.
     ///
-    /// Example: `<!DOCTYPE note>`
+    /// Emitted when the DOCTYPE doesn't have an internal subset.
+    ///
+    /// Example: `<!DOCTYPE note>`, `<!DOCTYPE note SYSTEM "note.dtd">`
     EmptyDtd(StrSpan<'a>, Option<ExternalId<'a>>),
     /// ENTITY token.
     ///
@@ -34,6 +43,40 @@ pub enum Token<'a> {
     ///
     /// Example: `<!ENTITY ns_extend "http://test.com">`
     EntityDeclaration(StrSpan<'a>, EntityDefinition<'a>),
+    /// ELEMENT token.
+    ///
+    /// Contains element name and content specification as is.
+    ///
+    /// Can appear only inside the DTD and only when
+    /// [`Tokenizer::enable_dtd_declaration_tokens`] is enabled.
+    ///
+    /// Example: `<!ELEMENT note (to, from, body?)>`
+    ///
+    /// [`Tokenizer::enable_dtd_declaration_tokens`]: struct.Tokenizer.html#method.enable_dtd_declaration_tokens
+    ElementDeclaration(StrSpan<'a>, StrSpan<'a>),
+    /// ATTLIST token.
+    ///
+    /// Contains element name and attribute definitions.
+    ///
+    /// Can appear only inside the DTD and only when
+    /// [`Tokenizer::enable_dtd_declaration_tokens`] is enabled.
+    ///
+    /// Example: `<!ATTLIST note id ID #REQUIRED lang CDATA "en">`
+    ///
+    /// [`Tokenizer::enable_dtd_declaration_tokens`]: struct.Tokenizer.html#method.enable_dtd_declaration_tokens
+    AttlistDeclaration(StrSpan<'a>, AttributeDefinitions<'a>),
+    /// NOTATION token.
+    ///
+    /// Contains notation name, public ID and system ID.
+    /// At least one of the IDs is set.
+    ///
+    /// Can appear only inside the DTD and only when
+    /// [`Tokenizer::enable_dtd_declaration_tokens`] is enabled.
+    ///
+    /// Example: `<!NOTATION png SYSTEM "image/png">`
+    ///
+    /// [`Tokenizer::enable_dtd_declaration_tokens`]: struct.Tokenizer.html#method.enable_dtd_declaration_tokens
+    NotationDeclaration(StrSpan<'a>, Option<StrSpan<'a>>, Option<StrSpan<'a>>),
     /// DOCTYPE end token.
     ///
     /// Example: `]>`
@@ -46,10 +89,14 @@ pub enum Token<'a> {
     ElementStart(StrSpan<'a>, StrSpan<'a>),
     /// Attribute.
     ///
-    /// Contains prefix and local part of the qualified name and value.
+    /// Contains prefix and local part of the qualified name, value
+    /// and an index of the attribute within its element.
+    ///
+    /// Attributes are emitted in the source order,
+    /// so the first attribute of each element has the index 0.
     ///
     /// Example: `name="value"`
-    Attribute((StrSpan<'a>, StrSpan<'a>), StrSpan<'a>),
+    Attribute((StrSpan<'a>, StrSpan<'a>), StrSpan<'a>, usize),
     /// Element end token.
     ElementEnd(ElementEnd<'a>),
     /// Text token.
@@ -69,10 +116,29 @@ pub enum Token<'a> {
     ///
     /// Spaces can be encoded like `&#x20`.
     Whitespaces(StrSpan<'a>),
+    /// Documents boundary token.
+    ///
+    /// Appears only when [`Tokenizer::set_multi_document_mode`] is enabled,
+    /// before each document except the first one.
+    ///
+    /// [`Tokenizer::set_multi_document_mode`]: struct.Tokenizer.html#method.set_multi_document_mode
+    DocumentBoundary,
     /// CDATA token.
     ///
+    /// Contains text between `<![CDATA[` and `]]>` as is.
+    /// Unlike `Text`, it should not be unescaped.
+    ///
     /// Example: `<![CDATA[text]]>`
     Cdata(StrSpan<'a>),
+    /// End of document token.
+    ///
+    /// Contains an absolute offset of the text end.
+    ///
+    /// Appears only when [`Tokenizer::enable_end_of_document_token`] is enabled,
+    /// once, after the last token.
+    ///
+    /// [`Tokenizer::enable_end_of_document_token`]: struct.Tokenizer.html#method.enable_end_of_document_token
+    EndOfDocument(usize),
 }
 
 
@@ -87,6 +153,52 @@ pub enum ElementEnd<'a> {
     Empty,
 }
 
+impl<'a> ElementEnd<'a> {
+    /// Checks that the current value is `Open`.
+    pub fn is_open(&self) -> bool {
+        matches!(*self, ElementEnd::Open)
+    }
+
+    /// Checks that the current value is `Close`.
+    pub fn is_close(&self) -> bool {
+        matches!(*self, ElementEnd::Close(..))
+    }
+
+    /// Checks that the current value is `Empty`.
+    pub fn is_empty(&self) -> bool {
+        matches!(*self, ElementEnd::Empty)
+    }
+
+    /// Returns a qualified name of the closed element.
+    ///
+    /// Returns `None` for `Open` and `Empty`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Token};
+    ///
+    /// let mut p = Tokenizer::from("<svg:g></svg:g>");
+    /// p.next(); // ElementStart
+    /// p.next(); // ElementEnd::Open
+    /// if let Some(Ok(Token::ElementEnd(end))) = p.next() {
+    ///     assert_eq!(end.name().unwrap().to_str(), "svg:g");
+    /// }
+    /// ```
+    pub fn name(&self) -> Option<StrSpan<'a>> {
+        match *self {
+            ElementEnd::Close(prefix, local) => {
+                if prefix.is_empty() {
+                    Some(local)
+                } else {
+                    Some(StrSpan::from_substr(local.full_str(), prefix.start(), local.end()))
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
 
 /// Representation of the [ExternalID](https://www.w3.org/TR/xml/#NT-ExternalID) value.
 #[allow(missing_docs)]
@@ -104,3 +216,379 @@ pub enum EntityDefinition<'a> {
     EntityValue(StrSpan<'a>),
     ExternalId(ExternalId<'a>),
 }
+
+/// Representation of the [DefaultDecl](https://www.w3.org/TR/xml/#NT-DefaultDecl) value.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AttributeDefault<'a> {
+    Required,
+    Implied,
+    Value(StrSpan<'a>),
+    Fixed(StrSpan<'a>),
+}
+
+
+/// Representation of the [AttDef](https://www.w3.org/TR/xml/#NT-AttDef) value.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AttributeDefinition<'a> {
+    /// An attribute name.
+    pub name: StrSpan<'a>,
+    /// An attribute type as is, like `CDATA`, `ID` or `(a | b)`.
+    pub att_type: StrSpan<'a>,
+    /// A default value as is.
+    pub default: AttributeDefault<'a>,
+}
+
+impl<'a> Token<'a> {
+    /// Returns declaration's version, encoding and standalone
+    /// if the current token is `Declaration`.
+    pub fn as_declaration(&self) -> Option<(StrSpan<'a>, Option<StrSpan<'a>>, Option<StrSpan<'a>>)> {
+        match *self {
+            Token::Declaration(version, encoding, standalone) => {
+                Some((version, encoding, standalone))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns target and content if the current token is `ProcessingInstruction`.
+    pub fn as_processing_instruction(&self) -> Option<(StrSpan<'a>, Option<StrSpan<'a>>)> {
+        match *self {
+            Token::ProcessingInstruction(target, content) => Some((target, content)),
+            _ => None,
+        }
+    }
+
+    /// Returns text between `<!--` and `-->` if the current token is `Comment`.
+    ///
+    /// The text is never trimmed.
+    pub fn as_comment(&self) -> Option<StrSpan<'a>> {
+        match *self {
+            Token::Comment(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Returns content if the current token is `ProcessingInstruction`.
+    ///
+    /// The content excludes `<?`, the target, spaces after the target and `?>`.
+    /// Returns `None` for a processing instruction without content, like `<?target ?>`.
+    pub fn pi_content(&self) -> Option<StrSpan<'a>> {
+        match *self {
+            Token::ProcessingInstruction(_, content) => content,
+            _ => None,
+        }
+    }
+
+    /// Returns name and external id if the current token is `DtdStart`.
+    pub fn as_dtd_start(&self) -> Option<(StrSpan<'a>, Option<ExternalId<'a>>)> {
+        match *self {
+            Token::DtdStart(name, id) => Some((name, id)),
+            _ => None,
+        }
+    }
+
+    /// Returns name and external id if the current token is `EmptyDtd`.
+    pub fn as_empty_dtd(&self) -> Option<(StrSpan<'a>, Option<ExternalId<'a>>)> {
+        match *self {
+            Token::EmptyDtd(name, id) => Some((name, id)),
+            _ => None,
+        }
+    }
+
+    /// Returns name and definition if the current token is `EntityDeclaration`.
+    pub fn as_entity_declaration(&self) -> Option<(StrSpan<'a>, EntityDefinition<'a>)> {
+        match *self {
+            Token::EntityDeclaration(name, def) => Some((name, def)),
+            _ => None,
+        }
+    }
+
+    /// Returns prefix and local part if the current token is `ElementStart`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::Tokenizer;
+    ///
+    /// let token = Tokenizer::from("<svg:rect/>").next().unwrap().unwrap();
+    /// if let Some((prefix, local)) = token.as_element_start() {
+    ///     assert_eq!(prefix.to_str(), "svg");
+    ///     assert_eq!(local.to_str(), "rect");
+    /// }
+    /// ```
+    pub fn as_element_start(&self) -> Option<(StrSpan<'a>, StrSpan<'a>)> {
+        match *self {
+            Token::ElementStart(prefix, local) => Some((prefix, local)),
+            _ => None,
+        }
+    }
+
+    /// Returns qualified name and value if the current token is `Attribute`.
+    pub fn as_attribute(&self) -> Option<((StrSpan<'a>, StrSpan<'a>), StrSpan<'a>)> {
+        match *self {
+            Token::Attribute(name, value, _) => Some((name, value)),
+            _ => None,
+        }
+    }
+
+    /// Returns `ElementEnd` if the current token is `ElementEnd`.
+    pub fn as_element_end(&self) -> Option<ElementEnd<'a>> {
+        match *self {
+            Token::ElementEnd(end) => Some(end),
+            _ => None,
+        }
+    }
+
+    /// Returns text if the current token is `Text`.
+    pub fn as_text(&self) -> Option<StrSpan<'a>> {
+        match *self {
+            Token::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Returns text if the current token is `Whitespaces`.
+    pub fn as_whitespaces(&self) -> Option<StrSpan<'a>> {
+        match *self {
+            Token::Whitespaces(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Returns text if the current token is `Cdata`.
+    ///
+    /// The text doesn't include the `<![CDATA[` and `]]>` delimiters.
+    pub fn as_cdata(&self) -> Option<StrSpan<'a>> {
+        match *self {
+            Token::Cdata(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Checks that the current token is `DtdEnd`.
+    pub fn is_dtd_end(&self) -> bool {
+        matches!(*self, Token::DtdEnd)
+    }
+
+    /// Returns the character content of `Text`, `Whitespaces` and `Cdata` tokens.
+    ///
+    /// `Cdata` is returned as is. For `Text` and `Whitespaces`, the predefined entity
+    /// and character references are expanded, which allocates only when the text
+    /// actually contains a reference or a CR. Other entity references are kept verbatim.
+    /// Line endings are replaced with LF, like in [`TextUnescape::decode`].
+    /// Whitespaces are not normalized otherwise.
+    ///
+    /// Returns `None` for any other token.
+    ///
+    /// [`TextUnescape::decode`]: struct.TextUnescape.html#method.decode
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use xmlparser::{Token, StrSpan};
+    ///
+    /// let token = Token::Text(StrSpan::from("a &amp; b"));
+    /// assert_eq!(token.text_content(), Some(Cow::Owned("a & b".to_string())));
+    /// ```
+    pub fn text_content(&self) -> Option<Cow<'a, str>> {
+        match *self {
+            Token::Cdata(text) => Some(Cow::Borrowed(text.to_str())),
+            Token::Text(text) | Token::Whitespaces(text) => Some(expand_references(text)),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn expand_references(text: StrSpan) -> Cow<str> {
+    TextUnescape::decode_lenient(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_tokens() -> Vec<Token<'static>> {
+        let s = StrSpan::from("text");
+
+        // `AttributeDefinitions` can be created only by the tokenizer.
+        let mut p = ::Tokenizer::from("<!DOCTYPE a [<!ATTLIST a b CDATA #IMPLIED>]>");
+        p.enable_dtd_declaration_tokens();
+        let attlist = p.nth(1).unwrap().unwrap();
+        assert!(matches!(attlist, Token::AttlistDeclaration(..)));
+
+        vec![
+            Token::Declaration(s, None, None),
+            Token::ProcessingInstruction(s, None),
+            Token::Comment(s),
+            Token::DtdStart(s, None),
+            Token::EmptyDtd(s, None),
+            Token::EntityDeclaration(s, EntityDefinition::EntityValue(s)),
+            Token::DtdEnd,
+            Token::ElementStart(s, s),
+            Token::Attribute((s, s), s, 0),
+            Token::ElementEnd(ElementEnd::Empty),
+            Token::Text(s),
+            Token::Whitespaces(s),
+            Token::Cdata(s),
+            Token::ElementDeclaration(s, s),
+            attlist,
+            Token::NotationDeclaration(s, None, Some(s)),
+            Token::DocumentBoundary,
+            Token::EndOfDocument(0),
+        ]
+    }
+
+    // Each accessor must return a value only for the token at the same index in `all_tokens`.
+    fn check<F: Fn(&Token) -> bool>(idx: usize, f: F) {
+        for (i, token) in all_tokens().iter().enumerate() {
+            assert_eq!(f(token), i == idx, "{:?}", token);
+        }
+    }
+
+    #[test]
+    fn text_content_1() {
+        let token = Token::Cdata(StrSpan::from("a &amp; b"));
+        assert_eq!(token.text_content(), Some(Cow::Borrowed("a &amp; b")));
+    }
+
+    #[test]
+    fn text_content_2() {
+        let token = Token::Text(StrSpan::from("text"));
+        match token.text_content() {
+            Some(Cow::Borrowed("text")) => {}
+            v => panic!("{:?}", v),
+        }
+    }
+
+    #[test]
+    fn text_content_3() {
+        let token = Token::Text(StrSpan::from("a &amp; &#x62; &ent; & c\r\n"));
+        match token.text_content() {
+            Some(Cow::Owned(ref v)) if v == "a & b &ent; & c\n" => {}
+            v => panic!("{:?}", v),
+        }
+    }
+
+    #[test]
+    fn text_content_4() {
+        let token = Token::Comment(StrSpan::from("text"));
+        assert_eq!(token.text_content(), None);
+    }
+
+    #[test]
+    fn text_content_5() {
+        // Whitespaces can contain character references too.
+        let token = Token::Whitespaces(StrSpan::from("&#x20;\n"));
+        assert_eq!(token.text_content(), Some(Cow::Owned(" \n".to_string())));
+    }
+
+    #[test]
+    fn as_declaration() {
+        check(0, |t| t.as_declaration().is_some());
+    }
+
+    #[test]
+    fn as_processing_instruction() {
+        check(1, |t| t.as_processing_instruction().is_some());
+    }
+
+    #[test]
+    fn as_comment() {
+        check(2, |t| t.as_comment().is_some());
+    }
+
+    #[test]
+    fn as_dtd_start() {
+        check(3, |t| t.as_dtd_start().is_some());
+    }
+
+    #[test]
+    fn as_empty_dtd() {
+        check(4, |t| t.as_empty_dtd().is_some());
+    }
+
+    #[test]
+    fn as_entity_declaration() {
+        check(5, |t| t.as_entity_declaration().is_some());
+    }
+
+    #[test]
+    fn is_dtd_end() {
+        check(6, |t| t.is_dtd_end());
+    }
+
+    #[test]
+    fn as_element_start() {
+        check(7, |t| t.as_element_start().is_some());
+    }
+
+    #[test]
+    fn as_attribute() {
+        check(8, |t| t.as_attribute().is_some());
+    }
+
+    #[test]
+    fn as_element_end() {
+        check(9, |t| t.as_element_end().is_some());
+    }
+
+    #[test]
+    fn as_text() {
+        check(10, |t| t.as_text().is_some());
+    }
+
+    #[test]
+    fn as_whitespaces() {
+        check(11, |t| t.as_whitespaces().is_some());
+    }
+
+    #[test]
+    fn as_cdata() {
+        check(12, |t| t.as_cdata().is_some());
+    }
+
+    #[test]
+    fn payload() {
+        let prefix = StrSpan::from_substr("svg:rect", 0, 3);
+        let local = StrSpan::from_substr("svg:rect", 4, 8);
+        let token = Token::ElementStart(prefix, local);
+        assert_eq!(token.as_element_start(), Some((prefix, local)));
+    }
+
+    #[test]
+    fn element_end_1() {
+        let end = ElementEnd::Open;
+        assert!(end.is_open());
+        assert!(!end.is_close());
+        assert!(!end.is_empty());
+        assert_eq!(end.name(), None);
+    }
+
+    #[test]
+    fn element_end_2() {
+        let end = ElementEnd::Empty;
+        assert!(!end.is_open());
+        assert!(!end.is_close());
+        assert!(end.is_empty());
+        assert_eq!(end.name(), None);
+    }
+
+    #[test]
+    fn element_end_3() {
+        let text = "</svg:g>";
+        let end = ElementEnd::Close(StrSpan::from_substr(text, 2, 5), StrSpan::from_substr(text, 6, 7));
+        assert!(!end.is_open());
+        assert!(end.is_close());
+        assert!(!end.is_empty());
+        assert_eq!(end.name().unwrap().to_str(), "svg:g");
+    }
+
+    #[test]
+    fn element_end_4() {
+        let text = "</g>";
+        let end = ElementEnd::Close(StrSpan::from_substr(text, 2, 2), StrSpan::from_substr(text, 2, 3));
+        assert_eq!(end.name().unwrap().to_str(), "g");
+    }
+}
@@ -0,0 +1,278 @@
+use core::fmt::{self, Write};
+
+use {
+    escape_attribute,
+    escape_text,
+    AttributeDefinitions,
+    ElementEnd,
+    EntityDefinition,
+    ExternalId,
+    QuoteChar,
+    StrSpan,
+    Token,
+};
+
+
+/// A tokens writer.
+///
+/// Serializes tokens back into XML. Token values are written as is,
+/// since they are not unescaped by the tokenizer. Attribute values and
+/// literals are written using the original quotes, if available.
+///
+/// Tokens don't store the formatting between their parts, like spaces around `=`.
+/// To preserve a document byte-for-byte, use [`write_verbatim`] with
+/// [`Tokenizer::verbatim_span`] for unchanged tokens and [`write_token`],
+/// [`write_text`] or [`write_attribute`] for the changed ones.
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::{Tokenizer, Token, TokenWriter, QuoteChar};
+///
+/// let text = "<a  x = 'old'>\n  <b x=\"1\"/>\n</a>";
+/// let mut p = Tokenizer::from(text);
+/// let mut w = TokenWriter::new(String::new());
+/// while let Some(token) = p.next() {
+///     match token.unwrap() {
+///         Token::Attribute((_, local), value, _) if value.to_str() == "old" => {
+///             w.write_attribute("", local.to_str(), "new & shiny", QuoteChar::Single).unwrap();
+///         }
+///         _ => w.write_verbatim(p.verbatim_span()).unwrap(),
+///     }
+/// }
+///
+/// assert_eq!(w.into_inner(), "<a x='new &amp; shiny'>\n  <b x=\"1\"/>\n</a>");
+/// ```
+///
+/// [`write_verbatim`]: #method.write_verbatim
+/// [`write_token`]: #method.write_token
+/// [`write_text`]: #method.write_text
+/// [`write_attribute`]: #method.write_attribute
+/// [`Tokenizer::verbatim_span`]: struct.Tokenizer.html#method.verbatim_span
+pub struct TokenWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> TokenWriter<W> {
+    /// Creates a new writer.
+    pub fn new(out: W) -> Self {
+        TokenWriter { out }
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.out
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    /// Writes a source text as is.
+    pub fn write_verbatim(&mut self, span: StrSpan) -> fmt::Result {
+        self.out.write_str(span.to_str())
+    }
+
+    /// Writes a text escaped by [`escape_text`].
+    ///
+    /// [`escape_text`]: fn.escape_text.html
+    pub fn write_text(&mut self, text: &str) -> fmt::Result {
+        self.out.write_str(&escape_text(text))
+    }
+
+    /// Writes an attribute with a value escaped by [`escape_attribute`].
+    ///
+    /// An empty `prefix` means no prefix. The attribute is preceded by a space.
+    ///
+    /// [`escape_attribute`]: fn.escape_attribute.html
+    pub fn write_attribute(&mut self, prefix: &str, local: &str, value: &str,
+                           quote: QuoteChar) -> fmt::Result {
+        let c = match quote {
+            QuoteChar::Single => '\'',
+            QuoteChar::Double => '"',
+        };
+
+        self.out.write_char(' ')?;
+        self.write_qname(prefix, local)?;
+        write!(self.out, "={}{}{}", c, escape_attribute(value, quote), c)
+    }
+
+    /// Writes a token.
+    ///
+    /// An `Attribute` token is preceded by a space.
+    /// `DocumentBoundary` and `EndOfDocument` tokens are not written.
+    pub fn write_token(&mut self, token: &Token) -> fmt::Result {
+        match *token {
+            Token::Declaration(version, encoding, standalone) => {
+                self.out.write_str("<?xml version=")?;
+                self.write_quoted(version)?;
+                if let Some(encoding) = encoding {
+                    self.out.write_str(" encoding=")?;
+                    self.write_quoted(encoding)?;
+                }
+
+                if let Some(standalone) = standalone {
+                    self.out.write_str(" standalone=")?;
+                    self.write_quoted(standalone)?;
+                }
+
+                self.out.write_str("?>")
+            }
+            Token::ProcessingInstruction(target, content) => {
+                write!(self.out, "<?{}", target)?;
+                if let Some(content) = content {
+                    write!(self.out, " {}", content)?;
+                }
+
+                self.out.write_str("?>")
+            }
+            Token::Comment(text) => {
+                write!(self.out, "<!--{}-->", text)
+            }
+            Token::DtdStart(name, external_id) => {
+                self.write_doctype(name, external_id)?;
+                self.out.write_str(" [")
+            }
+            Token::EmptyDtd(name, external_id) => {
+                self.write_doctype(name, external_id)?;
+                self.out.write_char('>')
+            }
+            Token::EntityDeclaration(name, definition) => {
+                self.out.write_str("<!ENTITY ")?;
+                if is_parameter_entity(name) {
+                    self.out.write_str("% ")?;
+                }
+
+                write!(self.out, "{} ", name)?;
+                match definition {
+                    EntityDefinition::EntityValue(value) => self.write_quoted(value)?,
+                    EntityDefinition::ExternalId(id) => self.write_external_id(id)?,
+                }
+
+                self.out.write_char('>')
+            }
+            Token::ElementDeclaration(name, spec) => {
+                write!(self.out, "<!ELEMENT {} {}>", name, spec)
+            }
+            Token::AttlistDeclaration(element, definitions) => {
+                self.write_attlist(element, definitions)
+            }
+            Token::NotationDeclaration(name, public_id, system_id) => {
+                write!(self.out, "<!NOTATION {} ", name)?;
+                match public_id {
+                    Some(public_id) => {
+                        self.out.write_str("PUBLIC ")?;
+                        self.write_quoted(public_id)?;
+                        if let Some(system_id) = system_id {
+                            self.out.write_char(' ')?;
+                            self.write_quoted(system_id)?;
+                        }
+                    }
+                    None => {
+                        self.out.write_str("SYSTEM ")?;
+                        if let Some(system_id) = system_id {
+                            self.write_quoted(system_id)?;
+                        }
+                    }
+                }
+
+                self.out.write_char('>')
+            }
+            Token::DtdEnd => {
+                self.out.write_str("]>")
+            }
+            Token::ElementStart(prefix, local) => {
+                self.out.write_char('<')?;
+                self.write_qname(prefix.to_str(), local.to_str())
+            }
+            Token::Attribute((prefix, local), value, _) => {
+                self.out.write_char(' ')?;
+                self.write_qname(prefix.to_str(), local.to_str())?;
+                self.out.write_char('=')?;
+                self.write_quoted(value)
+            }
+            Token::ElementEnd(end) => {
+                match end {
+                    ElementEnd::Open => self.out.write_char('>'),
+                    ElementEnd::Close(prefix, local) => {
+                        self.out.write_str("</")?;
+                        self.write_qname(prefix.to_str(), local.to_str())?;
+                        self.out.write_char('>')
+                    }
+                    ElementEnd::Empty => self.out.write_str("/>"),
+                }
+            }
+            Token::Text(text) | Token::Whitespaces(text) => {
+                self.out.write_str(text.to_str())
+            }
+            Token::Cdata(text) => {
+                write!(self.out, "<![CDATA[{}]]>", text)
+            }
+            Token::DocumentBoundary | Token::EndOfDocument(_) => {
+                Ok(())
+            }
+        }
+    }
+
+    fn write_qname(&mut self, prefix: &str, local: &str) -> fmt::Result {
+        if !prefix.is_empty() {
+            write!(self.out, "{}:", prefix)?;
+        }
+
+        self.out.write_str(local)
+    }
+
+    fn write_quoted(&mut self, value: StrSpan) -> fmt::Result {
+        let quote = original_quote(value);
+        write!(self.out, "{}{}{}", quote, value, quote)
+    }
+
+    fn write_doctype(&mut self, name: StrSpan, external_id: Option<ExternalId>) -> fmt::Result {
+        write!(self.out, "<!DOCTYPE {}", name)?;
+        if let Some(id) = external_id {
+            self.out.write_char(' ')?;
+            self.write_external_id(id)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_external_id(&mut self, id: ExternalId) -> fmt::Result {
+        match id {
+            ExternalId::System(system) => {
+                self.out.write_str("SYSTEM ")?;
+                self.write_quoted(system)
+            }
+            ExternalId::Public(public, system) => {
+                self.out.write_str("PUBLIC ")?;
+                self.write_quoted(public)?;
+                self.out.write_char(' ')?;
+                self.write_quoted(system)
+            }
+        }
+    }
+
+    fn write_attlist(&mut self, element: StrSpan, definitions: AttributeDefinitions) -> fmt::Result {
+        let definitions = definitions.span().trim();
+        if definitions.is_empty() {
+            write!(self.out, "<!ATTLIST {}>", element)
+        } else {
+            write!(self.out, "<!ATTLIST {} {}>", element, definitions)
+        }
+    }
+}
+
+// Returns a quote preceding the value in the original text or `"` by default.
+fn original_quote(value: StrSpan) -> char {
+    let text = value.full_str();
+    match text[..value.start()].chars().last() {
+        Some('\'') => '\'',
+        _ => '"',
+    }
+}
+
+// Parameter entities are declared as `<!ENTITY % name`.
+fn is_parameter_entity(name: StrSpan) -> bool {
+    name.full_str()[..name.start()].trim_end().ends_with('%')
+}
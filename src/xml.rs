@@ -1,10 +1,25 @@
-use std::fmt;
+use alloc::borrow::Cow;
+use core::cmp;
+use core::fmt;
+use core::mem;
+use core::ops::Range;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 
 use super::*;
 
 
-type Result<T> = ::std::result::Result<T, Error>;
-type StreamResult<T> = ::std::result::Result<T, StreamError>;
+type Result<T> = ::core::result::Result<T, Error>;
+type StreamResult<T> = ::core::result::Result<T, StreamError>;
+
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+// A maximum amount of states kept for `seek`.
+const SEEK_POINTS: usize = 8;
+
+pub(crate) const XML_NS: &str = "http://www.w3.org/XML/1998/namespace";
+pub(crate) const XMLNS_NS: &str = "http://www.w3.org/2000/xmlns/";
 
 #[derive(Clone, Copy, PartialEq)]
 enum State {
@@ -17,119 +32,2052 @@ enum State {
 }
 
 
-/// List of token types.
-///
-/// For internal use and errors.
-#[derive(Clone, Copy, PartialEq, Debug)]
-#[allow(missing_docs)]
-pub enum TokenType {
-    XMLDecl,
-    Comment,
-    PI,
-    DoctypeDecl,
-    ElementDecl,
-    AttlistDecl,
-    EntityDecl,
-    NotationDecl,
-    DoctypeEnd,
-    ElementStart,
-    ElementClose,
-    Attribute,
-    CDSect,
-    Whitespace,
-    CharData,
-    Unknown,
-}
+/// List of token types.
+///
+/// For internal use and errors.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub enum TokenType {
+    XMLDecl,
+    Comment,
+    PI,
+    DoctypeDecl,
+    ElementDecl,
+    AttlistDecl,
+    EntityDecl,
+    NotationDecl,
+    DoctypeEnd,
+    ElementStart,
+    ElementClose,
+    Attribute,
+    CDSect,
+    Whitespace,
+    CharData,
+    Unknown,
+}
+
+impl fmt::Display for TokenType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            TokenType::XMLDecl => "XML declaration",
+            TokenType::Comment => "comment",
+            TokenType::PI => "processing instruction",
+            TokenType::DoctypeDecl => "DOCTYPE declaration",
+            TokenType::ElementDecl => "element declaration",
+            TokenType::AttlistDecl => "attribute list declaration",
+            TokenType::EntityDecl => "entity declaration",
+            TokenType::NotationDecl => "notation declaration",
+            TokenType::DoctypeEnd => "DOCTYPE end",
+            TokenType::ElementStart => "element start",
+            TokenType::ElementClose => "element close",
+            TokenType::Attribute => "attribute",
+            TokenType::CDSect => "CDATA",
+            TokenType::Whitespace => "whitespace",
+            TokenType::CharData => "character data",
+            TokenType::Unknown => "unknown",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+
+/// Well-formedness checks state.
+#[derive(Clone)]
+struct StrictState<'a> {
+    open_elements: Vec<(StrSpan<'a>, StrSpan<'a>)>,
+    attributes: Vec<(StrSpan<'a>, StrSpan<'a>)>,
+    has_root: bool,
+}
+
+
+/// IDs uniqueness checks state.
+///
+/// Doesn't borrow the text, so it can be kept in a `Checkpoint`.
+#[derive(Clone)]
+struct IdState {
+    // Element and attribute names of the `ID` attributes declared in the DTD.
+    id_attributes: Vec<(String, String)>,
+    // A normalized value and a position of the attribute.
+    values: Map<String, ErrorPos>,
+    element: String,
+    // Attributes are visited in the text order, so their positions
+    // are calculated incrementally.
+    tracker: LineTracker,
+}
+
+
+/// Attribute types checks state.
+#[derive(Clone)]
+struct AttrTypesState<'a> {
+    // Element name, attribute name and attribute type declared in the DTD.
+    types: Vec<(&'a str, &'a str, StrSpan<'a>)>,
+    element: &'a str,
+}
+
+
+/// Standalone documents checks state.
+#[derive(Clone)]
+struct StandaloneState<'a> {
+    is_standalone: bool,
+    // Names of the general entities declared in the internal subset.
+    entities: Vec<&'a str>,
+}
+
+
+/// Limits checks state.
+#[derive(Clone, Copy)]
+struct LimitsState {
+    limits: Limits,
+    entity_declarations: usize,
+}
+
+
+// Tokenizer options.
+//
+// Kept together, so a tokenizer with the same options can be created for `seek`.
+#[derive(Clone, Copy, Default)]
+struct Config {
+    is_fragment_parsing: bool,
+    is_fragment_declaration: bool,
+    is_multi_document_parsing: bool,
+    is_namespace_well_formed: bool,
+    is_lazy_attributes: bool,
+    is_end_of_document_token: bool,
+    is_dtd_declaration_tokens: bool,
+    is_error_recovery: bool,
+    is_whitespace_text_skipping: bool,
+    strictness: Strictness,
+}
+
+
+/// A tokenizer state that doesn't borrow the text.
+///
+/// Allows to continue parsing after the text was extended.
+///
+/// Includes the options and the states of the `Limits`, `xml:space`,
+/// position tracking and IDs checks. Other checks borrow the text and are not kept.
+#[derive(Clone)]
+pub(crate) struct Checkpoint {
+    pos: usize,
+    state: State,
+    open_elements: usize,
+    // Start and end offsets of the current element prefix and local name.
+    curr_element: (usize, usize, usize, usize),
+    // A position of the text start.
+    origin: ErrorPos,
+    is_xml_1_1: bool,
+    config: Config,
+    limits: Option<LimitsState>,
+    ids: Option<IdState>,
+    xml_space: Option<Vec<XmlSpace>>,
+    line_tracker: Option<LineTracker>,
+}
+
+impl Default for Checkpoint {
+    fn default() -> Self {
+        Tokenizer::from("").into_checkpoint()
+    }
+}
+
+impl Checkpoint {
+    /// Returns an absolute offset of the first byte required to resume the parsing.
+    ///
+    /// The current element name is required while parsing attributes.
+    pub(crate) fn retain_from(&self) -> usize {
+        if self.state == State::Attributes {
+            cmp::min(self.pos, cmp::min(self.curr_element.0, self.curr_element.2))
+        } else {
+            self.pos
+        }
+    }
+
+    /// Returns a position at an absolute `offset` in the `text`.
+    ///
+    /// This operation is expensive, like `Stream::gen_error_pos`.
+    pub(crate) fn pos_at(&self, text: &str, offset: usize) -> ErrorPos {
+        let mut tracker = LineTracker::with_origin(self.origin);
+        tracker.is_xml_1_1 = self.is_xml_1_1;
+        tracker.advance_to(text, offset);
+        tracker.pos()
+    }
+
+    /// Returns a position after the last token.
+    pub(crate) fn current_pos(&self, text: &str) -> ErrorPos {
+        match self.line_tracker {
+            Some(ref tracker) => tracker.pos(),
+            None => self.pos_at(text, self.pos),
+        }
+    }
+
+    /// Returns the `xml:space` value of the current element.
+    pub(crate) fn xml_space(&self) -> XmlSpace {
+        match self.xml_space {
+            Some(ref stack) => stack.last().cloned().unwrap_or(XmlSpace::Default),
+            None => XmlSpace::Default,
+        }
+    }
+
+    /// Moves the checkpoint `n` bytes back after the start of the `text` was removed.
+    ///
+    /// The `text` is the text before the removal.
+    /// `n` must not be bigger than `retain_from()`.
+    pub(crate) fn shift_back(&mut self, text: &str, n: usize) {
+        debug_assert!(n <= self.retain_from());
+
+        self.origin = self.pos_at(text, n);
+        if let Some(ref mut tracker) = self.line_tracker {
+            tracker.shift_back(text, n);
+        }
+
+        if let Some(ref mut ids) = self.ids {
+            ids.tracker.shift_back(text, n);
+        }
+
+        let (prefix_start, prefix_end, local_start, local_end) = self.curr_element;
+        self.pos -= n;
+        self.curr_element = if self.state == State::Attributes {
+            (prefix_start - n, prefix_end - n, local_start - n, local_end - n)
+        } else {
+            (0, 0, 0, 0)
+        };
+    }
+}
+
+
+/// A namespace declaration.
+///
+/// Returned by [`Tokenizer::namespace_declarations`].
+///
+/// [`Tokenizer::namespace_declarations`]: struct.Tokenizer.html#method.namespace_declarations
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct NamespaceDeclaration<'a> {
+    /// A declared prefix.
+    ///
+    /// `None` for the default namespace.
+    pub prefix: Option<StrSpan<'a>>,
+    /// A namespace name, as is.
+    pub uri: StrSpan<'a>,
+}
+
+
+/// Tokenizer limits.
+///
+/// Used by [`Tokenizer::set_limits`].
+///
+/// All limits are disabled by default.
+///
+/// [`Tokenizer::set_limits`]: struct.Tokenizer.html#method.set_limits
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Limits {
+    /// A maximum element nesting depth. The root element is at the depth 1.
+    pub max_depth: usize,
+    /// A maximum attribute value length in bytes.
+    pub max_attribute_value_len: usize,
+    /// A maximum amount of entity declarations in a document.
+    pub max_entity_declarations: usize,
+    /// A maximum token length in bytes, including delimiters.
+    pub max_token_len: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_depth: usize::MAX,
+            max_attribute_value_len: usize::MAX,
+            max_entity_declarations: usize::MAX,
+            max_token_len: usize::MAX,
+        }
+    }
+}
+
+
+/// Well-formedness relaxations.
+///
+/// Used by [`Tokenizer::set_strictness`].
+///
+/// Nothing is relaxed by default.
+///
+/// [`Tokenizer::set_strictness`]: struct.Tokenizer.html#method.set_strictness
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Strictness {
+    /// Parses documents with `version="1.1"` according to XML 1.1.
+    ///
+    /// NEL and LS are treated as line breaks in positions.
+    /// Allows character references to the C0 control characters,
+    /// except NUL, like `&#x1;`. Such references are checked in entity values,
+    /// and in text and attribute values in the strict mode.
+    pub xml_1_1: bool,
+    /// Allows `--` inside comments.
+    pub allow_double_hyphen_in_comments: bool,
+    /// Allows duplicated attributes in the strict mode.
+    pub allow_duplicated_attributes: bool,
+    /// Allows C0 control characters, except NUL, in text, CDATA and attribute values
+    /// in the strict mode.
+    pub allow_control_chars: bool,
+}
+
+
+/// A limit kind.
+///
+/// Used by the `LimitExceeded` error.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub enum Limit {
+    Depth,
+    AttributeValueLength,
+    EntityDeclarations,
+    TokenLength,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            Limit::Depth => "nesting depth",
+            Limit::AttributeValueLength => "attribute value length",
+            Limit::EntityDeclarations => "entity declarations",
+            Limit::TokenLength => "token length",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+
+/// A document prolog.
+///
+/// Returned by [`Tokenizer::parse_prolog`].
+///
+/// [`Tokenizer::parse_prolog`]: struct.Tokenizer.html#method.parse_prolog
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Prolog<'a> {
+    /// A version from the XML declaration.
+    ///
+    /// `None` when there is no declaration.
+    pub version: Option<StrSpan<'a>>,
+    /// An encoding from the XML declaration.
+    pub encoding: Option<StrSpan<'a>>,
+    /// A standalone value from the XML declaration.
+    pub standalone: Option<StrSpan<'a>>,
+    /// A DOCTYPE name.
+    pub doctype: Option<StrSpan<'a>>,
+    /// A DOCTYPE external id.
+    pub external_id: Option<ExternalId<'a>>,
+    /// Indicates that the DOCTYPE has an internal subset.
+    pub has_internal_subset: bool,
+    /// An absolute offset of the root element start.
+    ///
+    /// `None` when there is no root element.
+    pub root_start: Option<usize>,
+}
+
+
+/// Tokenizer for the XML structure.
+pub struct Tokenizer<'a> {
+    stream: Stream<'a>,
+    state: State,
+    open_elements: usize,
+    token_depth: usize,
+    // The `is_balanced()` value before the end of parsing.
+    is_balanced_at_end: bool,
+    error_offset: usize,
+    curr_element: (StrSpan<'a>, StrSpan<'a>),
+    attributes_count: usize,
+    config: Config,
+    has_recovered_errors: bool,
+    attributes: StrSpan<'a>,
+    interner: Option<Interner<'a>>,
+    name_id: Option<NameId>,
+    strict: Option<StrictState<'a>>,
+    ids: Option<IdState>,
+    attr_types: Option<AttrTypesState<'a>>,
+    standalone: Option<StandaloneState<'a>>,
+    limits: Option<LimitsState>,
+    ns_declarations: Option<Vec<NamespaceDeclaration<'a>>>,
+    xml_space: Option<Vec<XmlSpace>>,
+    warnings: Option<Vec<Warning>>,
+    line_tracker: Option<LineTracker>,
+    token_start: usize,
+    // The source region of the last token, including the preceding skipped spaces.
+    verbatim: (usize, usize),
+    // More data can be appended to the text. Used by `PushTokenizer`.
+    is_partial_input: bool,
+    // States left by `seek`, so seeking back to a nearby offset doesn't require
+    // parsing from the text start.
+    seek_points: Vec<Tokenizer<'a>>,
+}
+
+impl<'a> From<&'a str> for Tokenizer<'a> {
+    fn from(text: &'a str) -> Self {
+        Self::from(StrSpan::from(text))
+    }
+}
+
+impl<'a> From<StrSpan<'a>> for Tokenizer<'a> {
+    fn from(span: StrSpan<'a>) -> Self {
+        Tokenizer {
+            stream: Stream::from(span),
+            state: State::Document,
+            open_elements: 0,
+            token_depth: 0,
+            is_balanced_at_end: false,
+            error_offset: 0,
+            curr_element: (StrSpan::from(""), StrSpan::from("")),
+            attributes_count: 0,
+            config: Config::default(),
+            has_recovered_errors: false,
+            attributes: StrSpan::from(""),
+            interner: None,
+            name_id: None,
+            strict: None,
+            ids: None,
+            attr_types: None,
+            standalone: None,
+            limits: None,
+            ns_declarations: None,
+            xml_space: None,
+            warnings: None,
+            line_tracker: None,
+            token_start: 0,
+            verbatim: (0, 0),
+            is_partial_input: false,
+            seek_points: Vec::new(),
+        }
+    }
+}
+
+
+/// Shorthand for:
+///
+/// ```text
+/// let start = stream.pos() - 2; // or any other number
+/// some_func().map_err(|e|
+///     Error::InvalidToken(Token::SomeToken, stream.gen_error_pos_from(start), Some(e))
+/// )
+/// ```
+macro_rules! map_err_at {
+    ($fun:expr, $token:expr, $stream:expr, $d:expr) => {{
+        let mut start = $stream.pos() as isize + $d;
+        debug_assert!(start >= 0);
+        if start < 0 { start = 0; }
+        $fun.map_err(|e|
+            Error::InvalidToken($token, $stream.gen_error_pos_from(start as usize), Some(e))
+        )
+    }}
+}
+
+impl<'a> Tokenizer<'a> {
+    /// Creates a tokenizer from a raw document.
+    ///
+    /// The encoding is detected and the text is decoded using [`decode_bytes`].
+    /// A UTF-8 document is borrowed as is, while other encodings are decoded
+    /// into the `buffer`, so token positions are relative to the decoded text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Token};
+    ///
+    /// let mut buffer = String::new();
+    /// let mut p = Tokenizer::from_bytes(b"\xFF\xFE<\0a\0/\0>\0", &mut buffer).unwrap();
+    /// match p.next().unwrap().unwrap() {
+    ///     Token::ElementStart(_, local) => assert_eq!(local.to_str(), "a"),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    ///
+    /// [`decode_bytes`]: fn.decode_bytes.html
+    pub fn from_bytes(bytes: &'a [u8], buffer: &'a mut String) -> Result<Self> {
+        match decode_bytes(bytes)? {
+            Cow::Borrowed(text) => Ok(Tokenizer::from(text)),
+            Cow::Owned(text) => {
+                *buffer = text;
+                let buffer: &'a String = buffer;
+                Ok(Tokenizer::from(buffer.as_str()))
+            }
+        }
+    }
+
+    /// Creates a tokenizer for a fragment inside the `text`.
+    ///
+    /// The same as [`set_fragment_mode`] on a `range` of the `text`,
+    /// but token spans and error positions are relative to the whole `text`.
+    /// Useful for parsing an element content or an external parsed entity
+    /// inside a larger document.
+    ///
+    /// Each construct is still validated, while element balance
+    /// is checked only within the fragment and only in the strict mode.
+    ///
+    /// Returns `None` when the `range` is out of bounds
+    /// or doesn't lie on char boundaries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Token};
+    ///
+    /// let text = "<msg><a/>text<b/></msg>";
+    /// let mut p = Tokenizer::from_fragment(text, 5..17).unwrap();
+    /// match p.next().unwrap().unwrap() {
+    ///     Token::ElementStart(_, local) => assert_eq!(local.start(), 6),
+    ///     _ => unreachable!(),
+    /// }
+    /// assert_eq!(p.count(), 4);
+    /// ```
+    ///
+    /// [`set_fragment_mode`]: #method.set_fragment_mode
+    pub fn from_fragment(text: &'a str, range: Range<usize>) -> Option<Self> {
+        let span = StrSpan::from(text).checked_slice_region(range.start, range.end)?;
+        let mut p = Tokenizer::from(span);
+        p.set_fragment_mode();
+        Some(p)
+    }
+
+    /// Enables document fragment parsing.
+    ///
+    /// By default, `xmlparser` will check for DTD, root element, etc.
+    /// But if we have to parse an XML fragment, it will lead to an error.
+    /// This method switch the parser to the root element content parsing mode.
+    /// So it will treat any data as a content of the root element.
+    ///
+    /// See also [`from_fragment`].
+    ///
+    /// [`from_fragment`]: #method.from_fragment
+    pub fn set_fragment_mode(&mut self) {
+        self.state = State::Elements;
+        self.config.is_fragment_parsing = true;
+    }
+
+    /// Allows an XML declaration at the start of a fragment.
+    ///
+    /// By default, a fragment starting with `<?xml ...?>` results in
+    /// the `UnexpectedToken` error, since the reserved `xml` target is allowed
+    /// only at the start of a document. In this mode, it is parsed as a `Declaration`.
+    ///
+    /// Has an effect only in the fragment mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Token};
+    ///
+    /// let mut p = Tokenizer::from("<?xml version='1.0'?><a/>");
+    /// p.set_fragment_mode();
+    /// p.enable_fragment_declaration();
+    /// assert!(matches!(p.next(), Some(Ok(Token::Declaration(_, _, _)))));
+    /// ```
+    pub fn enable_fragment_declaration(&mut self) {
+        self.config.is_fragment_declaration = true;
+    }
+
+    /// Enables lazy attributes parsing.
+    ///
+    /// In this mode, `Attribute` tokens are not emitted. Instead, the attributes
+    /// region of the current element is skipped, so `ElementStart` is followed
+    /// by `ElementEnd` and attributes can be parsed on demand via [`attributes()`].
+    ///
+    /// The skipped region is still validated before the `ElementEnd` token,
+    /// which also runs the strict mode, IDs, attribute types, namespaces,
+    /// limits and standalone checks for each attribute. So an invalid attribute
+    /// is reported the same way as without this mode.
+    ///
+    /// [`attributes()`]: #method.attributes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Token, ElementEnd};
+    ///
+    /// let mut p = Tokenizer::from("<svg width='10' height='20'/>");
+    /// p.set_lazy_attributes_mode();
+    /// assert!(matches!(p.next(), Some(Ok(Token::ElementStart(_, _)))));
+    ///
+    /// let mut attrs = p.attributes();
+    /// match attrs.next() {
+    ///     Some(Ok(Token::Attribute((_, name), value, _))) => {
+    ///         assert_eq!(name.to_str(), "width");
+    ///         assert_eq!(value.to_str(), "10");
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    ///
+    /// assert!(matches!(p.next(), Some(Ok(Token::ElementEnd(ElementEnd::Empty)))));
+    /// ```
+    pub fn set_lazy_attributes_mode(&mut self) {
+        self.config.is_lazy_attributes = true;
+    }
+
+    /// Returns attributes of the current element.
+    ///
+    /// Available only in the lazy attributes mode, between the `ElementStart`
+    /// and `ElementEnd` tokens. Otherwise, an empty iterator is returned.
+    pub fn attributes(&self) -> Attributes<'a> {
+        Attributes { stream: self.stream.sub_stream(self.attributes), index: 0 }
+    }
+
+    /// Enables the error recovery mode.
+    ///
+    /// By default, parsing stops after the first error.
+    /// In this mode, the tokenizer returns an error, skips to the next `<`
+    /// and continues. An unterminated token is skipped up to the next `<` after its start.
+    /// An element with invalid attributes is treated as an open one.
+    /// Errors from the optional checks don't skip anything,
+    /// since the token itself was parsed.
+    ///
+    /// `is_balanced()` returns `false` at the end when any error occurred.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Token};
+    ///
+    /// let mut p = Tokenizer::from("<a><b =/><c/></a>");
+    /// p.enable_error_recovery();
+    /// let errors = p.filter(|t| t.is_err()).count();
+    /// assert_eq!(errors, 1);
+    /// ```
+    pub fn enable_error_recovery(&mut self) {
+        self.config.is_error_recovery = true;
+    }
+
+    /// Skips `Whitespaces` tokens.
+    ///
+    /// Whitespaces inside an `xml:space="preserve"` scope are still returned
+    /// when `xml:space` scopes tracking is enabled. Whitespaces with character references,
+    /// like `&#x20;`, are never skipped, since such spaces are a part of the content.
+    ///
+    /// Unlike [`EventOptions::coalesce_text`], adjacent text and CDATA are not merged,
+    /// since a token can reference only a single region of the original text.
+    ///
+    /// [`EventOptions::coalesce_text`]: struct.EventOptions.html#structfield.coalesce_text
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Token};
+    ///
+    /// let mut p = Tokenizer::from("<a>\n  <b/>\n</a>");
+    /// p.skip_whitespace_text();
+    /// assert_eq!(p.count(), 5);
+    /// ```
+    pub fn skip_whitespace_text(&mut self) {
+        self.config.is_whitespace_text_skipping = true;
+    }
+
+    /// Enables the `EndOfDocument` token.
+    ///
+    /// When enabled, the `EndOfDocument` token is emitted once,
+    /// after the last token. It's not emitted after an error,
+    /// unless the error recovery is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Token};
+    ///
+    /// let mut p = Tokenizer::from("<a/>");
+    /// p.enable_end_of_document_token();
+    /// let token = p.last().unwrap().unwrap();
+    /// assert_eq!(token, Token::EndOfDocument(4));
+    /// ```
+    pub fn enable_end_of_document_token(&mut self) {
+        self.config.is_end_of_document_token = true;
+    }
+
+    /// Enables `ElementDeclaration`, `AttlistDeclaration`
+    /// and `NotationDeclaration` tokens.
+    ///
+    /// By default, these declarations are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Token};
+    ///
+    /// let mut p = Tokenizer::from("<!DOCTYPE a [<!ELEMENT a (#PCDATA)>]><a/>");
+    /// p.enable_dtd_declaration_tokens();
+    /// p.next(); // DtdStart
+    /// match p.next().unwrap().unwrap() {
+    ///     Token::ElementDeclaration(name, spec) => {
+    ///         assert_eq!(name.to_str(), "a");
+    ///         assert_eq!(spec.to_str(), "(#PCDATA)");
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn enable_dtd_declaration_tokens(&mut self) {
+        self.config.is_dtd_declaration_tokens = true;
+    }
+
+    /// Enables multiple documents parsing.
+    ///
+    /// By default, any data after the root element, except comments,
+    /// processing instructions and whitespaces, will lead to an error.
+    /// In this mode, such data will be parsed as a new document,
+    /// which may have its own declaration and DTD.
+    ///
+    /// Documents are separated by the `DocumentBoundary` token.
+    ///
+    /// Unlike the fragment mode, each document must still have a single root element.
+    pub fn set_multi_document_mode(&mut self) {
+        self.config.is_multi_document_parsing = true;
+    }
+
+    /// Enables namespace well-formedness checks.
+    ///
+    /// By default, element and attribute names are parsed according
+    /// to the XML 1.0 `Name` rule, which allows any amount of colons.
+    /// In this mode, names must be valid [QNames](https://www.w3.org/TR/xml-names/#ns-qualnames),
+    /// which means that a name can have only one colon and both parts must not be empty.
+    ///
+    /// Also, namespace declarations are checked against
+    /// [reserved prefixes and namespace names](https://www.w3.org/TR/xml-names/#xmlReserved).
+    /// So `xmlns:xmlns` cannot be declared and the `xml` prefix can be bound only
+    /// to its own namespace.
+    pub fn set_namespace_well_formed_mode(&mut self) {
+        self.config.is_namespace_well_formed = true;
+    }
+
+    /// Returns a start position of the last returned token.
+    ///
+    /// The position is a byte offset in the original text, like in `StrSpan::start()`.
+    ///
+    /// Leading whitespaces that are not a part of the token are not included,
+    /// but a BOM is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::Tokenizer;
+    ///
+    /// let mut p = Tokenizer::from("  <a/>");
+    /// p.next();
+    /// assert_eq!(p.token_start(), 2);
+    /// ```
+    pub fn token_start(&self) -> usize {
+        self.stream.span().start() + self.token_start
+    }
+
+    /// Returns the exact source of the last token or error.
+    ///
+    /// Unlike the token spans, it includes delimiters, like `<!--` and `-->`,
+    /// quotes, and the spaces preceding the token that are not a part of any token,
+    /// like spaces between attributes or outside the root element.
+    /// For an error, the span ends where the error was detected.
+    /// A position of the span can be calculated via [`ErrorPos::from_offset`].
+    ///
+    /// Spans of successfully parsed tokens have no gaps or overlaps.
+    /// Spaces at the end of the document are a part of the `EndOfDocument` token,
+    /// so with [`enable_end_of_document_token()`], the concatenation of all spans
+    /// is equal to the original text.
+    ///
+    /// [`enable_end_of_document_token()`]: #method.enable_end_of_document_token
+    /// [`ErrorPos::from_offset`]: struct.ErrorPos.html#method.from_offset
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::Tokenizer;
+    ///
+    /// let mut p = Tokenizer::from("<a  x = 'y'/>");
+    /// p.next(); // ElementStart
+    /// assert_eq!(p.verbatim_span().to_str(), "<a");
+    /// p.next(); // Attribute
+    /// assert_eq!(p.verbatim_span().to_str(), "  x = 'y'");
+    /// p.next(); // ElementEnd
+    /// assert_eq!(p.verbatim_span().to_str(), "/>");
+    /// ```
+    pub fn verbatim_span(&self) -> StrSpan<'a> {
+        self.stream.span().slice_region(self.verbatim.0, self.verbatim.1)
+    }
+
+    /// Returns the nesting depth of the last token.
+    ///
+    /// The root element tags and tokens outside of it are at the depth 0,
+    /// the root element content is at the depth 1 and so on.
+    /// The open and close tags of an element are at the same depth
+    /// as the element itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::Tokenizer;
+    ///
+    /// let mut p = Tokenizer::from("<a><b/>text</a>");
+    /// let mut depths = Vec::new();
+    /// while p.next().is_some() {
+    ///     depths.push(p.depth());
+    /// }
+    /// assert_eq!(depths, vec![0, 0, 1, 1, 1, 0]);
+    /// ```
+    pub fn depth(&self) -> usize {
+        self.token_depth
+    }
+
+    /// Checks that all opened elements are closed.
+    ///
+    /// Returns `true` after the root element end, or when there are no open elements
+    /// in the fragment mode. Returns `false` inside a start tag and after an error.
+    ///
+    /// Unlike the strict mode, close tags are not matched with the open ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::Tokenizer;
+    ///
+    /// let mut p = Tokenizer::from("<a><b/></a>");
+    /// p.next(); // ElementStart
+    /// p.next(); // ElementEnd::Open
+    /// assert!(!p.is_balanced());
+    /// p.next(); // ElementStart
+    /// p.next(); // ElementEnd::Empty
+    /// p.next(); // ElementEnd::Close
+    /// assert!(p.is_balanced());
+    /// ```
+    pub fn is_balanced(&self) -> bool {
+        match self.state {
+            State::AfterElements => true,
+            State::Elements => self.config.is_fragment_parsing && self.open_elements == 0,
+            State::Finished => self.is_balanced_at_end,
+            _ => false,
+        }
+    }
+
+    /// Parses the document prolog.
+    ///
+    /// Parsing stops at the root element start, so the rest
+    /// of the document is not checked. Use [`seek()`] with `root_start`
+    /// to continue from the root element.
+    ///
+    /// [`seek()`]: #method.seek
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::Tokenizer;
+    ///
+    /// let text = "<?xml version='1.0' encoding='UTF-8'?>\n<!DOCTYPE svg>\n<svg/>";
+    /// let prolog = Tokenizer::parse_prolog(text).unwrap();
+    /// assert_eq!(prolog.encoding.unwrap().to_str(), "UTF-8");
+    /// assert_eq!(prolog.doctype.unwrap().to_str(), "svg");
+    /// assert_eq!(prolog.root_start, Some(54));
+    /// ```
+    pub fn parse_prolog(text: &'a str) -> Result<Prolog<'a>> {
+        let mut prolog = Prolog::default();
+
+        let mut p = Tokenizer::from(text);
+        while let Some(token) = p.next() {
+            match token? {
+                Token::Declaration(version, encoding, standalone) => {
+                    prolog.version = Some(version);
+                    prolog.encoding = encoding;
+                    prolog.standalone = standalone;
+                }
+                Token::DtdStart(name, id) | Token::EmptyDtd(name, id) => {
+                    prolog.doctype = Some(name);
+                    prolog.external_id = id;
+                    prolog.has_internal_subset = p.state == State::Dtd;
+                }
+                Token::ElementStart(..) => {
+                    prolog.root_start = Some(p.token_start());
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(prolog)
+    }
+
+    /// Returns the unparsed part of the text.
+    ///
+    /// After an error, an empty string is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::Tokenizer;
+    ///
+    /// let mut p = Tokenizer::from("<a/>text");
+    /// p.set_fragment_mode();
+    /// p.next(); // ElementStart
+    /// p.next(); // ElementEnd::Empty
+    /// assert_eq!(p.remaining(), "text");
+    /// ```
+    pub fn remaining(&self) -> &'a str {
+        self.stream.slice_tail().to_str()
+    }
+
+    /// Creates a tokenizer that continues from the checkpoint.
+    ///
+    /// The `text` must start with the text the checkpoint was made from.
+    pub(crate) fn from_checkpoint(text: &'a str, checkpoint: Checkpoint) -> Self {
+        let (prefix_start, prefix_end, local_start, local_end) = checkpoint.curr_element;
+
+        let mut p = Tokenizer::from(text);
+        p.stream.set_pos(checkpoint.pos);
+        p.stream.set_origin(checkpoint.origin);
+        p.stream.set_xml_1_1(checkpoint.is_xml_1_1);
+        p.state = checkpoint.state;
+        p.open_elements = checkpoint.open_elements;
+        p.curr_element = (
+            StrSpan::from_substr(text, prefix_start, prefix_end),
+            StrSpan::from_substr(text, local_start, local_end),
+        );
+        p.config = checkpoint.config;
+        p.limits = checkpoint.limits;
+        p.ids = checkpoint.ids;
+        p.xml_space = checkpoint.xml_space;
+        p.line_tracker = checkpoint.line_tracker;
+        p
+    }
+
+    /// Returns the current state.
+    ///
+    /// Must not be called after an error.
+    pub(crate) fn into_checkpoint(self) -> Checkpoint {
+        let (prefix, local) = self.curr_element;
+        Checkpoint {
+            pos: self.stream.pos(),
+            state: self.state,
+            open_elements: self.open_elements,
+            curr_element: (prefix.start(), prefix.end(), local.start(), local.end()),
+            origin: self.stream.origin(),
+            is_xml_1_1: self.stream.is_xml_1_1(),
+            config: self.config,
+            limits: self.limits,
+            ids: self.ids,
+            xml_space: self.xml_space,
+            line_tracker: self.line_tracker,
+        }
+    }
+
+    /// Marks that more data can be appended to the text.
+    ///
+    /// In this mode, `next()` returns `None` when the next token may be incomplete,
+    /// leaving the state unchanged. Parsing errors are treated the same way,
+    /// since a truncated token cannot be distinguished from an invalid one.
+    pub(crate) fn set_partial_input(&mut self, is_partial: bool) {
+        self.is_partial_input = is_partial;
+    }
+
+    /// Checks that all tokens were returned.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.state == State::Finished
+    }
+
+    /// Moves the tokenizer to the token at the specified offset.
+    ///
+    /// The offset is a byte offset in the original text, like the one
+    /// returned by [`token_start()`]. It must point to a token start
+    /// or to the end of the text and must be reachable without errors,
+    /// unless the error recovery is enabled. Otherwise, the `InvalidSeekOffset` error
+    /// will be returned and the tokenizer will remain unchanged.
+    ///
+    /// The document is re-parsed up to the offset with the same options,
+    /// so all the enabled checks stay consistent. The parsing starts from
+    /// the current state or from one of the states left by the previous seeks,
+    /// so seeking back to a nearby offset doesn't require parsing the whole text again.
+    ///
+    /// [`token_start()`]: #method.token_start
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::Tokenizer;
+    ///
+    /// let mut p = Tokenizer::from("<a><b/></a>");
+    /// p.next(); // ElementStart
+    /// p.next(); // ElementEnd::Open
+    /// let token = p.next().unwrap().unwrap(); // ElementStart
+    /// let start = p.token_start();
+    /// p.next(); // ElementEnd::Empty
+    ///
+    /// p.seek(start).unwrap();
+    /// assert_eq!(p.next().unwrap().unwrap(), token);
+    /// assert!(p.seek(start + 1).is_err());
+    /// ```
+    pub fn seek(&mut self, offset: usize) -> Result<()> {
+        let span = self.stream.span();
+        let invalid_offset = || {
+            let offset = cmp::min(cmp::max(offset, span.start()), span.end());
+            Error::InvalidSeekOffset(gen_pos_at(&self.stream, offset))
+        };
+
+        if offset < span.start() || offset > span.end() {
+            return Err(invalid_offset());
+        }
+
+        let offset = offset - span.start();
+
+        // Start from the closest state before the offset.
+        let point = self.seek_points.iter()
+            .chain(core::iter::once(&*self))
+            .filter(|p| p.state != State::Finished && p.stream.pos() <= offset)
+            .max_by_key(|p| p.stream.pos());
+        let mut p = match point {
+            Some(point) => point.snapshot(),
+            None => {
+                let mut p = self.snapshot();
+                p.restart();
+                p
+            }
+        };
+
+        // Find out how many tokens precede the offset.
+        let mut probe = p.snapshot();
+        let mut count = 0;
+        loop {
+            match probe.next() {
+                Some(Ok(_)) if probe.token_start < offset => {
+                    count += 1;
+                }
+                Some(Ok(_)) if probe.token_start == offset => {
+                    break;
+                }
+                Some(Err(_)) if probe.config.is_error_recovery => {
+                    count += 1;
+                }
+                None if probe.stream.at_end() && offset == span.len() => {
+                    break;
+                }
+                _ => {
+                    return Err(invalid_offset());
+                }
+            }
+        }
+
+        // Names were already interned, so the interner can be shared.
+        p.interner = self.interner.take();
+        for _ in 0..count {
+            // The same tokens were already parsed, so the results are known.
+            let _ = p.next();
+        }
+
+        let mut prev = mem::replace(self, p);
+        self.seek_points = mem::take(&mut prev.seek_points);
+        if prev.state != State::Finished {
+            if self.seek_points.len() == SEEK_POINTS {
+                self.seek_points.remove(0);
+            }
+
+            self.seek_points.push(prev);
+        }
+
+        Ok(())
+    }
+
+    // Copies the parsing state, except the interner and the seek points.
+    fn snapshot(&self) -> Tokenizer<'a> {
+        Tokenizer {
+            stream: self.stream,
+            state: self.state,
+            open_elements: self.open_elements,
+            token_depth: self.token_depth,
+            is_balanced_at_end: self.is_balanced_at_end,
+            error_offset: self.error_offset,
+            curr_element: self.curr_element,
+            attributes_count: self.attributes_count,
+            config: self.config,
+            has_recovered_errors: self.has_recovered_errors,
+            attributes: self.attributes,
+            interner: None,
+            name_id: self.name_id,
+            strict: self.strict.clone(),
+            ids: self.ids.clone(),
+            attr_types: self.attr_types.clone(),
+            standalone: self.standalone.clone(),
+            limits: self.limits,
+            ns_declarations: self.ns_declarations.clone(),
+            xml_space: self.xml_space.clone(),
+            warnings: self.warnings.clone(),
+            line_tracker: self.line_tracker,
+            token_start: self.token_start,
+            verbatim: self.verbatim,
+            is_partial_input: self.is_partial_input,
+            seek_points: Vec::new(),
+        }
+    }
+
+    // Resets the parsing state to the text start, keeping the options.
+    fn restart(&mut self) {
+        self.reset_document();
+        self.state = self.initial_state();
+        self.stream.set_pos(0);
+        self.token_depth = 0;
+        self.is_balanced_at_end = false;
+        self.error_offset = 0;
+        self.curr_element = (StrSpan::from(""), StrSpan::from(""));
+        self.attributes_count = 0;
+        self.has_recovered_errors = false;
+        self.attributes = StrSpan::from("");
+        self.name_id = None;
+        self.token_start = 0;
+        self.verbatim = (0, 0);
+
+        if let Some(ref mut ids) = self.ids {
+            ids.tracker = self.stream.line_tracker();
+        }
+
+        if let Some(ref mut tracker) = self.line_tracker {
+            *tracker = self.stream.line_tracker();
+        }
+
+        if let Some(ref mut warnings) = self.warnings {
+            warnings.clear();
+        }
+    }
+
+    /// Collects all remaining tokens into the provided buffer.
+    ///
+    /// The buffer is cleared first, so it can be reused between documents
+    /// to avoid allocations.
+    ///
+    /// Returns the first error. Tokens before the error are kept in the buffer.
+    pub fn tokenize_into(&mut self, out: &mut Vec<Token<'a>>) -> Result<()> {
+        out.clear();
+        for token in self {
+            out.push(token?);
+        }
+
+        Ok(())
+    }
+
+    /// Returns an iterator over tokens with their start positions.
+    ///
+    /// Unlike calling `Stream::gen_error_pos_from` for each token,
+    /// positions are calculated incrementally.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, ErrorPos};
+    ///
+    /// let mut p = Tokenizer::from("<a>\n<b/></a>").with_positions();
+    /// p.next(); // ElementStart
+    /// p.next(); // ElementEnd::Open
+    /// p.next(); // Whitespaces
+    /// let (_, pos) = p.next().unwrap().unwrap();
+    /// assert_eq!(pos, ErrorPos::new(2, 1));
+    /// ```
+    pub fn with_positions(self) -> WithPositions<'a> {
+        WithPositions {
+            tokenizer: self,
+            tracker: LineTracker::new(),
+        }
+    }
+
+    /// Returns an iterator over tokens that end before the byte budget.
+    ///
+    /// `n` is a byte offset in the original text, like in `StrSpan::start()`.
+    /// The iterator stops at the first token that doesn't fit into the budget,
+    /// so partial tokens are never returned. An error is returned only if
+    /// the invalid token starts before the budget.
+    ///
+    /// The parsing can be resumed by re-creating the tokenizer and calling [`seek()`]
+    /// with [`TakeBytes::consumed`].
+    ///
+    /// [`seek()`]: #method.seek
+    /// [`TakeBytes::consumed`]: struct.TakeBytes.html#method.consumed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::Tokenizer;
+    ///
+    /// let text = "<a>text</a>";
+    /// let mut p = Tokenizer::from(text).take_bytes(5);
+    /// assert_eq!(p.by_ref().count(), 2); // ElementStart and ElementEnd::Open
+    /// assert_eq!(p.consumed(), 3);
+    /// ```
+    pub fn take_bytes(self, n: usize) -> TakeBytes<'a> {
+        let consumed = self.stream.span().start() + self.stream.pos();
+        TakeBytes {
+            tokenizer: self,
+            budget: n,
+            consumed,
+            is_finished: false,
+        }
+    }
+
+    /// Enables well-formedness checks.
+    ///
+    /// By default, `xmlparser` doesn't check that the elements tree is balanced,
+    /// that attributes are unique and that the root element is present.
+    /// This method enables such checks at the cost of heap allocations.
+    ///
+    /// Also, C0 control characters, except tab, CR and LF, are not allowed
+    /// in text, CDATA and attribute values. NUL is never allowed.
+    /// Character references in text and attribute values must refer to allowed characters.
+    ///
+    /// In the fragment mode, the root element is not required,
+    /// but elements must still be balanced.
+    pub fn set_strict_mode(&mut self) {
+        if self.strict.is_none() {
+            self.strict = Some(StrictState {
+                open_elements: Vec::new(),
+                attributes: Vec::new(),
+                has_root: false,
+            });
+        }
+    }
+
+    /// Enables IDs uniqueness checks.
+    ///
+    /// Values of the `xml:id` attributes and of the attributes declared
+    /// with the `ID` type in the DTD must be unique across the document.
+    /// Otherwise, the `DuplicatedId` error will be returned.
+    ///
+    /// Values are compared after whitespaces normalization.
+    ///
+    /// Since all values must be stored, this check is disabled by default.
+    pub fn enable_id_checking(&mut self) {
+        if self.ids.is_none() {
+            self.ids = Some(IdState {
+                id_attributes: Vec::new(),
+                values: Map::new(),
+                element: String::new(),
+                tracker: self.stream.line_tracker(),
+            });
+        }
+    }
+
+    /// Enables attribute values checks against the DTD.
+    ///
+    /// Values of the attributes declared in the `ATTLIST` with
+    /// an enumeration, `NOTATION`, `ID`, `IDREF`, `IDREFS`, `ENTITY`, `ENTITIES`,
+    /// `NMTOKEN` or `NMTOKENS` type must match this type.
+    /// Otherwise, the `InvalidAttributeValue` error will be returned.
+    ///
+    /// Values are checked after whitespaces normalization, but references are not resolved.
+    /// So values with references are not checked.
+    ///
+    /// Only the internal subset is used.
+    pub fn enable_attribute_type_checking(&mut self) {
+        if self.attr_types.is_none() {
+            self.attr_types = Some(AttrTypesState {
+                types: Vec::new(),
+                element: "",
+            });
+        }
+    }
+
+    /// Sets tokenizer limits.
+    ///
+    /// Useful for untrusted input. When a limit is exceeded,
+    /// the `LimitExceeded` error will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Limits};
+    ///
+    /// let mut p = Tokenizer::from("<a><b><c/></b></a>");
+    /// p.set_limits(Limits { max_depth: 2, ..Limits::default() });
+    /// let err = p.filter_map(|t| t.err()).next().unwrap();
+    /// assert_eq!(err.to_string(), "nesting depth limit exceeded at 1:7");
+    /// ```
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = Some(LimitsState {
+            limits,
+            entity_declarations: 0,
+        });
+    }
+
+    /// Sets well-formedness relaxations.
+    ///
+    /// Useful for documents from legacy systems.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Strictness};
+    ///
+    /// let mut p = Tokenizer::from("<a><!-- a -- b --></a>");
+    /// p.set_strictness(Strictness { allow_double_hyphen_in_comments: true, ..Strictness::default() });
+    /// assert!(p.all(|t| t.is_ok()));
+    /// ```
+    pub fn set_strictness(&mut self, strictness: Strictness) {
+        self.config.strictness = strictness;
+    }
+
+    /// Enables standalone documents checks.
+    ///
+    /// A document with `standalone="yes"` must not depend on the external
+    /// markup declarations. Since `xmlparser` doesn't read the external subset,
+    /// only entity references are checked: each referenced entity, except the
+    /// predefined ones, must be declared in the internal subset.
+    /// Otherwise, the `UndeclaredEntity` error will be returned.
+    ///
+    /// References in text and attribute values are checked.
+    /// Documents without `standalone="yes"` are not affected.
+    pub fn enable_standalone_checking(&mut self) {
+        if self.standalone.is_none() {
+            self.standalone = Some(StandaloneState {
+                is_standalone: false,
+                entities: Vec::new(),
+            });
+        }
+    }
+
+    /// Enables names interning.
+    ///
+    /// When enabled, qualified names of the `ElementStart`, `Attribute`
+    /// and `ElementEnd::Close` tokens will be interned and the id of the last one
+    /// can be retrieved via [`name_id()`].
+    ///
+    /// [`name_id()`]: #method.name_id
+    pub fn enable_interning(&mut self) {
+        if self.interner.is_none() {
+            self.interner = Some(Interner::new());
+        }
+    }
+
+    /// Returns names interner.
+    ///
+    /// Returns `None` if interning is not enabled.
+    pub fn interner(&self) -> Option<&Interner<'a>> {
+        self.interner.as_ref()
+    }
+
+    /// Returns an interned name id of the last returned token.
+    ///
+    /// Returns `None` if interning is not enabled or the last token has no name.
+    pub fn name_id(&self) -> Option<NameId> {
+        self.name_id
+    }
+
+    /// Enables namespace declarations recording.
+    ///
+    /// When enabled, `xmlns` and `xmlns:*` attributes of the current element
+    /// can be retrieved via [`namespace_declarations()`].
+    ///
+    /// [`namespace_declarations()`]: #method.namespace_declarations
+    pub fn enable_namespace_recording(&mut self) {
+        if self.ns_declarations.is_none() {
+            self.ns_declarations = Some(Vec::new());
+        }
+    }
+
+    /// Enables `xml:space` scopes tracking.
+    ///
+    /// Required by [`xml_space()`] and [`collapse_whitespace()`].
+    ///
+    /// [`xml_space()`]: #method.xml_space
+    /// [`collapse_whitespace()`]: #method.collapse_whitespace
+    pub fn enable_xml_space_tracking(&mut self) {
+        if self.xml_space.is_none() {
+            self.xml_space = Some(Vec::new());
+        }
+    }
+
+    /// Returns the `xml:space` value of the current element.
+    ///
+    /// The value is inherited from the parent element.
+    /// Returns `XmlSpace::Default` if tracking is not enabled.
+    pub fn xml_space(&self) -> XmlSpace {
+        match self.xml_space {
+            Some(ref stack) => stack.last().cloned().unwrap_or(XmlSpace::Default),
+            None => XmlSpace::Default,
+        }
+    }
+
+    /// Collapses whitespaces in a text of the current element.
+    ///
+    /// The same as [`collapse_whitespace`], but the text is returned as is
+    /// inside an `xml:space="preserve"` scope. Requires `xml:space` scopes tracking.
+    ///
+    /// [`collapse_whitespace`]: fn.collapse_whitespace.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Token};
+    ///
+    /// let mut p = Tokenizer::from("<a> 1  2 <b xml:space='preserve'> 3  4 </b></a>");
+    /// p.enable_xml_space_tracking();
+    ///
+    /// let mut texts = Vec::new();
+    /// while let Some(token) = p.next() {
+    ///     if let Token::Text(text) = token.unwrap() {
+    ///         texts.push(p.collapse_whitespace(text));
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(texts, vec!["1 2", " 3  4 "]);
+    /// ```
+    pub fn collapse_whitespace(&self, text: StrSpan<'a>) -> Cow<'a, str> {
+        match self.xml_space() {
+            XmlSpace::Preserve => Cow::Borrowed(text.to_str()),
+            XmlSpace::Default => collapse_whitespace(text.to_str()),
+        }
+    }
+
+    /// Returns namespace declarations of the current element.
+    ///
+    /// Declarations are listed in the document order and are available
+    /// until the next `ElementStart` token. Prefixes are not resolved
+    /// and the same prefix can be listed multiple times.
+    ///
+    /// Returns an empty slice if recording is not enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Token, ElementEnd};
+    ///
+    /// let mut p = Tokenizer::from("<a xmlns='http://a' xmlns:b='http://b'/>");
+    /// p.enable_namespace_recording();
+    /// while let Some(Ok(token)) = p.next() {
+    ///     if let Token::ElementEnd(ElementEnd::Empty) = token {
+    ///         break;
+    ///     }
+    /// }
+    ///
+    /// let decls = p.namespace_declarations();
+    /// assert_eq!(decls.len(), 2);
+    /// assert_eq!(decls[0].prefix, None);
+    /// assert_eq!(decls[1].prefix.unwrap().to_str(), "b");
+    /// assert_eq!(decls[1].uri.to_str(), "http://b");
+    /// ```
+    pub fn namespace_declarations(&self) -> &[NamespaceDeclaration<'a>] {
+        match self.ns_declarations {
+            Some(ref decls) => decls,
+            None => &[],
+        }
+    }
+
+    /// Enables warnings recording.
+    ///
+    /// Warnings can be retrieved via [`warnings()`].
+    ///
+    /// [`warnings()`]: #method.warnings
+    pub fn enable_warnings(&mut self) {
+        if self.warnings.is_none() {
+            self.warnings = Some(Vec::new());
+        }
+    }
+
+    /// Returns all warnings recorded so far.
+    ///
+    /// Returns an empty slice if recording is not enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, Warning, ErrorPos};
+    ///
+    /// let mut p = Tokenizer::from("\u{FEFF}<a/>");
+    /// p.enable_warnings();
+    /// for token in &mut p {
+    ///     token.unwrap();
+    /// }
+    ///
+    /// assert_eq!(p.warnings(), &[Warning::BomStripped(ErrorPos::new(1, 1))]);
+    /// ```
+    pub fn warnings(&self) -> &[Warning] {
+        match self.warnings {
+            Some(ref warnings) => warnings,
+            None => &[],
+        }
+    }
+
+    /// Enables incremental position tracking.
+    ///
+    /// The position is updated after each token, which makes
+    /// [`current_pos()`] cheap.
+    ///
+    /// [`current_pos()`]: #method.current_pos
+    pub fn enable_position_tracking(&mut self) {
+        if self.line_tracker.is_none() {
+            let mut tracker = self.stream.line_tracker();
+            tracker.advance_to(self.stream.span().full_str(), self.current_offset());
+            self.line_tracker = Some(tracker);
+        }
+    }
+
+    /// Returns a position after the last parsed token.
+    ///
+    /// Columns are counted in characters. CRLF and a lone CR are a single line break.
+    /// NEL and LS are line breaks too when a document is parsed as XML 1.1,
+    /// see [`Strictness::xml_1_1`]. Positions are the same with and without tracking.
+    ///
+    /// Takes constant time when position tracking is enabled.
+    /// Otherwise, the position is calculated from the start of the text,
+    /// like for errors.
+    ///
+    /// [`Strictness::xml_1_1`]: struct.Strictness.html#structfield.xml_1_1
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xmlparser::{Tokenizer, ErrorPos};
+    ///
+    /// let mut p = Tokenizer::from("<a>\r\n<b/></a>");
+    /// p.enable_position_tracking();
+    /// p.next(); // ElementStart
+    /// p.next(); // ElementEnd::Open
+    /// p.next(); // Whitespaces
+    /// assert_eq!(p.current_pos(), ErrorPos::new(2, 1));
+    /// ```
+    pub fn current_pos(&self) -> ErrorPos {
+        match self.line_tracker {
+            Some(ref tracker) => tracker.pos(),
+            None => self.stream.gen_error_pos(),
+        }
+    }
+
+    // Runs the recorders and the optional checks.
+    fn process_token(&mut self, token: &Token<'a>) -> Result<()> {
+        self.intern_name(token);
+        self.record_namespace(token);
+        self.record_xml_space(token);
+        self.record_version(token);
+        self.track_position();
+
+        self.check_control_chars(token)
+            .and_then(|_| self.check_char_refs(token))
+            .and_then(|_| self.check_qname(token))
+            .and_then(|_| self.check_token(token))
+            .and_then(|_| self.check_id(token))
+            .and_then(|_| self.check_attribute_type(token))
+            .and_then(|_| self.check_standalone(token))
+            .and_then(|_| self.check_limits(token))
+    }
+
+    // Parses attributes skipped in the lazy attributes mode before the element end,
+    // so they are validated and processed like `Attribute` tokens.
+    fn process_lazy_attributes(&mut self, token: &Token<'a>) -> Result<()> {
+        match *token {
+            Token::ElementEnd(_) if self.config.is_lazy_attributes
+                                 && self.state == State::Attributes => {}
+            _ => return Ok(()),
+        }
+
+        let token_start = self.token_start;
+        let (prefix, local) = self.curr_element;
+        for attr in self.attributes() {
+            let attr = attr.map_err(|e| {
+                Error::InElement(qname(prefix, local).to_string(), Box::new(e))
+            })?;
+
+            if let Token::Attribute((attr_prefix, _), _, _) = attr {
+                self.token_start = attr_prefix.start() - self.stream.span().start();
+            }
+
+            self.process_token(&attr)?;
+        }
+
+        self.token_start = token_start;
+        Ok(())
+    }
+
+    fn current_offset(&self) -> usize {
+        self.stream.span().start() + self.stream.pos()
+    }
+
+    fn record_version(&mut self, token: &Token<'a>) {
+        if let Token::Declaration(version, _, _) = *token {
+            let is_xml_1_1 = self.config.strictness.xml_1_1 && version.to_str() == "1.1";
+            self.stream.set_xml_1_1(is_xml_1_1);
+        }
+    }
+
+    fn track_position(&mut self) {
+        let offset = self.current_offset();
+        let tracker = match self.line_tracker {
+            Some(ref mut tracker) => tracker,
+            None => return,
+        };
+
+        tracker.is_xml_1_1 = self.stream.is_xml_1_1();
+        tracker.advance_to(self.stream.span().full_str(), offset);
+    }
+
+    fn record_xml_space(&mut self, token: &Token<'a>) {
+        let stack = match self.xml_space {
+            Some(ref mut stack) => stack,
+            None => return,
+        };
+
+        match *token {
+            Token::ElementStart(_, _) => {
+                let parent = stack.last().cloned().unwrap_or(XmlSpace::Default);
+                stack.push(parent);
+            }
+            Token::Attribute((prefix, local), value, _)
+                if prefix.to_str() == "xml" && local.to_str() == "space" => {
+                let space = match value.to_str() {
+                    "preserve" => XmlSpace::Preserve,
+                    "default" => XmlSpace::Default,
+                    _ => return,
+                };
+
+                if let Some(last) = stack.last_mut() {
+                    *last = space;
+                }
+            }
+              Token::ElementEnd(ElementEnd::Empty)
+            | Token::ElementEnd(ElementEnd::Close(_, _)) => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn record_namespace(&mut self, token: &Token<'a>) {
+        let decls = match self.ns_declarations {
+            Some(ref mut decls) => decls,
+            None => return,
+        };
+
+        match *token {
+            Token::ElementStart(_, _) => {
+                decls.clear();
+            }
+            Token::Attribute((prefix, local), value, _) => {
+                let prefix = match (prefix.to_str(), local.to_str()) {
+                    ("", "xmlns") => None,
+                    ("xmlns", _) => Some(local),
+                    _ => return,
+                };
+
+                decls.push(NamespaceDeclaration { prefix, uri: value });
+            }
+            _ => {}
+        }
+    }
+
+    fn intern_name(&mut self, token: &Token<'a>) {
+        let interner = match self.interner {
+            Some(ref mut interner) => interner,
+            None => return,
+        };
+
+        self.name_id = match *token {
+              Token::ElementStart(prefix, local)
+            | Token::Attribute((prefix, local), _, _)
+            | Token::ElementEnd(ElementEnd::Close(prefix, local)) => {
+                Some(interner.intern(qname(prefix, local)))
+            }
+            _ => None,
+        };
+    }
+
+    fn initial_state(&self) -> State {
+        if self.config.is_fragment_parsing {
+            State::Elements
+        } else {
+            State::Document
+        }
+    }
+
+    fn reset_document(&mut self) {
+        self.state = State::Document;
+        self.open_elements = 0;
+        self.stream.set_xml_1_1(false);
+
+        if let Some(ref mut strict) = self.strict {
+            strict.open_elements.clear();
+            strict.attributes.clear();
+            strict.has_root = false;
+        }
+
+        if let Some(ref mut ids) = self.ids {
+            ids.id_attributes.clear();
+            ids.values.clear();
+            ids.element.clear();
+        }
+
+        if let Some(ref mut attr_types) = self.attr_types {
+            attr_types.types.clear();
+            attr_types.element = "";
+        }
+
+        if let Some(ref mut standalone) = self.standalone {
+            standalone.is_standalone = false;
+            standalone.entities.clear();
+        }
+
+        if let Some(ref mut limits) = self.limits {
+            limits.entity_declarations = 0;
+        }
+
+        if let Some(ref mut decls) = self.ns_declarations {
+            decls.clear();
+        }
+
+        if let Some(ref mut xml_space) = self.xml_space {
+            xml_space.clear();
+        }
+    }
+
+    fn check_qname(&self, token: &Token<'a>) -> Result<()> {
+        if !self.config.is_namespace_well_formed {
+            return Ok(());
+        }
+
+        let (prefix, local, token_type) = match *token {
+            Token::ElementStart(prefix, local) => {
+                (prefix, local, TokenType::ElementStart)
+            }
+            Token::Attribute((prefix, local), _, _) => {
+                (prefix, local, TokenType::Attribute)
+            }
+            Token::ElementEnd(ElementEnd::Close(prefix, local)) => {
+                (prefix, local, TokenType::ElementClose)
+            }
+            _ => return Ok(()),
+        };
+
+        // An empty prefix points to the name start, so if the local part
+        // doesn't start at the same position, then the name starts with a colon.
+        let is_empty_prefix = prefix.is_empty() && prefix.start() != local.start();
+        if prefix.to_str().contains(':') || is_empty_prefix {
+            let pos = gen_pos_at(&self.stream, self.token_start());
+            return Err(Error::InvalidToken(token_type, pos, Some(StreamError::InvalidName)));
+        }
+
+        if let Token::Attribute(_, value, _) = *token {
+            if !Self::is_valid_ns_declaration(prefix.to_str(), local.to_str(), value.to_str()) {
+                let pos = gen_pos_at(&self.stream, self.token_start());
+                return Err(Error::InvalidNamespaceDeclaration(pos));
+            }
+        }
+
+        Ok(())
+    }
+
+    // https://www.w3.org/TR/xml-names/#xmlReserved
+    //
+    // The value is checked as is, without references resolving.
+    fn is_valid_ns_declaration(prefix: &str, local: &str, value: &str) -> bool {
+        match (prefix, local) {
+            ("", "xmlns") => {
+                // The default namespace can be undeclared.
+                value != XML_NS && value != XMLNS_NS
+            }
+            ("xmlns", "xml") => {
+                value == XML_NS
+            }
+            ("xmlns", "xmlns") => {
+                false
+            }
+            ("xmlns", _) => {
+                // Prefixes cannot be undeclared in XML Namespaces 1.0.
+                !value.is_empty() && value != XML_NS && value != XMLNS_NS
+            }
+            _ => true,
+        }
+    }
+
+    fn check_token(&mut self, token: &Token<'a>) -> Result<()> {
+        let strict = match self.strict {
+            Some(ref mut strict) => strict,
+            None => return Ok(()),
+        };
+
+        match *token {
+            Token::ElementStart(prefix, local) => {
+                strict.open_elements.push((prefix, local));
+                strict.attributes.clear();
+                strict.has_root = true;
+            }
+            Token::Attribute((prefix, local), _, _) => {
+                let name = qname(prefix, local);
+                if !self.config.strictness.allow_duplicated_attributes
+                    && strict.attributes.iter().any(|&(p, l)| qname(p, l) == name)
+                {
+                    let pos = gen_pos_at(&self.stream, prefix.start());
+                    return Err(Error::DuplicatedAttribute(pos));
+                }
+
+                strict.attributes.push((prefix, local));
+            }
+            Token::ElementEnd(ElementEnd::Empty) => {
+                strict.open_elements.pop();
+            }
+            Token::ElementEnd(ElementEnd::Close(prefix, local)) => {
+                let is_matched = match strict.open_elements.pop() {
+                    Some((p, l)) => qname(p, l) == qname(prefix, local),
+                    None => false,
+                };
+
+                if !is_matched {
+                    // Points to `</`.
+                    let pos = gen_pos_at(&self.stream, prefix.start().saturating_sub(2));
+                    return Err(Error::UnexpectedCloseTag(pos));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn check_id(&mut self, token: &Token<'a>) -> Result<()> {
+        let ids = match self.ids {
+            Some(ref mut ids) => ids,
+            None => return Ok(()),
+        };
+
+        match *token {
+            Token::ElementStart(prefix, local) => {
+                ids.element.clear();
+                ids.element.push_str(qname(prefix, local));
+            }
+            Token::Attribute((prefix, local), value, _) => {
+                let name = qname(prefix, local);
+                let element = ids.element.as_str();
+                let is_id = name == "xml:id"
+                    || ids.id_attributes.iter().any(|(e, n)| e == element && n == name);
+
+                if !is_id {
+                    return Ok(());
+                }
+
+                let value = normalize_tokenized_value(value.to_str());
 
-impl fmt::Display for TokenType {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let s = match *self {
-            TokenType::XMLDecl => "Declaration",
-            TokenType::Comment => "Comment",
-            TokenType::PI => "Processing Instruction",
-            TokenType::DoctypeDecl => "Doctype Declaration",
-            TokenType::ElementDecl => "Doctype Element Declaration",
-            TokenType::AttlistDecl => "Doctype Attributes Declaration",
-            TokenType::EntityDecl => "Doctype Entity Declaration",
-            TokenType::NotationDecl => "Doctype Notation Declaration",
-            TokenType::DoctypeEnd => "Doctype End",
-            TokenType::ElementStart => "Element Start",
-            TokenType::ElementClose => "Element Close",
-            TokenType::Attribute => "Attribute",
-            TokenType::CDSect => "CDATA",
-            TokenType::Whitespace => "Whitespace",
-            TokenType::CharData => "Character data",
-            TokenType::Unknown => "Unknown",
+                ids.tracker.is_xml_1_1 = self.stream.is_xml_1_1();
+                ids.tracker.advance_to(self.stream.span().full_str(), prefix.start());
+                let pos = ids.tracker.pos();
+                if let Some(prev_pos) = ids.values.get(&*value) {
+                    return Err(Error::DuplicatedId(pos, *prev_pos));
+                }
+
+                ids.values.insert(value.into_owned(), pos);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn check_attribute_type(&mut self, token: &Token<'a>) -> Result<()> {
+        let attr_types = match self.attr_types {
+            Some(ref mut attr_types) => attr_types,
+            None => return Ok(()),
         };
 
-        write!(f, "{}", s)
+        match *token {
+            Token::ElementStart(prefix, local) => {
+                attr_types.element = qname(prefix, local);
+            }
+            Token::Attribute((prefix, local), value, _) => {
+                let name = qname(prefix, local);
+                let element = attr_types.element;
+                let att_type = attr_types.types.iter()
+                    .find(|&&(e, n, _)| e == element && n == name)
+                    .map(|&(_, _, t)| t);
+
+                let att_type = match att_type {
+                    Some(att_type) => att_type,
+                    None => return Ok(()),
+                };
+
+                let value = value.to_str();
+                if value.contains('&') {
+                    return Ok(());
+                }
+
+                let value = normalize_tokenized_value(value);
+                if !is_valid_attribute_value(att_type.to_str(), &value) {
+                    let pos = gen_pos_at(&self.stream, prefix.start());
+                    return Err(Error::InvalidAttributeValue(pos));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
     }
-}
 
+    fn check_limits(&mut self, token: &Token<'a>) -> Result<()> {
+        let token_end = self.curr_token_end(token);
+        let state = match self.limits {
+            Some(ref mut state) => state,
+            None => return Ok(()),
+        };
 
-/// Tokenizer for the XML structure.
-pub struct Tokenizer<'a> {
-    stream: Stream<'a>,
-    state: State,
-    depth: usize,
-    is_fragment_parsing: bool,
-}
+        let limits = state.limits;
+        let token_start = self.stream.span().start() + self.token_start;
+        let stream = &self.stream;
+        let pos = |offset| gen_pos_at(stream, offset);
 
-impl<'a> From<&'a str> for Tokenizer<'a> {
-    fn from(text: &'a str) -> Self {
-        Self::from(StrSpan::from(text))
+        match *token {
+            Token::ElementStart(..) if self.open_elements >= limits.max_depth => {
+                return Err(Error::LimitExceeded(Limit::Depth, pos(token_start)));
+            }
+            Token::Attribute(_, value, _) if value.len() > limits.max_attribute_value_len => {
+                return Err(Error::LimitExceeded(Limit::AttributeValueLength, pos(value.start())));
+            }
+            Token::EntityDeclaration(..) => {
+                state.entity_declarations += 1;
+                if state.entity_declarations > limits.max_entity_declarations {
+                    return Err(Error::LimitExceeded(Limit::EntityDeclarations, pos(token_start)));
+                }
+            }
+            _ => {}
+        }
+
+        if token_end - token_start > limits.max_token_len {
+            return Err(Error::LimitExceeded(Limit::TokenLength, pos(token_start)));
+        }
+
+        Ok(())
     }
-}
 
-impl<'a> From<StrSpan<'a>> for Tokenizer<'a> {
-    fn from(span: StrSpan<'a>) -> Self {
-        Tokenizer {
-            stream: Stream::from(span),
-            state: State::Document,
-            depth: 0,
-            is_fragment_parsing: false,
+    // Returns an absolute end offset of the just parsed token.
+    fn curr_token_end(&self, token: &Token<'a>) -> usize {
+        match *token {
+            // Spaces after the closing quote are consumed too.
+            Token::Attribute(_, value, _) => value.end() + 1,
+            _ => self.stream.span().start() + self.stream.pos(),
         }
     }
-}
 
+    fn check_standalone(&mut self, token: &Token<'a>) -> Result<()> {
+        let standalone = match self.standalone {
+            Some(ref mut standalone) => standalone,
+            None => return Ok(()),
+        };
 
-/// Shorthand for:
-///
-/// ```no_run
-/// let start = stream.pos() - 2; // or any other number
-/// some_func().map_err(|e|
-///     Error::InvalidToken(Token::SomeToken, stream.gen_error_pos_from(start), Some(e))
-/// )
-/// ```
-macro_rules! map_err_at {
-    ($fun:expr, $token:expr, $stream:expr, $d:expr) => {{
-        let mut start = $stream.pos() as isize + $d;
-        debug_assert!(start >= 0);
-        if start < 0 { start = 0; }
-        $fun.map_err(|e|
-            Error::InvalidToken($token, $stream.gen_error_pos_from(start as usize), Some(e))
-        )
-    }}
-}
+        let text = match *token {
+            Token::Declaration(_, _, Some(value)) => {
+                standalone.is_standalone = value.to_str() == "yes";
+                return Ok(());
+            }
+            Token::EntityDeclaration(name, _) => {
+                // Parameter entities are declared as `<!ENTITY % name`.
+                let token_start = self.stream.span().start() + self.token_start;
+                let decl = &self.stream.span().full_str()[token_start..name.start()];
+                if !decl.contains('%') {
+                    standalone.entities.push(name.to_str());
+                }
 
-impl<'a> Tokenizer<'a> {
-    /// Enables document fragment parsing.
-    ///
-    /// By default, `xmlparser` will check for DTD, root element, etc.
-    /// But if we have to parse an XML fragment, it will lead to an error.
-    /// This method switch the parser to the root element content parsing mode.
-    /// So it will treat any data as a content of the root element.
-    pub fn set_fragment_mode(&mut self) {
-        self.state = State::Elements;
-        self.is_fragment_parsing = true;
+                return Ok(());
+            }
+            Token::Text(text) | Token::Attribute(_, text, _) => text,
+            _ => return Ok(()),
+        };
+
+        if !standalone.is_standalone {
+            return Ok(());
+        }
+
+        let mut s = Stream::from(text);
+        while !s.at_end() {
+            s.skip_until(b'&');
+            let start = s.pos();
+            if let Ok(Reference::EntityRef(name)) = s.consume_reference() {
+                let name = name.to_str();
+                let is_predefined = matches!(name, "lt" | "gt" | "amp" | "apos" | "quot");
+                if !is_predefined && !standalone.entities.contains(&name) {
+                    let pos = gen_pos_at(&self.stream, s.span().start() + start);
+                    return Err(Error::UndeclaredEntity(pos));
+                }
+            } else if !s.at_end() {
+                s.set_pos(start + 1);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Text, CDATA and attribute values are not checked during parsing,
+    // so we have to check them separately.
+    //
+    // NUL is always an error. Other C0 control characters,
+    // except tab, CR and LF, are errors only in the strict mode,
+    // unless allowed by `Strictness`.
+    fn check_control_chars(&self, token: &Token<'a>) -> Result<()> {
+        let (text, token_type) = match *token {
+            Token::Text(text) => (text, TokenType::CharData),
+            Token::Cdata(text) => (text, TokenType::CDSect),
+            Token::Attribute(_, value, _) => (value, TokenType::Attribute),
+            _ => return Ok(()),
+        };
+
+        let is_strict = self.strict.is_some() && !self.config.strictness.allow_control_chars;
+        let idx = text.as_bytes().iter().position(|c| {
+            match *c {
+                0 => true,
+                b'\t' | b'\n' | b'\r' => false,
+                c => is_strict && c < 0x20,
+            }
+        });
+
+        if let Some(idx) = idx {
+            let c = text.as_bytes()[idx];
+            let pos = gen_pos_at(&self.stream, text.start() + idx);
+            let token_pos = gen_pos_at(&self.stream, self.token_start());
+            let e = StreamError::InvalidChar(c, Box::new([]), pos);
+            return Err(Error::InvalidToken(token_type, token_pos, Some(e)));
+        }
+
+        Ok(())
+    }
+
+    fn check_char_refs(&self, token: &Token<'a>) -> Result<()> {
+        if self.strict.is_none() {
+            return Ok(());
+        }
+
+        let (text, token_type) = match *token {
+            Token::Text(text) => (text, TokenType::CharData),
+            Token::Attribute(_, value, _) => (value, TokenType::Attribute),
+            _ => return Ok(()),
+        };
+
+        let mut s = self.stream.sub_stream(text);
+        while let Some(idx) = s.slice_tail().to_str().find("&#") {
+            s.advance(idx);
+            if let Err(e) = s.consume_reference() {
+                let token_pos = gen_pos_at(&self.stream, self.token_start());
+                return Err(Error::InvalidToken(token_type, token_pos, Some(e)));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_document_end(&self) -> Result<()> {
+        let strict = match self.strict {
+            Some(ref strict) => strict,
+            None => return Ok(()),
+        };
+
+        if let Some(&(prefix, _)) = strict.open_elements.last() {
+            // Points to `<`.
+            let pos = gen_pos_at(&self.stream, prefix.start().saturating_sub(1));
+            return Err(Error::UnclosedElement(pos));
+        }
+
+        if !strict.has_root && !self.config.is_fragment_parsing {
+            return Err(Error::NoRootElement(self.stream.gen_error_pos()));
+        }
+
+        Ok(())
     }
 
-    fn parse_next_impl(s: &mut Stream<'a>, state: State) -> Option<Result<Token<'a>>> {
+    fn parse_next_impl(&mut self) -> Option<Result<Token<'a>>> {
+        let state = self.state;
+        let allow_double_hyphen = self.config.strictness.allow_double_hyphen_in_comments;
+        let s = &mut self.stream;
+
         if s.at_end() {
             return None;
         }
 
         let start = s.pos();
+        self.token_start = start;
+
+        if let Some(ref mut warnings) = self.warnings {
+            if state != State::Attributes && s.starts_with(BOM) {
+                warnings.push(Warning::BomStripped(s.gen_error_pos()));
+            }
+        }
 
         macro_rules! parse_token_type {
             () => ({
@@ -162,7 +2110,7 @@ impl<'a> Tokenizer<'a> {
                         Self::parse_declaration(s)
                     }
                     TokenType::Comment => {
-                        Self::parse_comment(s)
+                        Self::parse_comment(s, allow_double_hyphen)
                     }
                     TokenType::PI => {
                         Self::parse_pi(s)
@@ -175,7 +2123,7 @@ impl<'a> Tokenizer<'a> {
                     }
                     TokenType::Whitespace => {
                         s.skip_spaces();
-                        return Self::parse_next_impl(s, state);
+                        return self.parse_next_impl();
                     }
                     _ => {
                         gen_err!(token_type);
@@ -185,6 +2133,41 @@ impl<'a> Tokenizer<'a> {
             State::Dtd => {
                 let token_type = parse_token_type!();
                 match token_type {
+                    TokenType::AttlistDecl
+                        if self.config.is_dtd_declaration_tokens
+                           || self.ids.is_some()
+                           || self.attr_types.is_some() => {
+                        let res = map_err_at!(Self::parse_attlist_decl(s), token_type, s, -9);
+                        let (element, definitions) = match res {
+                            Ok(v) => v,
+                            Err(e) => return Some(Err(e)),
+                        };
+
+                        for def in definitions {
+                            if let Some(ref mut ids) = self.ids {
+                                if def.att_type.to_str() == "ID" {
+                                    let names = (element.to_string(), def.name.to_string());
+                                    ids.id_attributes.push(names);
+                                }
+                            }
+
+                            if let Some(ref mut attr_types) = self.attr_types {
+                                attr_types.types.push((element.to_str(), def.name.to_str(), def.att_type));
+                            }
+                        }
+
+                        if !self.config.is_dtd_declaration_tokens {
+                            return self.parse_next_impl();
+                        }
+
+                        Ok(Token::AttlistDeclaration(element, definitions))
+                    }
+                    TokenType::ElementDecl if self.config.is_dtd_declaration_tokens => {
+                        map_err_at!(Self::parse_element_decl(s), token_type, s, -9)
+                    }
+                    TokenType::NotationDecl if self.config.is_dtd_declaration_tokens => {
+                        map_err_at!(Self::parse_notation_decl(s), token_type, s, -10)
+                    }
                       TokenType::ElementDecl
                     | TokenType::NotationDecl
                     | TokenType::AttlistDecl => {
@@ -192,13 +2175,13 @@ impl<'a> Tokenizer<'a> {
                             gen_err!(token_type);
                         }
 
-                        return Self::parse_next_impl(s, state);
+                        return self.parse_next_impl();
                     }
                     TokenType::EntityDecl => {
                         Self::parse_entity_decl(s)
                     }
                     TokenType::Comment => {
-                        Self::parse_comment(s)
+                        Self::parse_comment(s, allow_double_hyphen)
                     }
                     TokenType::PI => {
                         Self::parse_pi(s)
@@ -208,7 +2191,7 @@ impl<'a> Tokenizer<'a> {
                     }
                     TokenType::Whitespace => {
                         s.skip_spaces();
-                        return Self::parse_next_impl(s, state);
+                        return self.parse_next_impl();
                     }
                     _ => {
                         gen_err!(token_type);
@@ -218,6 +2201,9 @@ impl<'a> Tokenizer<'a> {
             State::Elements => {
                 let token_type = parse_token_type!();
                 match token_type {
+                    TokenType::XMLDecl if self.config.is_fragment_declaration && start == 0 => {
+                        Self::parse_declaration(s)
+                    }
                     TokenType::ElementStart => {
                         Self::parse_element_start(s)
                     }
@@ -231,7 +2217,7 @@ impl<'a> Tokenizer<'a> {
                         Self::parse_pi(s)
                     }
                     TokenType::Comment => {
-                        Self::parse_comment(s)
+                        Self::parse_comment(s, allow_double_hyphen)
                     }
                     TokenType::CharData => {
                         Self::parse_text(s)
@@ -242,22 +2228,36 @@ impl<'a> Tokenizer<'a> {
                 }
             }
             State::Attributes => {
-                Self::consume_attribute(s).map_err(|e|
-                    Error::InvalidToken(TokenType::Attribute,
-                                        s.gen_error_pos_from(start), Some(e)))
+                // Spaces between attributes are not a part of the token.
+                s.skip_ascii_spaces();
+                self.token_start = s.pos();
+
+                let (prefix, local) = self.curr_element;
+                Self::consume_attribute(s).map_err(|e| {
+                    let e = Error::InvalidToken(TokenType::Attribute,
+                                                s.gen_error_pos_from(start), Some(e));
+                    Error::InElement(qname(prefix, local).to_string(), Box::new(e))
+                })
             }
             State::AfterElements => {
                 let token_type = parse_token_type!();
                 match token_type {
                     TokenType::Comment => {
-                        Self::parse_comment(s)
+                        Self::parse_comment(s, allow_double_hyphen)
                     }
                     TokenType::PI => {
                         Self::parse_pi(s)
                     }
                     TokenType::Whitespace => {
                         s.skip_spaces();
-                        return Self::parse_next_impl(s, state);
+                        return self.parse_next_impl();
+                    }
+                      TokenType::XMLDecl
+                    | TokenType::DoctypeDecl
+                    | TokenType::ElementStart if self.config.is_multi_document_parsing => {
+                        // The next document will be parsed from the start.
+                        s.set_pos(start);
+                        Ok(Token::DocumentBoundary)
                     }
                     _ => {
                         gen_err!(token_type);
@@ -272,11 +2272,80 @@ impl<'a> Tokenizer<'a> {
         Some(t)
     }
 
+    // Parses the next token when more data can be appended to the text.
+    //
+    // Returns `None` and restores the state when the token may be incomplete.
+    fn parse_partial(&mut self) -> Option<Result<Token<'a>>> {
+        let stream = self.stream;
+        let token_start = self.token_start;
+        let warnings_len = self.warnings.as_ref().map_or(0, Vec::len);
+        let ids_len = self.ids.as_ref().map_or(0, |ids| ids.id_attributes.len());
+        let attr_types_len = self.attr_types.as_ref().map_or(0, |attr_types| attr_types.types.len());
+
+        let t = self.parse_next_impl();
+        let len = self.stream.span().len();
+        let is_complete = match t {
+            Some(Ok(Token::ElementStart(_, _))) if self.config.is_lazy_attributes => {
+                let mut s = self.stream;
+                let _ = Self::skip_attributes(&mut s);
+                s.starts_with(b">") || s.starts_with(b"/>")
+            }
+            // A token at the end of the text can be extended, like a text or a name.
+            Some(Ok(_)) => self.stream.pos() < len,
+            _ => false,
+        };
+
+        if is_complete {
+            return t;
+        }
+
+        self.stream = stream;
+        self.token_start = token_start;
+
+        if let Some(ref mut warnings) = self.warnings {
+            warnings.truncate(warnings_len);
+        }
+
+        if let Some(ref mut ids) = self.ids {
+            ids.id_attributes.truncate(ids_len);
+        }
+
+        if let Some(ref mut attr_types) = self.attr_types {
+            attr_types.types.truncate(attr_types_len);
+        }
+
+        None
+    }
+
+    // Skips to the next resynchronization point after an error.
+    fn recover(&mut self) {
+        // Always make progress, even when the error is at the token start.
+        // When the token is unterminated, resync right after its start instead.
+        let pos = if self.stream.at_end() {
+            self.token_start + 1
+        } else {
+            cmp::max(self.stream.pos(), self.token_start + 1)
+        };
+        self.stream.set_pos(cmp::min(pos, self.stream.span().len()));
+
+        if self.state == State::Dtd {
+            self.stream.skip_until_either(b'<', b']');
+        } else {
+            self.stream.skip_until(b'<');
+        }
+
+        if self.state == State::Attributes {
+            // Treat an invalid start tag as an open element.
+            self.open_elements += 1;
+            self.state = State::Elements;
+        }
+    }
+
     fn parse_token_type(s: &mut Stream, state: State) -> StreamResult<TokenType> {
         let c1 = s.curr_byte()?;
 
         let t = match c1 {
-            0xEF => {
+            0xEF if s.starts_with(BOM) => {
                 // Skip BOM.
                 s.advance(3);
                 Self::parse_token_type(s, state)?
@@ -349,14 +2418,13 @@ impl<'a> Tokenizer<'a> {
             }
             _ => {
                 match state {
-                    State::Document | State::AfterElements | State::Dtd => {
-                        if s.starts_with_space() {
-                            TokenType::Whitespace
-                        } else {
-                            TokenType::Unknown
-                        }
+                    State::Document | State::AfterElements | State::Dtd
+                        if s.starts_with_space() => {
+                        TokenType::Whitespace
                     }
-                    State::Elements => {
+                    State::Document | State::AfterElements | State::Elements => {
+                        // Text is allowed only inside the root element,
+                        // but we still have to detect it for a better error.
                         TokenType::CharData
                     }
                     _ => {
@@ -379,6 +2447,7 @@ impl<'a> Tokenizer<'a> {
         let standalone = Self::parse_standalone(s)?;
 
         s.skip_ascii_spaces();
+        Self::check_pseudo_attribute(s, encoding.is_some(), standalone.is_some())?;
         s.skip_string(b"?>")?;
 
         Ok(Token::Declaration(version, encoding, standalone))
@@ -424,6 +2493,34 @@ impl<'a> Tokenizer<'a> {
         Ok(Some(name))
     }
 
+    // Makes sure that there are no pseudo-attributes left.
+    fn check_pseudo_attribute(
+        s: &Stream<'a>,
+        has_encoding: bool,
+        has_standalone: bool,
+    ) -> StreamResult<()> {
+        let mut tmp = *s;
+        let name = match tmp.consume_name() {
+            Ok(name) => name,
+            // Will be reported as a missing `?>`.
+            Err(_) => return Ok(()),
+        };
+
+        let is_duplicated = match name.to_str() {
+            "version" => true,
+            "encoding" => has_encoding,
+            "standalone" => has_standalone,
+            _ => false,
+        };
+
+        let pos = s.gen_error_pos();
+        if is_duplicated {
+            Err(StreamError::DuplicatedPseudoAttribute(name.to_str().into(), pos))
+        } else {
+            Err(StreamError::UnexpectedPseudoAttribute(name.to_str().into(), pos))
+        }
+    }
+
     // S 'standalone' Eq (("'" ('yes' | 'no') "'") | ('"' ('yes' | 'no') '"'))
     fn parse_standalone(s: &mut Stream<'a>) -> StreamResult<Option<StrSpan<'a>>> {
         s.skip_ascii_spaces();
@@ -441,9 +2538,10 @@ impl<'a> Tokenizer<'a> {
         match value.to_str() {
             "yes" | "no" => {}
             _ => {
-                let values = vec![value.to_str().into(), "yes".into(), "no".into()];
+                let expected = Box::new([Expectation::String("yes".into()),
+                                         Expectation::String("no".into())]);
                 let pos = s.gen_error_pos_from(start);
-                return Err(StreamError::InvalidString(values, pos));
+                return Err(StreamError::InvalidString(value.to_str().into(), expected, pos));
             }
         }
 
@@ -453,7 +2551,7 @@ impl<'a> Tokenizer<'a> {
     }
 
     // '<!--' ((Char - '-') | ('-' (Char - '-')))* '-->'
-    fn parse_comment(s: &mut Stream<'a>) -> Result<Token<'a>> {
+    fn parse_comment(s: &mut Stream<'a>, allow_double_hyphen: bool) -> Result<Token<'a>> {
         let start = s.pos() - 4;
 
         let text = s.consume_chars(|s, c| {
@@ -468,7 +2566,7 @@ impl<'a> Tokenizer<'a> {
             true
         });
 
-        if text.to_str().contains("--") {
+        if !allow_double_hyphen && text.to_str().contains("--") {
             let pos = s.gen_error_pos_from(start);
             return Err(Error::InvalidToken(TokenType::Comment, pos, None));
         }
@@ -528,7 +2626,7 @@ impl<'a> Tokenizer<'a> {
         let id = Self::parse_external_id(s)?;
         s.skip_spaces();
 
-        let c = s.consume_either(&[b'[', b'>'])?;
+        let c = s.consume_either(b"[>")?;
         if c == b'[' {
             Ok(Token::DtdStart(name, id))
         } else {
@@ -545,7 +2643,7 @@ impl<'a> Tokenizer<'a> {
 
             s.consume_spaces()?;
             let quote = s.consume_quote()?;
-            let literal1 = s.consume_bytes(|_, c| c != quote);
+            let literal1 = s.consume_until(quote);
             s.consume_byte(quote)?;
 
             let v = if id.to_str() == "SYSTEM" {
@@ -553,7 +2651,7 @@ impl<'a> Tokenizer<'a> {
             } else {
                 s.consume_spaces()?;
                 let quote = s.consume_quote()?;
-                let literal2 = s.consume_bytes(|_, c| c != quote);
+                let literal2 = s.consume_until(quote);
                 s.consume_byte(quote)?;
 
                 ExternalId::Public(literal1, literal2)
@@ -605,9 +2703,13 @@ impl<'a> Tokenizer<'a> {
         match c {
             b'"' | b'\'' => {
                 let quote = s.consume_quote()?;
-                let value = s.consume_bytes(|_, c| c != quote);
+                let value = s.consume_until(quote);
                 s.consume_byte(quote)?;
 
+                if is_ge {
+                    Self::check_entity_value(s.sub_stream(value))?;
+                }
+
                 Ok(EntityDefinition::EntityValue(value))
             }
             b'S' | b'P' => {
@@ -622,17 +2724,186 @@ impl<'a> Tokenizer<'a> {
                         }
                     }
 
-                    Ok(EntityDefinition::ExternalId(id))
+                    Ok(EntityDefinition::ExternalId(id))
+                } else {
+                    Err(StreamError::InvalidExternalID)
+                }
+            }
+            _ => {
+                let pos = s.gen_error_pos();
+                Err(StreamError::InvalidChar(c, Expectation::chars(b"\"'SP"), pos))
+            }
+        }
+    }
+
+    // A lightweight check that the replacement text will not break the content.
+    //
+    // Each `&` must start a reference and each `<` must start a markup.
+    // Nested references are not expanded.
+    fn check_entity_value(mut s: Stream<'a>) -> StreamResult<()> {
+        while !s.at_end() {
+            match s.curr_byte()? {
+                b'&' => {
+                    let start = s.pos();
+                    if s.consume_reference().is_err() {
+                        let pos = s.gen_error_pos_from(start);
+                        return Err(StreamError::InvalidEntityValue('&', pos));
+                    }
+                }
+                b'<' => {
+                    let is_markup = match s.slice_tail().to_str()[1..].chars().next() {
+                        Some(c) => c.is_xml_name_start() || c == '/' || c == '!' || c == '?',
+                        None => false,
+                    };
+
+                    if !is_markup {
+                        return Err(StreamError::InvalidEntityValue('<', s.gen_error_pos()));
+                    }
+
+                    s.advance(1);
+                }
+                _ => s.advance(1),
+            }
+        }
+
+        Ok(())
+    }
+
+    // AttlistDecl ::= '<!ATTLIST' S Name AttDef* S? '>'
+    //
+    // Returns element name and attribute definitions.
+    fn parse_attlist_decl(s: &mut Stream<'a>)
+        -> StreamResult<(StrSpan<'a>, AttributeDefinitions<'a>)>
+    {
+        s.consume_spaces()?;
+        let element = s.consume_name()?;
+
+        let start = s.pos();
+        s.skip_spaces();
+        while !s.is_curr_byte_eq(b'>') {
+            Self::parse_att_def(s)?;
+            s.skip_spaces();
+        }
+
+        let definitions = AttributeDefinitions { span: s.slice_back(start) };
+        s.advance(1);
+        Ok((element, definitions))
+    }
+
+    // AttDef        ::= S Name S AttType S DefaultDecl
+    // AttType       ::= StringType | TokenizedType | EnumeratedType
+    // NotationType  ::= 'NOTATION' S '(' S? Name (S? '|' S? Name)* S? ')'
+    // Enumeration   ::= '(' S? Nmtoken (S? '|' S? Nmtoken)* S? ')'
+    // DefaultDecl   ::= '#REQUIRED' | '#IMPLIED' | (('#FIXED' S)? AttValue)
+    //
+    // Enumerations are returned as is, including the `NOTATION` keyword.
+    fn parse_att_def(s: &mut Stream<'a>) -> StreamResult<AttributeDefinition<'a>> {
+        let name = s.consume_name()?;
+        s.consume_spaces()?;
+
+        let start = s.pos();
+        let att_type = if s.is_curr_byte_eq(b'(') {
+            Self::skip_enumeration(s)?;
+            s.slice_back(start)
+        } else {
+            let att_type = s.consume_name()?;
+            if att_type.to_str() == "NOTATION" {
+                s.consume_spaces()?;
+                Self::skip_enumeration(s)?;
+                s.slice_back(start)
+            } else {
+                att_type
+            }
+        };
+
+        s.consume_spaces()?;
+
+        let default = if s.starts_with(b"#REQUIRED") {
+            s.advance(9);
+            AttributeDefault::Required
+        } else if s.starts_with(b"#IMPLIED") {
+            s.advance(8);
+            AttributeDefault::Implied
+        } else {
+            let is_fixed = s.starts_with(b"#FIXED");
+            if is_fixed {
+                s.advance(6);
+                s.consume_spaces()?;
+            }
+
+            let value = s.consume_quoted()?;
+            if is_fixed {
+                AttributeDefault::Fixed(value)
+            } else {
+                AttributeDefault::Value(value)
+            }
+        };
+
+        Ok(AttributeDefinition { name, att_type, default })
+    }
+
+    // elementdecl ::= '<!ELEMENT' S Name S contentspec S? '>'
+    // contentspec ::= 'EMPTY' | 'ANY' | Mixed | children
+    //
+    // The content specification is returned as is.
+    fn parse_element_decl(s: &mut Stream<'a>) -> StreamResult<Token<'a>> {
+        s.consume_spaces()?;
+        let name = s.consume_name()?;
+        s.consume_spaces()?;
+
+        let start = s.pos();
+        s.skip_bytes(|_, c| c != b'>');
+        let spec = s.slice_back(start).trim();
+        if spec.is_empty() {
+            let c = s.curr_byte()?;
+            return Err(StreamError::InvalidChar(c, Expectation::chars(b"EA("), s.gen_error_pos()));
+        }
+
+        s.consume_byte(b'>')?;
+        Ok(Token::ElementDeclaration(name, spec))
+    }
+
+    // NotationDecl ::= '<!NOTATION' S Name S (ExternalID | PublicID) S? '>'
+    // PublicID     ::= 'PUBLIC' S PubidLiteral
+    fn parse_notation_decl(s: &mut Stream<'a>) -> StreamResult<Token<'a>> {
+        s.consume_spaces()?;
+        let name = s.consume_name()?;
+        s.consume_spaces()?;
+
+        let (public_id, system_id) = if s.starts_with(b"PUBLIC") {
+            s.advance(6);
+            s.consume_spaces()?;
+            let public_id = s.consume_quoted()?;
+
+            // The system literal is optional.
+            let system_id = if s.starts_with_space() {
+                s.skip_spaces();
+                if s.is_curr_byte_eq(b'>') {
+                    None
                 } else {
-                    Err(StreamError::InvalidExternalID)
+                    Some(s.consume_quoted()?)
                 }
-            }
-            _ => {
-                let chars = vec![c, b'"', b'\'', b'S', b'P'];
-                let pos = s.gen_error_pos();
-                Err(StreamError::InvalidChar(chars, pos))
-            }
-        }
+            } else {
+                None
+            };
+
+            (Some(public_id), system_id)
+        } else {
+            s.skip_string(b"SYSTEM")?;
+            s.consume_spaces()?;
+            (None, Some(s.consume_quoted()?))
+        };
+
+        s.skip_spaces();
+        s.consume_byte(b'>')?;
+        Ok(Token::NotationDeclaration(name, public_id, system_id))
+    }
+
+    fn skip_enumeration(s: &mut Stream) -> StreamResult<()> {
+        s.consume_byte(b'(')?;
+        s.skip_bytes(|_, c| c != b')' && c != b'>');
+        s.consume_byte(b')')?;
+        Ok(())
     }
 
     fn consume_decl(s: &mut Stream) -> StreamResult<()> {
@@ -651,9 +2922,16 @@ impl<'a> Tokenizer<'a> {
     // CData   ::= (Char* - (Char* ']]>' Char*))
     // CDEnd   ::= ']]>'
     fn parse_cdata_impl(s: &mut Stream<'a>) -> StreamResult<Token<'a>> {
-        let text = s.consume_bytes(|s, c| {
-            !(c == b']' && s.starts_with(b"]]>"))
-        });
+        let start = s.pos();
+        loop {
+            s.skip_until(b']');
+            if s.at_end() || s.starts_with(b"]]>") {
+                break;
+            }
+
+            s.advance(1);
+        }
+        let text = s.slice_back(start);
 
         s.skip_string(b"]]>")?;
 
@@ -703,18 +2981,49 @@ impl<'a> Tokenizer<'a> {
         }
 
         let (prefix, name) = s.consume_qname()?;
+        let value = Self::consume_attribute_value(s)?;
+        s.skip_ascii_spaces();
+
+        // The index is set by the caller.
+        Ok(Token::Attribute((prefix, name), value, 0))
+    }
+
+    // Skips everything up to the `/>` or `>` outside of the attribute values.
+    fn skip_attributes(s: &mut Stream<'a>) -> StrSpan<'a> {
+        let start = s.pos();
+        let mut quote = None;
+        while let Some(c) = s.get_curr_byte() {
+            match (c, quote) {
+                (b'"', None) | (b'\'', None) => quote = Some(c),
+                (_, Some(q)) if c == q => quote = None,
+                (b'>', None) => break,
+                (b'/', None) if s.starts_with(b"/>") => break,
+                _ => {}
+            }
+
+            s.advance(1);
+        }
+
+        if s.at_end() {
+            // An unclosed tag. Let the regular attributes parsing report an error.
+            s.set_pos(start);
+        }
+
+        s.slice_back(start)
+    }
+
+    // Eq AttValue
+    fn consume_attribute_value(s: &mut Stream<'a>) -> StreamResult<StrSpan<'a>> {
         s.consume_eq()?;
         let quote = s.consume_quote()?;
-        let value = s.consume_bytes(|_, c| c != quote);
-
+        let value = s.consume_until(quote);
         s.consume_byte(quote)?;
-        s.skip_ascii_spaces();
 
-        Ok(Token::Attribute((prefix, name), value))
+        Ok(value)
     }
 
     fn parse_text(s: &mut Stream<'a>) -> Result<Token<'a>> {
-        let text = s.consume_bytes(|_, c| c != b'<');
+        let text = s.consume_until(b'<');
 
         let mut ts = Stream::from(text);
         // TODO: optimize
@@ -731,51 +3040,490 @@ impl<'a> Iterator for Tokenizer<'a> {
     type Item = Result<Token<'a>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.stream.at_end() || self.state == State::Finished {
-            self.state = State::Finished;
+        if self.state == State::Finished {
             return None;
         }
 
-        let t = Self::parse_next_impl(&mut self.stream, self.state);
+        let verbatim_start = self.stream.pos();
+        let t = if self.is_partial_input {
+            // The end of the text is not the end of the document.
+            Some(self.parse_partial()?)
+        } else if !self.stream.at_end() {
+            self.parse_next_impl()
+        } else {
+            None
+        };
+
+        let mut t = match t {
+            Some(t) => t,
+            None => {
+                self.is_balanced_at_end = self.is_balanced() && !self.has_recovered_errors;
+                self.state = State::Finished;
 
-        if let Some(ref t) = t {
-            match *t {
-                Ok(Token::ElementStart(_, _)) => {
-                    self.state = State::Attributes;
+                if let Err(e) = self.check_document_end() {
+                    return Some(Err(e));
                 }
-                Ok(Token::ElementEnd(ref end)) => {
-                    match *end {
-                        ElementEnd::Open => {
-                            self.depth += 1;
-                        }
-                        ElementEnd::Close(_, _) => {
-                            if self.depth > 0 {
-                                self.depth -= 1;
-                            }
-                        }
-                        ElementEnd::Empty => {}
-                    }
 
-                    if self.depth == 0 && !self.is_fragment_parsing {
-                        self.state = State::AfterElements;
-                    } else {
-                        self.state = State::Elements;
-                    }
+                if self.config.is_end_of_document_token {
+                    self.token_start = self.stream.pos();
+                    self.verbatim = (verbatim_start, self.stream.pos());
+                    let span = self.stream.span();
+                    return Some(Ok(Token::EndOfDocument(span.start() + span.len())));
+                }
+
+                return None;
+            }
+        };
+
+        // A token that failed an optional check in the recovery mode.
+        let mut check_error = None;
+        if let Ok(token) = t {
+            let res = self.process_lazy_attributes(&token)
+                          .and_then(|_| self.process_token(&token));
+            if let Err(e) = res {
+                if self.config.is_error_recovery {
+                    check_error = Some(e);
+                } else {
+                    t = Err(e);
                 }
-                Ok(Token::DtdStart(_, _)) => {
-                    self.state = State::Dtd;
+            }
+        }
+
+        self.token_depth = self.open_elements;
+
+        match t {
+            Ok(Token::ElementStart(prefix, local)) => {
+                self.curr_element = (prefix, local);
+                self.attributes_count = 0;
+                self.state = State::Attributes;
+
+                if self.config.is_lazy_attributes {
+                    self.attributes = Self::skip_attributes(&mut self.stream);
                 }
-                Ok(Token::DtdEnd) => {
-                    self.state = State::Document;
+            }
+            Ok(Token::ElementEnd(ref end)) => {
+                self.attributes = StrSpan::from("");
+
+                match *end {
+                    ElementEnd::Open => {
+                        self.open_elements += 1;
+                    }
+                    ElementEnd::Close(_, _) => {
+                        if self.open_elements > 0 {
+                            self.open_elements -= 1;
+                        }
+
+                        self.token_depth = self.open_elements;
+                    }
+                    ElementEnd::Empty => {}
                 }
-                Err(_) => {
-                    self.stream.jump_to_end();
-                    self.state = State::Finished;
+
+                if self.open_elements == 0 && !self.config.is_fragment_parsing {
+                    self.state = State::AfterElements;
+                } else {
+                    self.state = State::Elements;
                 }
-                _ => {}
             }
+            Ok(Token::Attribute(_, _, ref mut index)) => {
+                *index = self.attributes_count;
+                self.attributes_count += 1;
+            }
+            Ok(Token::DtdStart(_, _)) => {
+                self.state = State::Dtd;
+            }
+            Ok(Token::DtdEnd) => {
+                self.state = State::Document;
+            }
+            Ok(Token::DocumentBoundary) => {
+                self.reset_document();
+            }
+            Err(_) if self.config.is_error_recovery => {
+                self.error_offset = self.stream.span().start() + self.stream.pos();
+                self.verbatim = (verbatim_start, self.stream.pos());
+                self.has_recovered_errors = true;
+                self.recover();
+            }
+            Err(_) => {
+                self.error_offset = self.stream.span().start() + self.stream.pos();
+                self.verbatim = (verbatim_start, self.stream.pos());
+                self.stream.jump_to_end();
+                self.is_balanced_at_end = false;
+                self.state = State::Finished;
+            }
+            _ => {}
+        }
+
+        if let Some(e) = check_error {
+            self.verbatim = (verbatim_start, self.stream.pos());
+            self.error_offset = self.stream.span().start() + self.stream.pos();
+            self.has_recovered_errors = true;
+            return Some(Err(e));
+        }
+
+        if t.is_ok() {
+            self.verbatim = (verbatim_start, self.stream.pos());
+        }
+
+        if let Ok(Token::Whitespaces(text)) = t {
+            if self.config.is_whitespace_text_skipping
+                && self.xml_space() == XmlSpace::Default
+                && text.as_bytes().iter().all(|c| c.is_xml_space())
+            {
+                // Whitespaces are followed by markup or the end of the text,
+                // so the next token is not skipped.
+                return self.next();
+            }
+        }
+
+        Some(t)
+    }
+}
+
+
+/// An iterator over tokens with their start positions.
+///
+/// Created by [`Tokenizer::with_positions`].
+///
+/// [`Tokenizer::with_positions`]: struct.Tokenizer.html#method.with_positions
+pub struct WithPositions<'a> {
+    tokenizer: Tokenizer<'a>,
+    tracker: LineTracker,
+}
+
+impl<'a> Iterator for WithPositions<'a> {
+    type Item = Result<(Token<'a>, ErrorPos)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = match self.tokenizer.next()? {
+            Ok(token) => token,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let start = self.tokenizer.token_start();
+        self.tracker.advance_to(self.tokenizer.stream.span().full_str(), start);
+        let pos = self.tracker.pos();
+        self.tracker.is_xml_1_1 = self.tokenizer.stream.is_xml_1_1();
+
+        Some(Ok((token, pos)))
+    }
+}
+
+/// An iterator over tokens that fit into a byte budget.
+///
+/// Created by [`Tokenizer::take_bytes`].
+///
+/// [`Tokenizer::take_bytes`]: struct.Tokenizer.html#method.take_bytes
+pub struct TakeBytes<'a> {
+    tokenizer: Tokenizer<'a>,
+    budget: usize,
+    consumed: usize,
+    is_finished: bool,
+}
+
+impl<'a> TakeBytes<'a> {
+    /// Returns an absolute offset from which the parsing should be resumed.
+    ///
+    /// It's the start of the first token that doesn't fit into the budget
+    /// or the end of the text. Can be bigger than the budget
+    /// when tokens are separated by spaces.
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+}
+
+impl<'a> Iterator for TakeBytes<'a> {
+    type Item = Result<Token<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_finished {
+            return None;
+        }
+
+        let p = &mut self.tokenizer;
+        let token = match p.next() {
+            Some(token) => token,
+            None => {
+                self.is_finished = true;
+                self.consumed = p.stream.span().end();
+                return None;
+            }
+        };
+
+        let start = p.token_start();
+        let end = match token {
+            // Trailing spaces are not a part of the token.
+            Ok(Token::Attribute(_, value, _)) => value.end() + 1,
+            Ok(_) => p.stream.span().start() + p.stream.pos(),
+            Err(_) => start,
+        };
+
+        if start >= self.budget || end > self.budget {
+            self.is_finished = true;
+            self.consumed = start;
+            return None;
+        }
+
+        match token {
+            Ok(_) => self.consumed = end,
+            Err(_) => self.is_finished = true,
+        }
+
+        Some(token)
+    }
+}
+
+/// An iterator over pseudo-attributes of a processing instruction.
+///
+/// Pseudo-attributes use the attribute syntax, like in
+/// `<?xml-stylesheet type="text/xsl" href="style.xsl"?>`.
+/// Values are returned as is.
+///
+/// Iteration stops after the first error.
+///
+/// # Examples
+///
+/// ```
+/// use xmlparser::{Tokenizer, PseudoAttributes};
+///
+/// let token = Tokenizer::from("<?xml-stylesheet type='text/xsl' href='style.xsl'?>")
+///     .next().unwrap().unwrap();
+/// let (_, content) = token.as_processing_instruction().unwrap();
+///
+/// let mut attrs = PseudoAttributes::from(content.unwrap());
+/// let (name, value) = attrs.next().unwrap().unwrap();
+/// assert_eq!(name.to_str(), "type");
+/// assert_eq!(value.to_str(), "text/xsl");
+/// ```
+pub struct PseudoAttributes<'a> {
+    stream: Stream<'a>,
+}
+
+impl<'a> From<StrSpan<'a>> for PseudoAttributes<'a> {
+    fn from(content: StrSpan<'a>) -> Self {
+        PseudoAttributes {
+            stream: Stream::from(content),
+        }
+    }
+}
+
+impl<'a> Iterator for PseudoAttributes<'a> {
+    type Item = Result<(StrSpan<'a>, StrSpan<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let s = &mut self.stream;
+        s.skip_spaces();
+
+        if s.at_end() {
+            return None;
+        }
+
+        let start = s.pos();
+        let res = s.consume_name().and_then(|name| {
+            let value = Tokenizer::consume_attribute_value(s)?;
+
+            // Pseudo-attributes must be separated by spaces.
+            if !s.at_end() && !s.starts_with_space() {
+                let c = s.curr_char()?;
+                return Err(StreamError::InvalidSpace(c, s.gen_error_pos()));
+            }
+
+            Ok((name, value))
+        });
+
+        match res {
+            Ok(attr) => Some(Ok(attr)),
+            Err(e) => {
+                let pos = s.gen_error_pos_from(start);
+                s.jump_to_end();
+                Some(Err(Error::InvalidToken(TokenType::PI, pos, Some(e))))
+            }
+        }
+    }
+}
+
+/// An iterator over attributes of an element.
+///
+/// Yields `Attribute` tokens.
+/// Iteration stops after the first error.
+///
+/// Created by [`Tokenizer::attributes`], but can also be created
+/// from any attributes region, like `a='1' b='2'`.
+///
+/// [`Tokenizer::attributes`]: struct.Tokenizer.html#method.attributes
+pub struct Attributes<'a> {
+    stream: Stream<'a>,
+    index: usize,
+}
+
+impl<'a> From<StrSpan<'a>> for Attributes<'a> {
+    fn from(span: StrSpan<'a>) -> Self {
+        Attributes {
+            stream: Stream::from(span),
+            index: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for Attributes<'a> {
+    type Item = Result<Token<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let s = &mut self.stream;
+        s.skip_spaces();
+
+        if s.at_end() {
+            return None;
+        }
+
+        let start = s.pos();
+        let index = self.index;
+        let res = s.consume_qname().and_then(|(prefix, local)| {
+            let value = Tokenizer::consume_attribute_value(s)?;
+            Ok(Token::Attribute((prefix, local), value, index))
+        });
+
+        match res {
+            Ok(token) => {
+                self.index += 1;
+                Some(Ok(token))
+            }
+            Err(e) => {
+                let pos = s.gen_error_pos_from(start);
+                s.jump_to_end();
+                Some(Err(Error::InvalidToken(TokenType::Attribute, pos, Some(e))))
+            }
+        }
+    }
+}
+
+/// An iterator over attribute definitions.
+///
+/// Returned by the `AttlistDeclaration` token.
+/// Definitions are validated during tokenizing.
+///
+/// **Note:** compared by the remaining definitions content.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AttributeDefinitions<'a> {
+    span: StrSpan<'a>,
+}
+
+impl<'a> AttributeDefinitions<'a> {
+    /// Returns the remaining definitions as is.
+    pub fn span(&self) -> StrSpan<'a> {
+        self.span
+    }
+}
+
+impl<'a> Iterator for AttributeDefinitions<'a> {
+    type Item = AttributeDefinition<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut s = Stream::from(self.span);
+        s.skip_spaces();
+        if s.at_end() {
+            return None;
+        }
+
+        let def = Tokenizer::parse_att_def(&mut s).ok()?;
+        self.span = s.slice_tail();
+        Some(def)
+    }
+}
+
+/// Checks that the text is a well-formed XML document.
+///
+/// Returns the first error. Unlike a plain `Tokenizer`, also checks that
+/// the elements tree is balanced, attributes are unique and the root element is present.
+///
+/// # Examples
+///
+/// ```
+/// assert!(xmlparser::validate("<a><b/></a>").is_ok());
+/// assert!(xmlparser::validate("<a><b></a>").is_err());
+/// ```
+pub fn validate(text: &str) -> Result<()> {
+    let mut p = Tokenizer::from(text);
+    p.set_strict_mode();
+    for token in p {
+        token?;
+    }
+
+    Ok(())
+}
+
+/// Normalizes a value of an attribute with a tokenized type, like `ID`,
+/// by collapsing and trimming spaces.
+///
+/// Allocates only when the value is not normalized already.
+fn normalize_tokenized_value(value: &str) -> Cow<'_, str> {
+    let bytes = value.as_bytes();
+    let is_normalized = bytes.first() != Some(&b' ')
+        && bytes.last() != Some(&b' ')
+        && !bytes.iter().any(|&c| c == b'\t' || c == b'\n' || c == b'\r')
+        && !value.contains("  ");
+    if is_normalized {
+        return Cow::Borrowed(value);
+    }
+
+    Cow::Owned(value.split(&[' ', '\t', '\n', '\r'][..])
+                    .filter(|s| !s.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" "))
+}
+
+/// Checks that a normalized attribute value matches the declared type.
+fn is_valid_attribute_value(att_type: &str, value: &str) -> bool {
+    fn is_name(s: &str) -> bool {
+        let mut chars = s.chars();
+        match chars.next() {
+            Some(c) if c.is_xml_name_start() => chars.all(|c| c.is_xml_name()),
+            _ => false,
         }
+    }
+
+    fn is_nmtoken(s: &str) -> bool {
+        !s.is_empty() && s.chars().all(|c| c.is_xml_name())
+    }
+
+    let tokens: Vec<_> = value.split(' ').filter(|s| !s.is_empty()).collect();
+    let single = if tokens.len() == 1 { tokens[0] } else { "" };
+
+    match att_type {
+        "ID" | "IDREF" | "ENTITY" => {
+            is_name(single)
+        }
+        "IDREFS" | "ENTITIES" => {
+            !tokens.is_empty() && tokens.iter().all(|t| is_name(t))
+        }
+        "NMTOKEN" => {
+            is_nmtoken(single)
+        }
+        "NMTOKENS" => {
+            !tokens.is_empty() && tokens.iter().all(|t| is_nmtoken(t))
+        }
+        _ if att_type.ends_with(')') => {
+            // Enumeration or NOTATION.
+            let list = match att_type.find('(') {
+                Some(idx) => &att_type[idx + 1..att_type.len() - 1],
+                None => return true,
+            };
+
+            !single.is_empty() && list.split('|').any(|item| item.trim() == single)
+        }
+        _ => true,
+    }
+}
 
-        t
+/// Returns a qualified name from the prefix and local part.
+fn qname<'a>(prefix: StrSpan<'a>, local: StrSpan<'a>) -> &'a str {
+    if prefix.is_empty() {
+        local.to_str()
+    } else {
+        &local.full_str()[prefix.start()..local.end()]
     }
 }
+
+/// Calculates a position from an absolute offset in the original text.
+fn gen_pos_at(s: &Stream, offset: usize) -> ErrorPos {
+    s.gen_error_pos_from(offset - s.span().start())
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "async")]
+
+extern crate xmlparser as xml;
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use xml::{AsyncSource, AsyncTokenizer, OwnedElementEnd, OwnedToken};
+
+// Returns chunks one by one, with `Pending` before each of them.
+struct Chunks {
+    chunks: Vec<&'static [u8]>,
+    is_ready: bool,
+}
+
+impl AsyncSource for Chunks {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        if !self.is_ready {
+            self.is_ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        self.is_ready = false;
+        if self.chunks.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let chunk = self.chunks.remove(0);
+        buf[..chunk.len()].copy_from_slice(chunk);
+        Poll::Ready(Ok(chunk.len()))
+    }
+}
+
+fn noop_waker() -> Waker {
+    fn clone(_: *const ()) -> RawWaker { RawWaker::new(std::ptr::null(), &VTABLE) }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+    unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+}
+
+fn block_on<F: Future>(mut future: F) -> F::Output {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        // Not moved after pinning.
+        let future = unsafe { Pin::new_unchecked(&mut future) };
+        if let Poll::Ready(v) = future.poll(&mut cx) {
+            return v;
+        }
+    }
+}
+
+fn collect(chunks: Vec<&'static [u8]>) -> Vec<Result<OwnedToken, String>> {
+    let mut p = AsyncTokenizer::new(Chunks { chunks, is_ready: false });
+    let mut tokens = Vec::new();
+    while let Some(token) = block_on(p.next_token()) {
+        tokens.push(token.map_err(|e| e.to_string()));
+    }
+
+    tokens
+}
+
+fn s(text: &str) -> String {
+    text.to_string()
+}
+
+#[test]
+fn async_01() {
+    assert_eq!(collect(vec![b"<a x", b"='1'>\xD1", b"\x82</", b"a>"]), vec![
+        Ok(OwnedToken::ElementStart(s(""), s("a"))),
+        Ok(OwnedToken::Attribute((s(""), s("x")), s("1"), 0)),
+        Ok(OwnedToken::ElementEnd(OwnedElementEnd::Open)),
+        Ok(OwnedToken::Text(s("т"))),
+        Ok(OwnedToken::ElementEnd(OwnedElementEnd::Close(s(""), s("a")))),
+    ]);
+}
+
+#[test]
+fn async_02() {
+    assert_eq!(collect(vec![b"<a>\n<b x='"]), vec![
+        Ok(OwnedToken::ElementStart(s(""), s("a"))),
+        Ok(OwnedToken::ElementEnd(OwnedElementEnd::Open)),
+        Ok(OwnedToken::Whitespaces(s("\n"))),
+        Ok(OwnedToken::ElementStart(s(""), s("b"))),
+        Err(s("invalid token 'attribute' at 2:3 cause \
+                unexpected end of stream while parsing element 'b'")),
+    ]);
+}
+
+#[test]
+fn async_03() {
+    assert_eq!(collect(vec![b"<a>text\xFF</a>"]), vec![
+        Ok(OwnedToken::ElementStart(s(""), s("a"))),
+        Ok(OwnedToken::ElementEnd(OwnedElementEnd::Open)),
+        Ok(OwnedToken::Text(s("text"))),
+        Err(s("invalid UTF-8 at 1:8")),
+    ]);
+}
+
+#[test]
+fn async_04() {
+    // A truncated UTF-8 sequence.
+    assert_eq!(collect(vec![b"<a/>\xD1"]), vec![
+        Ok(OwnedToken::ElementStart(s(""), s("a"))),
+        Ok(OwnedToken::ElementEnd(OwnedElementEnd::Empty)),
+        Err(s("invalid UTF-8 at 1:5")),
+    ]);
+}
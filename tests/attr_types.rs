@@ -0,0 +1,87 @@
+extern crate xmlparser as xml;
+
+fn check(text: &str) -> Result<(), xml::Error> {
+    let mut p = xml::Tokenizer::from(text);
+    p.enable_attribute_type_checking();
+    for token in p {
+        token?;
+    }
+
+    Ok(())
+}
+
+fn check_attr(att_type: &str, value: &str) -> Result<(), xml::Error> {
+    let text = format!("<!DOCTYPE a [<!ATTLIST a v {} #IMPLIED>]><a v='{}'/>", att_type, value);
+    check(&text)
+}
+
+#[test]
+fn attr_types_01() {
+    check_attr("NMTOKENS", "a b:c  1.2\n-d").unwrap();
+    check_attr("NMTOKEN", " 1.2 ").unwrap();
+    check_attr("IDREFS", "a b").unwrap();
+    check_attr("IDREF", "a").unwrap();
+    check_attr("ID", "_a").unwrap();
+    check_attr("ENTITIES", "a b").unwrap();
+    check_attr("ENTITY", "a").unwrap();
+    check_attr("CDATA", "  ").unwrap();
+}
+
+#[test]
+fn attr_types_02() {
+    check_attr("(x|y)", "x").unwrap();
+    check_attr("( x | y )", " y ").unwrap();
+    check_attr("NOTATION (x|y)", "y").unwrap();
+}
+
+#[test]
+fn attr_types_03() {
+    // Values with references are not checked.
+    check_attr("(x|y)", "&#x7A;").unwrap();
+}
+
+#[test]
+fn attr_types_04() {
+    // Undeclared attributes and attributes of other elements are not checked.
+    check("<!DOCTYPE a [<!ATTLIST b v (x|y) #IMPLIED>]><a v='z'><b w='z'/></a>").unwrap();
+}
+
+#[test]
+fn attr_types_05() {
+    // Disabled by default.
+    let text = "<!DOCTYPE a [<!ATTLIST a v (x|y) #IMPLIED>]><a v='z'/>";
+    for token in xml::Tokenizer::from(text) {
+        token.unwrap();
+    }
+}
+
+#[test]
+fn attr_types_err_01() {
+    let err = check("<!DOCTYPE a [<!ATTLIST a v (x|y) #IMPLIED>]>\n<a v='z'/>").unwrap_err();
+    assert_eq!(err.to_string(), "invalid attribute value at 2:4");
+}
+
+#[test]
+fn attr_types_err_02() {
+    assert!(check_attr("(x|y)", "x y").is_err());
+    assert!(check_attr("(x|y)", "").is_err());
+    assert!(check_attr("NOTATION (x|y)", "z").is_err());
+    assert!(check_attr("NMTOKENS", "a $").is_err());
+    assert!(check_attr("NMTOKENS", " ").is_err());
+    assert!(check_attr("NMTOKEN", "a b").is_err());
+    assert!(check_attr("IDREFS", "a 1b").is_err());
+    assert!(check_attr("IDREF", "").is_err());
+    assert!(check_attr("ID", "1").is_err());
+    assert!(check_attr("ENTITY", "a b").is_err());
+}
+
+#[test]
+fn attr_types_with_ids() {
+    let text = "<!DOCTYPE a [<!ATTLIST b id ID #IMPLIED>]><a><b id='1'/><b id='x'/><b id='x'/></a>";
+
+    let mut p = xml::Tokenizer::from(text);
+    p.enable_attribute_type_checking();
+    p.enable_id_checking();
+    let errors: Vec<_> = p.filter_map(|t| t.err()).map(|e| e.to_string()).collect();
+    assert_eq!(errors, vec!["invalid attribute value at 1:49".to_string()]);
+}
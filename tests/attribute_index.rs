@@ -0,0 +1,38 @@
+extern crate xmlparser as xml;
+
+fn indices(p: xml::Tokenizer) -> Vec<(String, usize)> {
+    let mut list = Vec::new();
+    for token in p {
+        if let xml::Token::Attribute((_, local), _, index) = token.unwrap() {
+            list.push((local.to_string(), index));
+        }
+    }
+
+    list
+}
+
+fn expected(list: &[(&str, usize)]) -> Vec<(String, usize)> {
+    list.iter().map(|&(n, i)| (n.to_string(), i)).collect()
+}
+
+#[test]
+fn attribute_index_01() {
+    let p = xml::Tokenizer::from("<a z='1' y='2' x:b='3'><b c='4' a='5'/><c/><d d='6'/></a>");
+    assert_eq!(indices(p), expected(&[
+        ("z", 0), ("y", 1), ("b", 2),
+        ("c", 0), ("a", 1),
+        ("d", 0),
+    ]));
+}
+
+#[test]
+fn attribute_index_02() {
+    let mut p = xml::Tokenizer::from("<a b='1' c='2'/>");
+    p.set_lazy_attributes_mode();
+    p.next(); // ElementStart
+    let list: Vec<_> = p.attributes().map(|t| match t.unwrap() {
+        xml::Token::Attribute((_, local), _, index) => (local.to_string(), index),
+        _ => unreachable!(),
+    }).collect();
+    assert_eq!(list, expected(&[("b", 0), ("c", 1)]));
+}
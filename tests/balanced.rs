@@ -0,0 +1,45 @@
+extern crate xmlparser as xml;
+
+fn balanced(text: &str, fragment: bool) -> Vec<bool> {
+    let mut p = xml::Tokenizer::from(text);
+    if fragment {
+        p.set_fragment_mode();
+    }
+
+    let mut list = vec![p.is_balanced()];
+    while p.next().is_some() {
+        list.push(p.is_balanced());
+    }
+
+    list
+}
+
+#[test]
+fn balanced_01() {
+    // Start, <a, b='1', >, <c, />, </a>, <!--d-->, end.
+    assert_eq!(balanced("<a b='1'><c/></a><!--d-->", false),
+               vec![false, false, false, false, false, false, true, true]);
+}
+
+#[test]
+fn balanced_02() {
+    // An unclosed element.
+    assert_eq!(balanced("<a><b>", false), vec![false, false, false, false, false]);
+}
+
+#[test]
+fn balanced_03() {
+    assert_eq!(balanced("text<a x='1'/>", true), vec![true, true, false, false, true]);
+    assert_eq!(balanced("<a>", true), vec![true, false, false]);
+}
+
+#[test]
+fn balanced_04() {
+    // An error.
+    let mut p = xml::Tokenizer::from("<a/><b/>");
+    p.next();
+    p.next();
+    assert!(p.is_balanced());
+    assert!(p.next().unwrap().is_err());
+    assert!(!p.is_balanced());
+}
@@ -0,0 +1,73 @@
+extern crate xmlparser as xml;
+
+use xml::{PushTokenizer, Stream, Tokenizer};
+
+// Parses the text in all modes and returns the last error, if any.
+fn parse(text: &str) -> Option<String> {
+    let mut last_error = None;
+    for mode in 0..3 {
+        let mut p = Tokenizer::from(text);
+        match mode {
+            1 => p.set_strict_mode(),
+            2 => p.set_fragment_mode(),
+            _ => {}
+        }
+
+        for token in p {
+            if let Err(e) = token {
+                last_error = Some(e.to_string());
+            }
+        }
+    }
+
+    last_error
+}
+
+#[test]
+fn bounds_01() {
+    let list = [
+        "<", "<!", "<!-", "<!--", "<!-- a -", "<?", "<?xml", "<?xml ", "<?xml version=",
+        "<![CDATA[", "<![CDATA[a]]", "<!DOCTYPE", "<!DOCTYPE a [", "<!DOCTYPE a [<!ENTITY",
+        "<!DOCTYPE a [<!ENTITY b '&#", "<!DOCTYPE a [<!ATTLIST a b (", "<a", "<a b", "<a b=",
+        "<a b='", "<a b='&#", "<a>&", "<a>&#", "<a>&#x", "<a>&#x1", "<a></", "<a></a",
+    ];
+
+    for text in list.iter() {
+        assert!(parse(text).is_some(), "{:?}", text);
+    }
+}
+
+#[test]
+fn bounds_02() {
+    // Multi-byte characters at the end of the input.
+    let list = [
+        "<т", "<a т", "<a b='т", "<!-- т", "<?т", "<a>т", "</т", "<?xml version='т",
+        "<!DOCTYPE т", "\u{FEFF}", "\u{FEFF}<", "<a>😀&#x", "<😀/>",
+    ];
+
+    for text in list.iter() {
+        parse(text);
+    }
+}
+
+#[test]
+fn bounds_03() {
+    // Truncated UTF-8 sequences.
+    let text = "<a b='т'>т😀</a>".as_bytes();
+    for i in 0..text.len() {
+        let mut p = PushTokenizer::new();
+        p.feed(&text[..i]);
+        p.end_of_input();
+        while p.next_token().is_some() {}
+    }
+}
+
+#[test]
+fn bounds_04() {
+    // A position inside a multi-byte character.
+    let mut s = Stream::from("тт");
+    s.advance(1);
+    assert_eq!(s.curr_char().unwrap_err().to_string(), "unexpected character '\\u{82}' at 1:1");
+    assert!(s.consume_name().is_err());
+    assert!(s.skip_string(b"a").is_err());
+}
@@ -68,3 +68,45 @@ test!(cdata_09, "<p><![CDATA[bracket ]after]]></p>",
     Token::Cdata("bracket ]after"),
     Token::ElementEnd(ElementEnd::Close("", "p"))
 );
+
+test!(cdata_10, "<p><![CDATA[bracket ]>after]]></p>",
+    Token::ElementStart("", "p"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Cdata("bracket ]>after"),
+    Token::ElementEnd(ElementEnd::Close("", "p"))
+);
+
+test!(cdata_11, "<p><![CDATA[]]]></p>",
+    Token::ElementStart("", "p"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Cdata("]"),
+    Token::ElementEnd(ElementEnd::Close("", "p"))
+);
+
+test!(cdata_12, "<p><![CDATA[data]]]]>]]></p>",
+    Token::ElementStart("", "p"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Cdata("data]]"),
+    Token::Text("]]>"),
+    Token::ElementEnd(ElementEnd::Close("", "p"))
+);
+
+test!(cdata_13, "<p><![CDATA[]]></p>",
+    Token::ElementStart("", "p"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Cdata(""),
+    Token::ElementEnd(ElementEnd::Close("", "p"))
+);
+
+test!(cdata_14, "<p><![CDATA[a]] >b]]></p>",
+    Token::ElementStart("", "p"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Cdata("a]] >b"),
+    Token::ElementEnd(ElementEnd::Close("", "p"))
+);
+
+test!(cdata_err_01, "<p><![CDATA[data]]</p>",
+    Token::ElementStart("", "p"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Error("invalid token 'CDATA' at 1:4 cause expected ']]>' not '' at 1:23".to_string())
+);
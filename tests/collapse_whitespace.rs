@@ -0,0 +1,80 @@
+extern crate xmlparser as xml;
+
+use std::borrow::Cow;
+
+use xml::{collapse_whitespace, Token, Tokenizer, XmlSpace};
+
+fn collect(text: &str) -> Vec<String> {
+    let mut p = Tokenizer::from(text);
+    p.enable_xml_space_tracking();
+
+    let mut texts = Vec::new();
+    while let Some(token) = p.next() {
+        if let Token::Text(text) = token.unwrap() {
+            texts.push(p.collapse_whitespace(text).into_owned());
+        }
+    }
+
+    texts
+}
+
+#[test]
+fn collapse_whitespace_01() {
+    assert_eq!(collapse_whitespace("a  b\t\tc\r\nd"), "a b c d");
+    assert_eq!(collapse_whitespace("  \n a b \t"), "a b");
+    assert_eq!(collapse_whitespace(" \n "), "");
+}
+
+#[test]
+fn collapse_whitespace_02() {
+    match collapse_whitespace("a b c") {
+        Cow::Borrowed(s) => assert_eq!(s, "a b c"),
+        Cow::Owned(s) => panic!("unexpected allocation: {}", s),
+    }
+
+    // Only trimmed.
+    match collapse_whitespace("  a b  ") {
+        Cow::Borrowed(s) => assert_eq!(s, "a b"),
+        Cow::Owned(s) => panic!("unexpected allocation: {}", s),
+    }
+
+    match collapse_whitespace("a\nb") {
+        Cow::Owned(s) => assert_eq!(s, "a b"),
+        Cow::Borrowed(s) => panic!("expected an allocation: {}", s),
+    }
+}
+
+#[test]
+fn collapse_whitespace_03() {
+    // Non-ASCII spaces are not XML spaces.
+    assert_eq!(collapse_whitespace("\u{A0}a\u{A0} b"), "\u{A0}a\u{A0} b");
+}
+
+#[test]
+fn xml_space_01() {
+    assert_eq!(collect("<a>  text  </a>"), vec!["text"]);
+    assert_eq!(collect("<a xml:space='preserve'>  1  <b>  2  </b><c xml:space='default'>  3  </c>  4  </a>"),
+               vec!["  1  ", "  2  ", "3", "  4  "]);
+}
+
+#[test]
+fn xml_space_02() {
+    // An empty element doesn't affect siblings.
+    assert_eq!(collect("<a><b xml:space='preserve'/>  1  </a>"), vec!["1"]);
+}
+
+#[test]
+fn xml_space_03() {
+    let mut p = Tokenizer::from("<a xml:space='preserve'><b/></a>");
+    assert_eq!(p.xml_space(), XmlSpace::Default);
+    p.enable_xml_space_tracking();
+    p.next(); // ElementStart
+    p.next(); // Attribute
+    assert_eq!(p.xml_space(), XmlSpace::Preserve);
+    p.next(); // ElementEnd::Open
+    p.next(); // ElementStart
+    assert_eq!(p.xml_space(), XmlSpace::Preserve);
+    p.next(); // ElementEnd::Empty
+    p.next(); // ElementEnd::Close
+    assert_eq!(p.xml_space(), XmlSpace::Default);
+}
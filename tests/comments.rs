@@ -16,6 +16,8 @@ test!(comment_10, "<!--<-->", Token::Comment("<"));
 test!(comment_11, "<!--<--->", Token::Comment("<-"));
 test!(comment_12, "<!--<!-->", Token::Comment("<!"));
 test!(comment_13, "<!---->", Token::Comment(""));
+test!(comment_14, "<!--a- ->b-->", Token::Comment("a- ->b"));
+test!(comment_15, "<!--a->b-->", Token::Comment("a->b"));
 
 macro_rules! test_err {
     ($name:ident, $text:expr) => (
@@ -23,7 +25,7 @@ macro_rules! test_err {
         fn $name() {
             let mut p = xml::Tokenizer::from($text);
             assert_eq!(p.next().unwrap().unwrap_err().to_string(),
-                       "invalid token 'Comment' at 1:1");
+                       "invalid token 'comment' at 1:1");
         }
     )
 }
@@ -59,3 +61,26 @@ test_err!(comment_err_28, "<!--<!-<!--x-->");
 test_err!(comment_err_29, "<!----!->");
 test_err!(comment_err_30, "<!----!x>");
 test_err!(comment_err_31, "<!-----x>");
+
+// `--` must be followed by `>`.
+test_err!(comment_err_32, "<!--a--b-->");
+test_err!(comment_err_33, "<!--a-- >-->");
+
+fn comment_span(text: &str) -> (usize, usize, String) {
+    let token = xml::Tokenizer::from(text).next().unwrap().unwrap();
+    let span = token.as_comment().unwrap();
+    (span.start(), span.end(), span.to_str().to_string())
+}
+
+#[test]
+fn comment_text_01() {
+    assert_eq!(comment_span("<!---->"), (4, 4, String::new()));
+    assert_eq!(comment_span("<!-- a -->"), (4, 7, " a ".to_string()));
+    assert_eq!(comment_span("<!--\n-a\n-->"), (4, 8, "\n-a\n".to_string()));
+}
+
+#[test]
+fn comment_text_02() {
+    let token = xml::Tokenizer::from("<?a?>").next().unwrap().unwrap();
+    assert!(token.as_comment().is_none());
+}
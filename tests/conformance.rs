@@ -0,0 +1,43 @@
+extern crate xmlparser as xml;
+
+use xml::{run_conformance_case, ConformanceResult};
+
+// Sample cases in the W3C XML Conformance Test Suite format.
+const CASES: &[(&str, &str, bool)] = &[
+    ("valid-01", "<?xml version='1.0'?>\n<!DOCTYPE doc [<!ELEMENT doc (#PCDATA)>]>\n<doc>text</doc>\n", true),
+    ("valid-02", "<doc><a x='1'/><!-- c --><?pi?><![CDATA[<>]]></doc>", true),
+    ("not-wf-01", "<doc><a></doc>", false),
+    ("not-wf-02", "<doc>", false),
+    ("not-wf-03", "<doc>\u{1}</doc>", false),
+    ("not-wf-04", "<doc a='1' a='2'/>", false),
+];
+
+#[test]
+fn conformance_01() {
+    for &(id, input, expect_valid) in CASES {
+        assert!(run_conformance_case(input, expect_valid).is_passed(), "{}", id);
+    }
+}
+
+#[test]
+fn conformance_02() {
+    match run_conformance_case("<doc><a></doc>", true) {
+        ConformanceResult::UnexpectedError(e) => {
+            assert_eq!(e.to_string(), "unexpected close tag at 1:9");
+        }
+        _ => panic!(),
+    }
+
+    match run_conformance_case("<doc/>", false) {
+        ConformanceResult::UnexpectedSuccess => {}
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn conformance_03() {
+    match run_conformance_case("<doc>\u{1}</doc>", false) {
+        ConformanceResult::Passed(Some(_)) => {}
+        _ => panic!(),
+    }
+}
@@ -0,0 +1,57 @@
+extern crate xmlparser as xml;
+
+#[macro_use] mod token;
+use token::*;
+
+macro_rules! test_strict {
+    ($name:ident, $text:expr, $err:expr) => (
+        #[test]
+        fn $name() {
+            assert_eq!(xml::validate($text).map_err(|e| e.to_string()), $err);
+        }
+    )
+}
+
+test!(control_chars_01, "<a>\t\r\n</a>",
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Whitespaces("\t\r\n"),
+    Token::ElementEnd(ElementEnd::Close("", "a"))
+);
+
+// Passed as is by default.
+test!(control_chars_02, "<a x='\u{B}'>\u{B}</a>",
+    Token::ElementStart("", "a"),
+    Token::Attribute("", "x", "\u{B}"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Text("\u{B}"),
+    Token::ElementEnd(ElementEnd::Close("", "a"))
+);
+
+test!(control_chars_err_01, "<a>text\0</a>",
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Error("invalid token 'character data' at 1:4 cause unexpected character '\\0' at 1:8".to_string())
+);
+
+test!(control_chars_err_02, "<a x='\0'/>",
+    Token::ElementStart("", "a"),
+    Token::Error("invalid token 'attribute' at 1:4 cause unexpected character '\\0' at 1:7".to_string())
+);
+
+test!(control_chars_err_03, "<a><![CDATA[\0]]></a>",
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Error("invalid token 'CDATA' at 1:4 cause unexpected character '\\0' at 1:13".to_string())
+);
+
+test_strict!(control_chars_strict_01, "<a x='\t\r\n'>\t\r\n<![CDATA[\t\r\n]]>\ttext\r\n</a>", Ok(()));
+
+test_strict!(control_chars_strict_02, "<a>\n text\u{B}</a>",
+    Err("invalid token 'character data' at 1:4 cause unexpected character '\\u{b}' at 2:6".to_string()));
+
+test_strict!(control_chars_strict_03, "<a x='\u{1F}'/>",
+    Err("invalid token 'attribute' at 1:4 cause unexpected character '\\u{1f}' at 1:7".to_string()));
+
+test_strict!(control_chars_strict_04, "<a>\0</a>",
+    Err("invalid token 'character data' at 1:4 cause unexpected character '\\0' at 1:4".to_string()));
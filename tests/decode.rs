@@ -0,0 +1,64 @@
+extern crate xmlparser as xml;
+
+use std::borrow::Cow;
+
+use xml::{StrSpan, TextUnescape, Token, Tokenizer, XmlSpace};
+
+fn decode(text: &str, normalize_spaces: bool) -> Result<String, String> {
+    let space = if normalize_spaces { XmlSpace::Default } else { XmlSpace::Preserve };
+    TextUnescape::decode(StrSpan::from(text), space)
+        .map(|s| s.into_owned())
+        .map_err(|e| e.to_string())
+}
+
+#[test]
+fn decode_01() {
+    assert_eq!(decode("&lt;&gt;&amp;&apos;&quot;", false).unwrap(), "<>&'\"");
+    assert_eq!(decode("&#x1F600;&#128512;&#x41;", false).unwrap(), "\u{1F600}\u{1F600}A");
+}
+
+#[test]
+fn decode_02() {
+    // Line endings.
+    assert_eq!(decode("a\r\nb\rc\nd\r", false).unwrap(), "a\nb\nc\nd\n");
+    assert_eq!(decode("a\r\nb\rc\nd\te", true).unwrap(), "a b c d e");
+    assert_eq!(decode("  a  ", true).unwrap(), "  a  ");
+}
+
+#[test]
+fn decode_03() {
+    // Spaces from references are not normalized.
+    assert_eq!(decode("&#xD;&#xA;&#x9;", true).unwrap(), "\r\n\t");
+}
+
+#[test]
+fn decode_04() {
+    // Nothing to decode.
+    match TextUnescape::decode(StrSpan::from("text\n"), XmlSpace::Preserve).unwrap() {
+        Cow::Borrowed(s) => assert_eq!(s, "text\n"),
+        Cow::Owned(_) => panic!(),
+    }
+}
+
+#[test]
+fn decode_err_01() {
+    assert_eq!(decode("a & b", false).unwrap_err(), "invalid reference at 1:3");
+    assert_eq!(decode("&ent;", false).unwrap_err(), "invalid reference at 1:1");
+    assert_eq!(decode("&#x0;", false).unwrap_err(), "invalid reference at 1:1");
+    assert_eq!(decode("&#xD800;", false).unwrap_err(), "invalid reference at 1:1");
+    assert_eq!(decode("&#x110000;", false).unwrap_err(), "invalid reference at 1:1");
+    assert_eq!(decode("&#;", false).unwrap_err(), "invalid reference at 1:1");
+    assert_eq!(decode("&amp", false).unwrap_err(), "invalid reference at 1:1");
+}
+
+#[test]
+fn decode_err_02() {
+    // Positions are in the original text.
+    let text = "<a>\n<b c='1 &#xFFFE;'/></a>";
+    let value = Tokenizer::from(text).filter_map(|t| match t.unwrap() {
+        Token::Attribute(_, value, _) => Some(value),
+        _ => None,
+    }).next().unwrap();
+    assert_eq!(TextUnescape::decode(value, XmlSpace::Default).unwrap_err().to_string(),
+               "invalid reference at 2:9");
+}
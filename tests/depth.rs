@@ -0,0 +1,61 @@
+extern crate xmlparser as xml;
+
+fn depths(text: &str) -> Vec<(String, usize)> {
+    let mut p = xml::Tokenizer::from(text);
+    let mut list = Vec::new();
+    while let Some(token) = p.next() {
+        let name = match token.unwrap() {
+            xml::Token::ElementStart(_, local) => format!("<{}", local),
+            xml::Token::Attribute((_, local), _, _) => format!("@{}", local),
+            xml::Token::ElementEnd(xml::ElementEnd::Open) => ">".to_string(),
+            xml::Token::ElementEnd(xml::ElementEnd::Empty) => "/>".to_string(),
+            xml::Token::ElementEnd(xml::ElementEnd::Close(_, local)) => format!("</{}>", local),
+            xml::Token::Text(text) => text.to_str().to_string(),
+            _ => "?".to_string(),
+        };
+
+        list.push((name, p.depth()));
+    }
+
+    list
+}
+
+#[test]
+fn depth_01() {
+    let list = depths("<!--c--><a x='1'><b><c/>t</b><d>t</d></a><!--c-->");
+    let expected = vec![
+        ("?", 0),
+        ("<a", 0), ("@x", 0), (">", 0),
+        ("<b", 1), (">", 1),
+        ("<c", 2), ("/>", 2),
+        ("t", 2),
+        ("</b>", 1),
+        ("<d", 1), (">", 1), ("t", 2), ("</d>", 1),
+        ("</a>", 0),
+        ("?", 0),
+    ];
+    let expected: Vec<_> = expected.into_iter().map(|(n, d)| (n.to_string(), d)).collect();
+    assert_eq!(list, expected);
+}
+
+#[test]
+fn depth_02() {
+    let mut p = xml::Tokenizer::from("<a/>");
+    assert_eq!(p.depth(), 0);
+    p.next();
+    assert_eq!(p.depth(), 0);
+    p.next();
+    assert_eq!(p.depth(), 0);
+}
+
+#[test]
+fn depth_03() {
+    let mut p = xml::Tokenizer::from("<a><b>");
+    p.set_fragment_mode();
+    let mut list = Vec::new();
+    while let Some(Ok(_)) = p.next() {
+        list.push(p.depth());
+    }
+
+    assert_eq!(list, vec![0, 0, 1, 1]);
+}
@@ -0,0 +1,74 @@
+extern crate xmlparser as xml;
+
+use xml::{diff, DiffOptions, ErrorPos};
+
+fn pos(row: u32, col: u32) -> ErrorPos {
+    ErrorPos::new(row, col)
+}
+
+#[test]
+fn diff_01() {
+    let text = "<?xml version='1.0'?><!DOCTYPE a><a x='1'><!--c--><b>text</b><![CDATA[d]]></a>";
+    assert_eq!(diff(text, text, DiffOptions::default()), None);
+}
+
+#[test]
+fn diff_02() {
+    // Quotes and spaces inside tokens are not significant.
+    assert_eq!(diff("<a x='1'  />", "<a x = \"1\"/>", DiffOptions::default()), None);
+}
+
+#[test]
+fn diff_03() {
+    let a = "<a x='1' y='2'/>";
+    let b = "<a y='2' x='1'/>";
+    assert_eq!(diff(a, b, DiffOptions::default()), Some((pos(1, 4), pos(1, 4))));
+
+    let opt = DiffOptions { ignore_attributes_order: true, .. DiffOptions::default() };
+    assert_eq!(diff(a, b, opt), None);
+}
+
+#[test]
+fn diff_04() {
+    let a = "<a>\n  <b>one</b>\n</a>";
+    let b = "<a>\n  <b>two</b>\n</a>";
+    assert_eq!(diff(a, b, DiffOptions::default()), Some((pos(2, 6), pos(2, 6))));
+}
+
+#[test]
+fn diff_05() {
+    let a = "<a>\n  <b/>\n</a>";
+    let b = "<a><b/></a>";
+    assert_eq!(diff(a, b, DiffOptions::default()), Some((pos(1, 4), pos(1, 4))));
+
+    let opt = DiffOptions { ignore_whitespaces: true, .. DiffOptions::default() };
+    assert_eq!(diff(a, b, opt), None);
+}
+
+#[test]
+fn diff_06() {
+    assert_eq!(diff("<a><b/></a>", "<a/>", DiffOptions::default()),
+               Some((pos(1, 3), pos(1, 3))));
+}
+
+#[test]
+fn diff_07() {
+    assert_eq!(diff("<a/><!--c-->", "<a/>", DiffOptions::default()),
+               Some((pos(1, 5), pos(1, 5))));
+}
+
+#[test]
+fn diff_08() {
+    // Attributes of different elements are not mixed.
+    let opt = DiffOptions { ignore_attributes_order: true, .. DiffOptions::default() };
+    assert_eq!(diff("<a x='1'><b y='2'/></a>", "<a y='2'><b x='1'/></a>", opt),
+               Some((pos(1, 4), pos(1, 4))));
+}
+
+#[test]
+fn diff_09() {
+    // A token after the sorted attributes.
+    let opt = DiffOptions { ignore_attributes_order: true, .. DiffOptions::default() };
+    assert_eq!(diff("<a x='1' y='2'><b/></a>", "<a y='2' x='1'><c/></a>", opt),
+               Some((pos(1, 16), pos(1, 16))));
+}
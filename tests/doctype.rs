@@ -30,6 +30,23 @@ test!(dtd_06, "<!DOCTYPE greeting><a/>",
     Token::ElementEnd(ElementEnd::Empty)
 );
 
+test!(dtd_07, "<!DOCTYPE greeting SYSTEM 'hello.dtd' [<!ENTITY a 'b'>]>",
+    Token::DtdStart("greeting", Some(ExternalId::System("hello.dtd"))),
+    Token::EntityDecl("a", EntityDefinition::EntityValue("b")),
+    Token::DtdEnd
+);
+
+test!(dtd_08, "<!DOCTYPE greeting PUBLIC 'hello.dtd' 'goodbye.dtd' >",
+    Token::EmptyDtd("greeting", Some(ExternalId::Public("hello.dtd", "goodbye.dtd")))
+);
+
+test!(dtd_09, "<!DOCTYPE greeting [\n]><a/>",
+    Token::DtdStart("greeting", None),
+    Token::DtdEnd,
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Empty)
+);
+
 test!(dtd_entity_01,
 "<!DOCTYPE svg [
     <!ENTITY ns_extend \"http://ns.adobe.com/Extensibility/1.0/\">
@@ -111,17 +128,48 @@ test!(dtd_entity_06,
     Token::DtdEnd
 );
 
+test!(dtd_entity_07,
+"<!DOCTYPE svg [
+    <!ENTITY a '<b attr=\"&quot;\">&c;&#x20;</b><!-- d --><?e?>'>
+    <!ENTITY % f 'a < b'>
+]>",
+    Token::DtdStart("svg", None),
+    Token::EntityDecl("a",
+        EntityDefinition::EntityValue("<b attr=\"&quot;\">&c;&#x20;</b><!-- d --><?e?>")
+    ),
+    Token::EntityDecl("f", EntityDefinition::EntityValue("a < b")),
+    Token::DtdEnd
+);
+
 test!(dtd_err_01, "<!DOCTYPEEG[<!ENTITY%ETT\u{000a}SSSSSSSS<D_IDYT;->\u{000a}<",
-    Token::Error("invalid token 'Doctype Declaration' at 1:1 cause expected space not 'E' at 1:10".to_string())
+    Token::Error("invalid token 'DOCTYPE declaration' at 1:1 cause expected space not 'E' at 1:10".to_string())
 );
 
 test!(dtd_err_02, "<!DOCTYPE s [<!ENTITY % name S YSTEM",
     Token::DtdStart("s", None),
-    Token::Error("invalid token 'Doctype Entity Declaration' at 1:14 cause invalid ExternalID".to_string())
+    Token::Error("invalid token 'entity declaration' at 1:14 cause invalid ExternalID".to_string())
 );
 
 test!(dtd_err_03, "<!DOCTYPE s [<!ENTITY % name B",
     Token::DtdStart("s", None),
-    Token::Error("invalid token 'Doctype Entity Declaration' at 1:14 cause \
-                  expected '\"', ''', 'S', 'P' not 'B' at 1:30".to_string())
+    Token::Error("invalid token 'entity declaration' at 1:14 cause \
+                  expected one of ['\"', '\\'', 'S', 'P'] but found 'B' at 1:30".to_string())
+);
+
+test!(dtd_err_04, "<!DOCTYPE s [<!ENTITY a 'b < c'>]>",
+    Token::DtdStart("s", None),
+    Token::Error("invalid token 'entity declaration' at 1:14 cause \
+                  unescaped '<' in entity value at 1:28".to_string())
+);
+
+test!(dtd_err_05, "<!DOCTYPE s [<!ENTITY a 'b & c'>]>",
+    Token::DtdStart("s", None),
+    Token::Error("invalid token 'entity declaration' at 1:14 cause \
+                  unescaped '&' in entity value at 1:28".to_string())
+);
+
+test!(dtd_err_06, "<!DOCTYPE s [<!ENTITY a 'b<'>]>",
+    Token::DtdStart("s", None),
+    Token::Error("invalid token 'entity declaration' at 1:14 cause \
+                  unescaped '<' in entity value at 1:27".to_string())
 );
@@ -19,16 +19,50 @@ test!(document_05, str::from_utf8(b"\xEF\xBB\xBF<a/>").unwrap(),
     Token::ElementEnd(ElementEnd::Empty)
 );
 
+// U+FFE5 starts with 0xEF, but it's not a BOM.
+test!(document_05_1, "<a>\u{FFE5}</a>",
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Text("\u{FFE5}"),
+    Token::ElementEnd(ElementEnd::Close("", "a"))
+);
+
 test!(document_err_01, "<![CDATA[text]]>",
     Token::Error("unexpected token 'CDATA' at 1:1".to_string())
 );
 
 test!(document_err_02, " &www---------Ӥ+----------w-----www_",
-    Token::Error("unknown token at 1:2".to_string())
+    Token::Error("unexpected token 'character data' at 1:2".to_string())
 );
 
 test!(document_err_03, "q",
-    Token::Error("unknown token at 1:1".to_string())
+    Token::Error("unexpected token 'character data' at 1:1".to_string())
+);
+
+test!(document_06_1, "  \n<a/>",
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Empty)
+);
+
+test!(document_06_2, "<!--c--><a/>",
+    Token::Comment("c"),
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Empty)
+);
+
+test!(document_err_06, "hello<a/>",
+    Token::Error("unexpected token 'character data' at 1:1".to_string())
+);
+
+test!(document_err_07, "<?xml version='1.0'?>\n hello <a/>",
+    Token::Declaration("1.0", None, None),
+    Token::Error("unexpected token 'character data' at 2:2".to_string())
+);
+
+test!(document_err_08, "<a/> hello",
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Empty),
+    Token::Error("unexpected token 'character data' at 1:6".to_string())
 );
 
 test!(document_err_04, "<!>",
@@ -56,3 +90,165 @@ fn parse_fragment_1() {
         _ => panic!(),
     }
 }
+
+fn collect_fragment(text: &str, allow_declaration: bool) -> Vec<Token<'_>> {
+    let mut p = xml::Tokenizer::from(text);
+    p.set_fragment_mode();
+    if allow_declaration {
+        p.enable_fragment_declaration();
+    }
+
+    p.map(|t| to_test_token(t)).collect()
+}
+
+#[test]
+fn parse_fragment_2() {
+    // A leading declaration is rejected by default.
+    assert_eq!(collect_fragment("<?xml version='1.0'?><p/>", false), vec![
+        Token::Error("unexpected token 'XML declaration' at 1:1".to_string()),
+    ]);
+}
+
+#[test]
+fn parse_fragment_3() {
+    assert_eq!(collect_fragment("<?xml version='1.0'?><p/>text", true), vec![
+        Token::Declaration("1.0", None, None),
+        Token::ElementStart("", "p"),
+        Token::ElementEnd(ElementEnd::Empty),
+        Token::Text("text"),
+    ]);
+}
+
+#[test]
+fn parse_fragment_4() {
+    // Only at the start of a fragment.
+    assert_eq!(collect_fragment("<p/><?xml version='1.0'?>", true), vec![
+        Token::ElementStart("", "p"),
+        Token::ElementEnd(ElementEnd::Empty),
+        Token::Error("unexpected token 'XML declaration' at 1:5".to_string()),
+    ]);
+}
+
+test!(document_06, "<!--c-->&#x20;&#32;<a/>&#xA;",
+    Token::Comment("c"),
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Empty)
+);
+
+test!(document_07, "<!DOCTYPE a [&#x20;<!ENTITY b 'c'>&#xA;]>",
+    Token::DtdStart("a", None),
+    Token::EntityDecl("b", EntityDefinition::EntityValue("c")),
+    Token::DtdEnd
+);
+
+// Character references are not spaces inside a markup.
+test!(document_err_05, "<a&#x20;x='1'/>",
+    Token::ElementStart("", "a"),
+    Token::Error("invalid token 'attribute' at 1:3 cause invalid name token while parsing element 'a'".to_string())
+);
+
+#[test]
+fn parse_multi_document_1() {
+    let s = "<?xml version='1.0'?>\n<a/>\n<!--c-->\n<?xml version='1.0'?>\n<b>text</b>\n<c/>";
+    let mut p = xml::Tokenizer::from(s);
+    p.set_multi_document_mode();
+
+    let tokens: Vec<_> = p.map(|t| to_test_token(t)).collect();
+    assert_eq!(tokens, vec![
+        Token::Declaration("1.0", None, None),
+        Token::ElementStart("", "a"),
+        Token::ElementEnd(ElementEnd::Empty),
+        Token::Comment("c"),
+        Token::DocumentBoundary,
+        Token::Declaration("1.0", None, None),
+        Token::ElementStart("", "b"),
+        Token::ElementEnd(ElementEnd::Open),
+        Token::Text("text"),
+        Token::ElementEnd(ElementEnd::Close("", "b")),
+        Token::DocumentBoundary,
+        Token::ElementStart("", "c"),
+        Token::ElementEnd(ElementEnd::Empty),
+    ]);
+}
+
+#[test]
+fn parse_multi_document_2() {
+    let s = "<a/><!DOCTYPE b><b/>";
+    let mut p = xml::Tokenizer::from(s);
+    p.set_multi_document_mode();
+    p.set_strict_mode();
+
+    let tokens: Vec<_> = p.map(|t| to_test_token(t)).collect();
+    assert_eq!(tokens, vec![
+        Token::ElementStart("", "a"),
+        Token::ElementEnd(ElementEnd::Empty),
+        Token::DocumentBoundary,
+        Token::EmptyDtd("b", None),
+        Token::ElementStart("", "b"),
+        Token::ElementEnd(ElementEnd::Empty),
+    ]);
+}
+
+#[test]
+fn parse_multi_document_3() {
+    // Text is still not allowed after the root element.
+    let mut p = xml::Tokenizer::from("<a/>text<b/>");
+    p.set_multi_document_mode();
+
+    let tokens: Vec<_> = p.map(|t| to_test_token(t)).collect();
+    assert_eq!(tokens, vec![
+        Token::ElementStart("", "a"),
+        Token::ElementEnd(ElementEnd::Empty),
+        Token::Error("unexpected token 'character data' at 1:5".to_string()),
+    ]);
+}
+
+#[test]
+fn from_fragment_1() {
+    let text = "<msg>\n<a/>text<b x='1'/></msg>";
+    let p = xml::Tokenizer::from_fragment(text, 6..24).unwrap();
+    let tokens: Vec<_> = p.map(to_test_token).collect();
+    assert_eq!(tokens, vec![
+        Token::ElementStart("", "a"),
+        Token::ElementEnd(ElementEnd::Empty),
+        Token::Text("text"),
+        Token::ElementStart("", "b"),
+        Token::Attribute("", "x", "1"),
+        Token::ElementEnd(ElementEnd::Empty),
+    ]);
+}
+
+#[test]
+fn from_fragment_2() {
+    // Positions are relative to the whole text.
+    let text = "<msg>\n<a b=></a></msg>";
+    let mut p = xml::Tokenizer::from_fragment(text, 6..16).unwrap();
+    p.next();
+    assert_eq!(p.next().unwrap().unwrap_err().to_string(),
+               "invalid token 'attribute' at 2:3 cause \
+                expected quote mark not '>' at 2:6 while parsing element 'a'");
+}
+
+#[test]
+fn from_fragment_3() {
+    // Balance is checked only within the fragment.
+    let text = "<msg><a></msg>";
+    let mut p = xml::Tokenizer::from_fragment(text, 5..8).unwrap();
+    p.set_strict_mode();
+    let err = p.filter_map(|t| t.err()).next().unwrap();
+    assert_eq!(err.to_string(), "unclosed element at 1:6");
+
+    let mut p = xml::Tokenizer::from_fragment("<msg></a></msg>", 5..9).unwrap();
+    p.set_strict_mode();
+    let err = p.filter_map(|t| t.err()).next().unwrap();
+    assert_eq!(err.to_string(), "unexpected close tag at 1:6");
+}
+
+#[test]
+fn from_fragment_4() {
+    let text = "<a>т</a>";
+    // Splits a two-byte character.
+    assert!(xml::Tokenizer::from_fragment(text, 4..5).is_none());
+    assert!(xml::Tokenizer::from_fragment(text, 3..10).is_none());
+    assert!(xml::Tokenizer::from_fragment(text, 3..5).is_some());
+}
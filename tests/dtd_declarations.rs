@@ -0,0 +1,136 @@
+extern crate xmlparser as xml;
+
+#[macro_use] mod token;
+use token::*;
+
+macro_rules! test {
+    ($name:ident, $text:expr, $($token:expr),*) => (
+        #[test]
+        fn $name() {
+            let mut p = xml::Tokenizer::from($text);
+            p.enable_dtd_declaration_tokens();
+            $(
+                assert_eq!(to_test_token(p.next().unwrap()), $token);
+            )*
+            assert!(p.next().is_none());
+        }
+    )
+}
+
+test!(element_01, "<!DOCTYPE a [<!ELEMENT a EMPTY>]>",
+    Token::DtdStart("a", None),
+    Token::ElementDecl("a", "EMPTY"),
+    Token::DtdEnd
+);
+
+test!(element_02, "<!DOCTYPE a [<!ELEMENT note ( to , (from|by)* , body? ) >]>",
+    Token::DtdStart("a", None),
+    Token::ElementDecl("note", "( to , (from|by)* , body? )"),
+    Token::DtdEnd
+);
+
+test!(element_03, "<!DOCTYPE a [<!ELEMENT a (#PCDATA|b)*>]>",
+    Token::DtdStart("a", None),
+    Token::ElementDecl("a", "(#PCDATA|b)*"),
+    Token::DtdEnd
+);
+
+test!(element_err_01, "<!DOCTYPE a [<!ELEMENT a >]>",
+    Token::DtdStart("a", None),
+    Token::Error("invalid token 'element declaration' at 1:14 cause \
+                  expected one of ['E', 'A', '('] but found '>' at 1:26".to_string())
+);
+
+test!(attlist_01, "<!DOCTYPE a [<!ATTLIST b id ID #REQUIRED name CDATA #IMPLIED>]>",
+    Token::DtdStart("a", None),
+    Token::AttlistDecl("b", vec![
+        ("id", "ID", AttributeDefault::Required),
+        ("name", "CDATA", AttributeDefault::Implied),
+    ]),
+    Token::DtdEnd
+);
+
+test!(attlist_02, "<!DOCTYPE a [<!ATTLIST b type (x | y) 'x' lang CDATA #FIXED \"en\">]>",
+    Token::DtdStart("a", None),
+    Token::AttlistDecl("b", vec![
+        ("type", "(x | y)", AttributeDefault::Value("x")),
+        ("lang", "CDATA", AttributeDefault::Fixed("en")),
+    ]),
+    Token::DtdEnd
+);
+
+test!(attlist_03, "<!DOCTYPE a [<!ATTLIST b f NOTATION (png|gif) #IMPLIED>]>",
+    Token::DtdStart("a", None),
+    Token::AttlistDecl("b", vec![
+        ("f", "NOTATION (png|gif)", AttributeDefault::Implied),
+    ]),
+    Token::DtdEnd
+);
+
+test!(attlist_04, "<!DOCTYPE a [<!ATTLIST b >]>",
+    Token::DtdStart("a", None),
+    Token::AttlistDecl("b", vec![]),
+    Token::DtdEnd
+);
+
+test!(attlist_err_01, "<!DOCTYPE a [<!ATTLIST b id ID>]>",
+    Token::DtdStart("a", None),
+    Token::Error("invalid token 'attribute list declaration' at 1:14 cause \
+                  expected space not '>' at 1:31".to_string())
+);
+
+test!(notation_01, "<!DOCTYPE a [<!NOTATION png SYSTEM 'image/png'>]>",
+    Token::DtdStart("a", None),
+    Token::NotationDecl("png", None, Some("image/png")),
+    Token::DtdEnd
+);
+
+test!(notation_02, "<!DOCTYPE a [<!NOTATION png PUBLIC 'png' >]>",
+    Token::DtdStart("a", None),
+    Token::NotationDecl("png", Some("png"), None),
+    Token::DtdEnd
+);
+
+test!(notation_03, "<!DOCTYPE a [<!NOTATION png PUBLIC 'png' 'image/png'>]>",
+    Token::DtdStart("a", None),
+    Token::NotationDecl("png", Some("png"), Some("image/png")),
+    Token::DtdEnd
+);
+
+test!(notation_err_01, "<!DOCTYPE a [<!NOTATION png>]>",
+    Token::DtdStart("a", None),
+    Token::Error("invalid token 'notation declaration' at 1:14 cause \
+                  expected space not '>' at 1:28".to_string())
+);
+
+#[test]
+fn skipped_by_default() {
+    let mut p = xml::Tokenizer::from("<!DOCTYPE a [<!ELEMENT a ANY><!NOTATION n SYSTEM 'n'>]>");
+    assert_eq!(to_test_token(p.next().unwrap()), Token::DtdStart("a", None));
+    assert_eq!(to_test_token(p.next().unwrap()), Token::DtdEnd);
+}
+
+#[test]
+fn with_ids() {
+    let text = "<!DOCTYPE a [<!ATTLIST b id ID #IMPLIED>]><a><b id='x'/><b id='x'/></a>";
+    let mut p = xml::Tokenizer::from(text);
+    p.enable_dtd_declaration_tokens();
+    p.enable_id_checking();
+    let err = p.filter_map(|t| t.err()).next().unwrap();
+    assert_eq!(err.to_string(), "duplicated id at 1:60, previously defined at 1:49");
+}
+
+#[test]
+fn owned() {
+    let mut p = xml::Tokenizer::from("<!DOCTYPE a [<!ATTLIST b id ID 'x'>]>");
+    p.enable_dtd_declaration_tokens();
+    p.next();
+    assert_eq!(p.next().unwrap().unwrap().into_owned(), xml::OwnedToken::AttlistDeclaration(
+        "b".to_string(),
+        vec![xml::OwnedAttributeDefinition {
+            name: "id".to_string(),
+            att_type: "ID".to_string(),
+            default: xml::OwnedAttributeDefault::Value("x".to_string()),
+        }],
+    ));
+}
@@ -52,60 +52,60 @@ test!(element_08, "<:circle/>",
 );
 
 test!(element_err_01, "<>",
-    Token::Error("invalid token 'Element Start' at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid token 'element start' at 1:1 cause invalid name token".to_string())
 );
 
 test!(element_err_02, "</",
-    Token::Error("unexpected token 'Element Close' at 1:1".to_string())
+    Token::Error("unexpected token 'element close' at 1:1".to_string())
 );
 
 test!(element_err_03, "</a",
-    Token::Error("unexpected token 'Element Close' at 1:1".to_string())
+    Token::Error("unexpected token 'element close' at 1:1".to_string())
 );
 
 test!(element_err_04, "<a x='test' /",
     Token::ElementStart("", "a"),
     Token::Attribute("", "x", "test"),
-    Token::Error("invalid token 'Attribute' at 1:13 cause unexpected end of stream".to_string())
+    Token::Error("invalid token 'attribute' at 1:13 cause unexpected end of stream while parsing element 'a'".to_string())
 );
 
 test!(element_err_05, "<<",
-    Token::Error("invalid token 'Element Start' at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid token 'element start' at 1:1 cause invalid name token".to_string())
 );
 
 test!(element_err_06, "< a",
-    Token::Error("invalid token 'Element Start' at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid token 'element start' at 1:1 cause invalid name token".to_string())
 );
 
 test!(element_err_07, "< ",
-    Token::Error("invalid token 'Element Start' at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid token 'element start' at 1:1 cause invalid name token".to_string())
 );
 
 test!(element_err_08, "<&#x9;",
-    Token::Error("invalid token 'Element Start' at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid token 'element start' at 1:1 cause invalid name token".to_string())
 );
 
 test!(element_err_09, "<a></a></a>",
     Token::ElementStart("", "a"),
     Token::ElementEnd(ElementEnd::Open),
     Token::ElementEnd(ElementEnd::Close("", "a")),
-    Token::Error("unexpected token 'Element Close' at 1:8".to_string())
+    Token::Error("unexpected token 'element close' at 1:8".to_string())
 );
 
 test!(element_err_10, "<a/><a/>",
     Token::ElementStart("", "a"),
     Token::ElementEnd(ElementEnd::Empty),
-    Token::Error("unexpected token 'Element Start' at 1:5".to_string())
+    Token::Error("unexpected token 'element start' at 1:5".to_string())
 );
 
 test!(element_err_11, "<a></br/></a>",
     Token::ElementStart("", "a"),
     Token::ElementEnd(ElementEnd::Open),
-    Token::Error("invalid token 'Element Close' at 1:4 cause expected '>' not '/' at 1:8".to_string())
+    Token::Error("invalid token 'element close' at 1:4 cause expected '>' but found '/' at 1:8".to_string())
 );
 
 test!(element_err_12, "<svg:/>",
-    Token::Error("invalid token 'Element Start' at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid token 'element start' at 1:1 cause invalid name token".to_string())
 );
 
 
@@ -156,21 +156,35 @@ test!(attribute_07, "<c q:a='b'/>",
 
 test!(attribute_err_01, "<c az=test>",
     Token::ElementStart("", "c"),
-    Token::Error("invalid token 'Attribute' at 1:3 cause expected quote mark not 't' at 1:7".to_string())
+    Token::Error("invalid token 'attribute' at 1:3 cause expected quote mark not 't' at 1:7 while parsing element 'c'".to_string())
 );
 
 test!(attribute_err_02, "<c a>",
     Token::ElementStart("", "c"),
-    Token::Error("invalid token 'Attribute' at 1:3 cause expected \'=\' not \'>\' at 1:5".to_string())
+    Token::Error("invalid token 'attribute' at 1:3 cause expected '=' but found '>' at 1:5 while parsing element 'c'".to_string())
 );
 
 test!(attribute_err_03, "<c a/>",
     Token::ElementStart("", "c"),
-    Token::Error("invalid token 'Attribute' at 1:3 cause expected '=' not '/' at 1:5".to_string())
+    Token::Error("invalid token 'attribute' at 1:3 cause expected '=' but found '/' at 1:5 while parsing element 'c'".to_string())
 );
 
 test!(attribute_err_04, "<c a='b' q/>",
     Token::ElementStart("", "c"),
     Token::Attribute("", "a", "b"),
-    Token::Error("invalid token 'Attribute' at 1:10 cause expected '=' not '/' at 1:11".to_string())
+    Token::Error("invalid token 'attribute' at 1:10 cause expected '=' but found '/' at 1:11 while parsing element 'c'".to_string())
+);
+
+test!(attribute_err_05, "<svg:rect a='b'\n  width=10/>",
+    Token::ElementStart("svg", "rect"),
+    Token::Attribute("", "a", "b"),
+    Token::Error("invalid token 'attribute' at 2:3 cause expected quote mark not '1' at 2:9 while parsing element 'svg:rect'".to_string())
+);
+
+test!(attribute_err_06, "<a><b c='d' e></b></a>",
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::ElementStart("", "b"),
+    Token::Attribute("", "c", "d"),
+    Token::Error("invalid token 'attribute' at 1:13 cause expected '=' but found '>' at 1:14 while parsing element 'b'".to_string())
 );
@@ -0,0 +1,151 @@
+extern crate xmlparser as xml;
+
+use std::borrow::Cow;
+
+use xml::{decode_bytes, Encoding};
+
+fn utf16(text: &str, is_le: bool) -> Vec<u8> {
+    let mut v = Vec::new();
+    for c in text.encode_utf16() {
+        let b = if is_le { c.to_le_bytes() } else { c.to_be_bytes() };
+        v.extend_from_slice(&b);
+    }
+
+    v
+}
+
+#[test]
+fn detect_01() {
+    assert_eq!(Encoding::detect(b"\xEF\xBB\xBF<a/>").unwrap(), Encoding::Utf8);
+}
+
+#[test]
+fn detect_02() {
+    // No BOM.
+    assert_eq!(Encoding::detect(&utf16("<?xml version='1.0'?>", true)).unwrap(), Encoding::Utf16Le);
+    assert_eq!(Encoding::detect(&utf16("<?xml version='1.0'?>", false)).unwrap(), Encoding::Utf16Be);
+}
+
+#[test]
+fn detect_03() {
+    let text = b"<?xml version='1.0' encoding='UTF-8'?><a/>";
+    assert_eq!(Encoding::detect(text).unwrap(), Encoding::Utf8);
+}
+
+#[test]
+fn detect_04() {
+    let text = b"<?xml version='1.0' encoding='US-ASCII'?><a/>";
+    assert_eq!(Encoding::detect(text).unwrap(), Encoding::Latin1);
+}
+
+#[test]
+fn detect_err_01() {
+    let text = b"<?xml version='1.0' encoding='KOI8-R'?><a/>";
+    assert_eq!(Encoding::detect(text).unwrap_err().to_string(),
+               "unsupported encoding 'KOI8-R' at 1:31");
+}
+
+#[test]
+fn detect_err_02() {
+    // UTF-16 without a BOM is detected by the first bytes.
+    let text = b"<?xml version='1.0' encoding='UTF-16'?><a/>";
+    assert_eq!(Encoding::detect(text).unwrap_err().to_string(),
+               "unsupported encoding 'UTF-16' at 1:31");
+}
+
+#[test]
+fn detect_05() {
+    let mut data = vec![0xFE, 0xFF];
+    data.extend(utf16("<?xml version='1.0' encoding='utf-16'?><a/>", false));
+    assert_eq!(Encoding::detect(&data).unwrap(), Encoding::Utf16Be);
+}
+
+#[test]
+fn detect_err_03() {
+    // The declared encoding doesn't match the BOM.
+    let text = b"\xEF\xBB\xBF<?xml version='1.0' encoding='latin1'?><a/>";
+    assert_eq!(Encoding::detect(text).unwrap_err().to_string(),
+               "unsupported encoding 'latin1' at 1:32");
+}
+
+#[test]
+fn detect_err_04() {
+    let mut data = vec![0xFF, 0xFE];
+    data.extend(utf16("<?xml version='1.0' encoding='UTF-8'?><a/>", true));
+    assert_eq!(Encoding::detect(&data).unwrap_err().to_string(),
+               "unsupported encoding 'UTF-8' at 1:31");
+}
+
+#[test]
+fn detect_err_05() {
+    // No BOM.
+    let data = utf16("<?xml version='1.0' encoding='UTF-16BE'?><a/>", true);
+    assert_eq!(Encoding::detect(&data).unwrap_err().to_string(),
+               "unsupported encoding 'UTF-16BE' at 1:31");
+}
+
+#[test]
+fn decode_01() {
+    let text = "<a>\u{1F600}</a>";
+    assert!(match decode_bytes(text.as_bytes()).unwrap() {
+        Cow::Borrowed(s) => s == text,
+        Cow::Owned(_) => false,
+    });
+}
+
+#[test]
+fn decode_02() {
+    let mut data = vec![0xFF, 0xFE];
+    data.extend(utf16("<a>\u{1F600}</a>", true));
+    assert_eq!(decode_bytes(&data).unwrap(), "<a>\u{1F600}</a>");
+}
+
+#[test]
+fn decode_03() {
+    let mut data = vec![0xFE, 0xFF];
+    data.extend(utf16("<a>\u{1F600}</a>", false));
+    assert_eq!(decode_bytes(&data).unwrap(), "<a>\u{1F600}</a>");
+}
+
+#[test]
+fn decode_err_01() {
+    assert_eq!(decode_bytes(b"<a>\n\xFF</a>").unwrap_err().to_string(), "invalid UTF-8 at 2:1");
+}
+
+#[test]
+fn decode_err_02() {
+    // An unpaired surrogate.
+    let mut data = vec![0xFF, 0xFE];
+    data.extend(utf16("<a>", true));
+    data.extend_from_slice(&[0x00, 0xD8]);
+    data.extend(utf16("</a>", true));
+    assert_eq!(decode_bytes(&data).unwrap_err().to_string(), "invalid UTF-16 at 1:4");
+}
+
+#[test]
+fn decode_err_03() {
+    // A truncated code unit.
+    let mut data = vec![0xFF, 0xFE];
+    data.extend(utf16("<a/>", true));
+    data.push(0x00);
+    assert_eq!(decode_bytes(&data).unwrap_err().to_string(), "invalid UTF-16 at 1:5");
+}
+
+#[test]
+fn from_bytes_01() {
+    let mut buffer = String::new();
+    let p = xml::Tokenizer::from_bytes(b"<?xml version='1.0' encoding='latin1'?><a b='\xE9'/>",
+                                       &mut buffer).unwrap();
+    let values: Vec<_> = p.filter_map(|t| match t.unwrap() {
+        xml::Token::Attribute(_, value, _) => Some(value.to_str().to_string()),
+        _ => None,
+    }).collect();
+    assert_eq!(values, vec!["\u{E9}".to_string()]);
+}
+
+#[test]
+fn from_bytes_02() {
+    let mut buffer = String::new();
+    let p = xml::Tokenizer::from_bytes(b"<a/>", &mut buffer).unwrap();
+    assert_eq!(p.count(), 2);
+}
@@ -0,0 +1,64 @@
+extern crate xmlparser as xml;
+
+#[macro_use] mod token;
+use token::*;
+
+fn collect(text: &str, enable: bool) -> Vec<Token<'_>> {
+    let mut p = xml::Tokenizer::from(text);
+    if enable {
+        p.enable_end_of_document_token();
+    }
+
+    let mut tokens = Vec::new();
+    for t in &mut p {
+        tokens.push(to_test_token(t));
+    }
+
+    // The iterator must stay exhausted.
+    assert!(p.next().is_none());
+
+    tokens
+}
+
+#[test]
+fn end_of_document_01() {
+    assert_eq!(collect("<a/>\n", true), vec![
+        Token::ElementStart("", "a"),
+        Token::ElementEnd(ElementEnd::Empty),
+        Token::EndOfDocument(5),
+    ]);
+}
+
+#[test]
+fn end_of_document_02() {
+    // Disabled by default.
+    assert_eq!(collect("<a/>", false), vec![
+        Token::ElementStart("", "a"),
+        Token::ElementEnd(ElementEnd::Empty),
+    ]);
+}
+
+#[test]
+fn end_of_document_03() {
+    assert_eq!(collect("", true), vec![Token::EndOfDocument(0)]);
+}
+
+#[test]
+fn end_of_document_04() {
+    // Not emitted after an error.
+    assert_eq!(collect("<a/><b/>", true), vec![
+        Token::ElementStart("", "a"),
+        Token::ElementEnd(ElementEnd::Empty),
+        Token::Error("unexpected token 'element start' at 1:5".to_string()),
+    ]);
+}
+
+#[test]
+fn end_of_document_05() {
+    let mut p = xml::Tokenizer::from("<a/><b/>");
+    p.set_multi_document_mode();
+    p.enable_end_of_document_token();
+    let tokens: Vec<_> = p.map(to_test_token).collect();
+    assert_eq!(tokens.iter().filter(|t| **t == Token::EndOfDocument(8)).count(), 1);
+    assert_eq!(tokens.last(), Some(&Token::EndOfDocument(8)));
+}
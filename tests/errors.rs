@@ -0,0 +1,55 @@
+extern crate xmlparser as xml;
+
+#[cfg(feature = "std")]
+use std::error::Error as StdError;
+
+use xml::{ErrorKind, ErrorPos, Expectation, Tokenizer};
+
+fn first_error(text: &str) -> xml::Error {
+    Tokenizer::from(text).filter_map(|t| t.err()).next().unwrap()
+}
+
+#[test]
+fn kind_01() {
+    assert_eq!(first_error("<a/>text").kind(), ErrorKind::UnexpectedToken);
+    assert_eq!(first_error("<!>").kind(), ErrorKind::UnknownToken);
+    // Looks through `InElement`.
+    assert_eq!(first_error("<a b=1/>").kind(), ErrorKind::InvalidToken);
+}
+
+#[test]
+fn expected_01() {
+    let e = first_error("<a b='1'c/>");
+    assert_eq!(e.expected(), &[Expectation::Char('=')]);
+    assert_eq!(e.pos(), ErrorPos::new(1, 9));
+}
+
+#[test]
+fn expected_02() {
+    let e = first_error("<!DOCTYPE a [<!ENTITY b x>]>");
+    assert_eq!(e.kind(), ErrorKind::InvalidToken);
+    assert_eq!(e.expected(), &[Expectation::Char('"'), Expectation::Char('\''),
+                               Expectation::Char('S'), Expectation::Char('P')]);
+    assert_eq!(e.cause().unwrap().pos(), Some(ErrorPos::new(1, 25)));
+}
+
+#[test]
+fn expected_03() {
+    assert_eq!(first_error("<a b=1/>").expected(),
+               &[Expectation::Char('"'), Expectation::Char('\'')]);
+    assert!(first_error("<a/>text").expected().is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn source_01() {
+    // InElement -> InvalidToken -> StreamError
+    let e = first_error("<a b=1/>");
+    let e = e.source().unwrap();
+    assert_eq!(e.to_string(), "invalid token 'attribute' at 1:3 cause expected quote mark not '1' at 1:6");
+    let e = e.source().unwrap();
+    assert_eq!(e.to_string(), "expected quote mark not '1' at 1:6");
+    assert!(e.source().is_none());
+
+    assert!(first_error("<a/>text").source().is_none());
+}
@@ -0,0 +1,59 @@
+extern crate xmlparser;
+
+use std::borrow::Cow;
+
+use xmlparser::{escape_attribute, escape_text, QuoteChar};
+
+#[test]
+fn escape_text_01() {
+    match escape_text("text 'a' \"b\"") {
+        Cow::Borrowed(s) => assert_eq!(s, "text 'a' \"b\""),
+        Cow::Owned(s) => panic!("unexpected allocation: {}", s),
+    }
+}
+
+#[test]
+fn escape_text_02() {
+    assert_eq!(escape_text("&"), "&amp;");
+    assert_eq!(escape_text("<"), "&lt;");
+    assert_eq!(escape_text(">"), "&gt;");
+    assert_eq!(escape_text("т<т>&amp;"), "т&lt;т&gt;&amp;amp;");
+}
+
+#[test]
+fn escape_text_03() {
+    assert_eq!(escape_text(""), "");
+}
+
+#[test]
+fn escape_attribute_01() {
+    match escape_attribute("text 'a'", QuoteChar::Double) {
+        Cow::Borrowed(s) => assert_eq!(s, "text 'a'"),
+        Cow::Owned(s) => panic!("unexpected allocation: {}", s),
+    }
+
+    match escape_attribute("text \"a\"", QuoteChar::Single) {
+        Cow::Borrowed(s) => assert_eq!(s, "text \"a\""),
+        Cow::Owned(s) => panic!("unexpected allocation: {}", s),
+    }
+}
+
+#[test]
+fn escape_attribute_02() {
+    assert_eq!(escape_attribute("\"'", QuoteChar::Double), "&quot;'");
+    assert_eq!(escape_attribute("\"'", QuoteChar::Single), "\"&apos;");
+    assert_eq!(escape_attribute("a&<>", QuoteChar::Single), "a&amp;&lt;&gt;");
+}
+
+#[test]
+fn escape_attribute_03() {
+    // Round-trip via the tokenizer.
+    let value = "a \"b\" & <c>";
+    let text = format!("<e v=\"{}\"/>", escape_attribute(value, QuoteChar::Double));
+    let mut p = xmlparser::Tokenizer::from(text.as_str());
+    p.next();
+    match p.next() {
+        Some(Ok(xmlparser::Token::Attribute(_, v, _))) => assert_eq!(v.to_str(), "a &quot;b&quot; &amp; &lt;c&gt;"),
+        t => panic!("{:?}", t),
+    }
+}
@@ -0,0 +1,143 @@
+extern crate xmlparser as xml;
+
+use xml::{parse_into_events, parse_into_events_with, ErrorPos, Event, EventOptions, ExpandedName,
+          Warning};
+
+fn name(name: &ExpandedName) -> String {
+    match name.namespace {
+        Some(ns) => format!("{{{}}}{}", ns, name.local),
+        None => name.local.to_str().to_string(),
+    }
+}
+
+fn collect(text: &str) -> Result<Vec<String>, String> {
+    let events = parse_into_events(text).map_err(|e| e.to_string())?;
+    Ok(events.iter().map(|e| match *e {
+        Event::StartElement(ref n, ref attrs) => {
+            let attrs: Vec<_> = attrs.iter().map(|(n, v)| format!(" {}='{}'", name(n), v)).collect();
+            format!("<{}{}>", name(n), attrs.concat())
+        }
+        Event::EndElement(ref n) => format!("</{}>", name(n)),
+        Event::Text(ref text) => text.to_string(),
+        Event::Comment(text) => format!("<!--{}-->", text),
+        Event::ProcessingInstruction(target, _) => format!("<?{}?>", target),
+    }).collect())
+}
+
+#[test]
+fn events_01() {
+    let text = "<?xml version='1.0'?>\n\
+                <svg xmlns='http://www.w3.org/2000/svg' xmlns:x='http://x.com'>\
+                <x:a x:b='1 &amp; 2' c='&#x33;'>a &lt; b</x:a><![CDATA[&amp;]]><!--c--><?pi?>\
+                </svg>";
+    assert_eq!(collect(text).unwrap(), vec![
+        "<{http://www.w3.org/2000/svg}svg \
+         {http://www.w3.org/2000/xmlns/}xmlns='http://www.w3.org/2000/svg' \
+         {http://www.w3.org/2000/xmlns/}x='http://x.com'>",
+        "<{http://x.com}a {http://x.com}b='1 & 2' c='3'>",
+        "a < b",
+        "</{http://x.com}a>",
+        "&amp;",
+        "<!--c-->",
+        "<?pi?>",
+        "</{http://www.w3.org/2000/svg}svg>",
+    ]);
+}
+
+#[test]
+fn events_02() {
+    // Scopes are restored after the element end.
+    let text = "<a xmlns='1'><b xmlns='2' xmlns:p='3'><p:c/></b><c/><d xmlns=''/></a>";
+    assert_eq!(collect(text).unwrap(), vec![
+        "<{1}a {http://www.w3.org/2000/xmlns/}xmlns='1'>",
+        "<{2}b {http://www.w3.org/2000/xmlns/}xmlns='2' {http://www.w3.org/2000/xmlns/}p='3'>",
+        "<{3}c>", "</{3}c>",
+        "</{2}b>",
+        "<{1}c>", "</{1}c>",
+        "<d {http://www.w3.org/2000/xmlns/}xmlns=''>", "</d>",
+        "</{1}a>",
+    ]);
+}
+
+#[test]
+fn events_03() {
+    assert_eq!(collect("<a xml:lang='en'/>").unwrap(), vec![
+        "<a {http://www.w3.org/XML/1998/namespace}lang='en'>", "</a>",
+    ]);
+}
+
+#[test]
+fn events_04() {
+    assert_eq!(collect("<a><b:c/></a>").unwrap_err(), "unknown namespace prefix at 1:5");
+    assert_eq!(collect("<a b:c='1'/>").unwrap_err(), "unknown namespace prefix at 1:4");
+    assert_eq!(collect("<a><b></a>").unwrap_err(), "unexpected close tag at 1:7");
+}
+
+#[test]
+fn events_05() {
+    // A fragment relying on an enclosing binding of `svg`.
+    let text = "<svg:g svg:x='1' y='2'><svg:rect/></svg:g>";
+    assert_eq!(collect(text).unwrap_err(), "unknown namespace prefix at 1:2");
+
+    let opt = EventOptions { lenient_namespaces: true, ..EventOptions::default() };
+    let (events, warnings) = parse_into_events_with(text, opt).unwrap();
+    let names: Vec<_> = events.iter().map(|e| match *e {
+        Event::StartElement(ref n, ref attrs) => {
+            assert!(attrs.iter().all(|(n, _)| n.namespace.is_none()));
+            (n.namespace, n.prefix.to_str(), n.local.to_str())
+        }
+        Event::EndElement(ref n) => (n.namespace, n.prefix.to_str(), n.local.to_str()),
+        _ => unreachable!(),
+    }).collect();
+    assert_eq!(names, vec![
+        (None, "svg", "g"), (None, "svg", "rect"), (None, "svg", "rect"), (None, "svg", "g"),
+    ]);
+    assert_eq!(warnings, vec![
+        Warning::UnknownNamespacePrefix(ErrorPos::new(1, 2)),
+        Warning::UnknownNamespacePrefix(ErrorPos::new(1, 8)),
+        Warning::UnknownNamespacePrefix(ErrorPos::new(1, 25)),
+    ]);
+}
+
+#[test]
+fn events_06() {
+    // Declared prefixes are still resolved in the lenient mode.
+    let opt = EventOptions { lenient_namespaces: true, ..EventOptions::default() };
+    let (events, warnings) = parse_into_events_with("<a xmlns:b='http://b'><b:c/></a>", opt).unwrap();
+    match events[1] {
+        Event::StartElement(ref n, _) => assert_eq!(n.namespace, Some("http://b")),
+        _ => unreachable!(),
+    }
+    assert!(warnings.is_empty());
+}
+
+fn texts(text: &str, coalesce_text: bool, skip_whitespace_text: bool) -> Vec<String> {
+    let opt = EventOptions { coalesce_text, skip_whitespace_text, ..EventOptions::default() };
+    let (events, _) = parse_into_events_with(text, opt).unwrap();
+    events.iter().filter_map(|e| match *e {
+        Event::Text(ref text) => Some(text.to_string()),
+        _ => None,
+    }).collect()
+}
+
+#[test]
+fn events_07() {
+    let text = "<a>x &amp; <![CDATA[<y>]]> z<!--c-->w</a>";
+    assert_eq!(texts(text, false, false), vec!["x & ", "<y>", " z", "w"]);
+    assert_eq!(texts(text, true, false), vec!["x & <y> z", "w"]);
+}
+
+#[test]
+fn events_08() {
+    let text = "<a>\n  <b> t </b>\n  <c><![CDATA[ ]]></c>&#x20;\n</a>";
+    assert_eq!(texts(text, false, false), vec!["\n  ", " t ", "\n  ", " ", " \n"]);
+    // Spaces from CDATA and character references are kept.
+    assert_eq!(texts(text, false, true), vec![" t ", " ", " \n"]);
+}
+
+#[test]
+fn events_09() {
+    // Merged text is checked as a whole.
+    let text = "<a> <![CDATA[x]]> <b/> \n </a>";
+    assert_eq!(texts(text, true, true), vec![" x "]);
+}
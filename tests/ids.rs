@@ -0,0 +1,74 @@
+extern crate xmlparser as xml;
+
+fn check(text: &str) -> Result<(), xml::Error> {
+    let mut p = xml::Tokenizer::from(text);
+    p.enable_id_checking();
+    for token in p {
+        token?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn ids_01() {
+    check("<a xml:id='a1'><b xml:id='b1'/><b xml:id='b2'/></a>").unwrap();
+}
+
+#[test]
+fn ids_02() {
+    // `id` is not an ID without a DTD.
+    check("<a id='a1'><b id='a1'/></a>").unwrap();
+}
+
+#[test]
+fn ids_03() {
+    let text = "\
+<!DOCTYPE a [
+    <!ATTLIST b id ID #IMPLIED name CDATA 'n' type (x|y) #REQUIRED>
+]>
+<a id='a1'><b id='b1' type='x'/><b id='b2'/></a>";
+    check(text).unwrap();
+}
+
+#[test]
+fn ids_err_01() {
+    let err = check("<a xml:id='a1'>\n  <b xml:id='a1'/>\n</a>").unwrap_err();
+    assert_eq!(err.to_string(), "duplicated id at 2:6, previously defined at 1:4");
+}
+
+#[test]
+fn ids_err_02() {
+    // Values are normalized.
+    let err = check("<a xml:id='a1'><b xml:id=' a1 '/></a>").unwrap_err();
+    assert_eq!(err.to_string(), "duplicated id at 1:19, previously defined at 1:4");
+
+    let err = check("<a xml:id='\ta1\r\n'><b xml:id='a1'/></a>").unwrap_err();
+    assert_eq!(err.to_string(), "duplicated id at 2:6, previously defined at 1:4");
+}
+
+#[test]
+fn ids_err_03() {
+    let text = "\
+<!DOCTYPE a [
+    <!ATTLIST b name CDATA #FIXED 'n' id ID #IMPLIED>
+]>
+<a><b id='b1'/><c id='b1'/><b id='b1'/></a>";
+    let err = check(text).unwrap_err();
+    assert_eq!(err.to_string(), "duplicated id at 4:31, previously defined at 4:7");
+}
+
+#[test]
+fn ids_err_04() {
+    // `xml:id` and DTD IDs share the same values.
+    let text = "<!DOCTYPE a [<!ATTLIST b id ID #IMPLIED>]><a xml:id='v'><b id='v'/></a>";
+    let err = check(text).unwrap_err();
+    assert_eq!(err.to_string(), "duplicated id at 1:60, previously defined at 1:46");
+}
+
+#[test]
+fn ids_err_05() {
+    let err = check("<!DOCTYPE a [<!ATTLIST b id ID>]><a/>").unwrap_err();
+    assert_eq!(err.to_string(),
+               "invalid token 'attribute list declaration' at 1:14 cause expected space not '>' at 1:31");
+}
@@ -0,0 +1,49 @@
+extern crate xmlparser as xml;
+
+#[test]
+fn interning_1() {
+    let mut p = xml::Tokenizer::from("<svg:g id='1'><svg:g id='2'/><rect id='3'/></svg:g>");
+    p.enable_interning();
+
+    let mut ids = Vec::new();
+    while let Some(token) = p.next() {
+        token.unwrap();
+        if let Some(id) = p.name_id() {
+            ids.push(id);
+        }
+    }
+
+    // svg:g, id, svg:g, id, rect, id, svg:g
+    assert_eq!(ids.len(), 7);
+    assert_eq!(ids[0], ids[2]);
+    assert_eq!(ids[0], ids[6]);
+    assert_eq!(ids[1], ids[3]);
+    assert_eq!(ids[1], ids[5]);
+    assert_ne!(ids[0], ids[1]);
+    assert_ne!(ids[0], ids[4]);
+
+    let interner = p.interner().unwrap();
+    assert_eq!(interner.len(), 3);
+    assert_eq!(interner.resolve(ids[0]), Some("svg:g"));
+    assert_eq!(interner.resolve(ids[1]), Some("id"));
+    assert_eq!(interner.resolve(ids[4]), Some("rect"));
+}
+
+#[test]
+fn interning_2() {
+    let mut p = xml::Tokenizer::from("<a/>");
+    p.next().unwrap().unwrap();
+    assert!(p.name_id().is_none());
+    assert!(p.interner().is_none());
+}
+
+#[test]
+fn interning_3() {
+    let mut p = xml::Tokenizer::from("<a>text</a>");
+    p.enable_interning();
+    p.next().unwrap().unwrap();
+    assert!(p.name_id().is_some());
+    p.next().unwrap().unwrap();
+    p.next().unwrap().unwrap();
+    assert!(p.name_id().is_none());
+}
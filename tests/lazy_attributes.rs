@@ -0,0 +1,127 @@
+extern crate xmlparser as xml;
+
+#[macro_use] mod token;
+use token::*;
+
+fn lazy(text: &str) -> xml::Tokenizer<'_> {
+    let mut p = xml::Tokenizer::from(text);
+    p.set_lazy_attributes_mode();
+    p
+}
+
+#[test]
+fn lazy_attributes_01() {
+    let mut p = lazy("<a b='1' c:d=\"2\"><e f='>/>'/></a>");
+    assert_eq!(to_test_token(p.next().unwrap()), Token::ElementStart("", "a"));
+    let attrs: Vec<_> = p.attributes().map(to_test_token).collect();
+    assert_eq!(attrs, vec![Token::Attribute("", "b", "1"), Token::Attribute("c", "d", "2")]);
+    assert_eq!(to_test_token(p.next().unwrap()), Token::ElementEnd(ElementEnd::Open));
+
+    assert_eq!(to_test_token(p.next().unwrap()), Token::ElementStart("", "e"));
+    let attrs: Vec<_> = p.attributes().map(to_test_token).collect();
+    assert_eq!(attrs, vec![Token::Attribute("", "f", ">/>")]);
+    assert_eq!(to_test_token(p.next().unwrap()), Token::ElementEnd(ElementEnd::Empty));
+    assert_eq!(to_test_token(p.next().unwrap()), Token::ElementEnd(ElementEnd::Close("", "a")));
+    assert!(p.next().is_none());
+}
+
+#[test]
+fn lazy_attributes_02() {
+    // The skipped attributes are validated at the element end.
+    let mut p = lazy("<a b='1' c=2 d/>");
+    assert_eq!(to_test_token(p.next().unwrap()), Token::ElementStart("", "a"));
+    assert_eq!(to_test_token(p.attributes().next().unwrap()), Token::Attribute("", "b", "1"));
+    assert_eq!(to_test_token(p.next().unwrap()),
+               Token::Error("invalid token 'attribute' at 1:10 cause expected quote mark not '2' \
+                             at 1:12 while parsing element 'a'".to_string()));
+    assert!(p.next().is_none());
+}
+
+#[test]
+fn lazy_attributes_03() {
+    let mut p = lazy("<a b='1' c=2/>");
+    p.next().unwrap().unwrap();
+    let attrs: Vec<_> = p.attributes().map(to_test_token).collect();
+    assert_eq!(attrs, vec![
+        Token::Attribute("", "b", "1"),
+        Token::Error("invalid token 'attribute' at 1:10 cause expected quote mark not '2' at 1:12".to_string()),
+    ]);
+}
+
+#[test]
+fn lazy_attributes_04() {
+    // No attributes.
+    let mut p = lazy("<a><b /></a>");
+    p.next().unwrap().unwrap();
+    assert!(p.attributes().next().is_none());
+    p.next().unwrap().unwrap();
+    p.next().unwrap().unwrap();
+    assert!(p.attributes().next().is_none());
+    assert_eq!(to_test_token(p.next().unwrap()), Token::ElementEnd(ElementEnd::Empty));
+}
+
+#[test]
+fn lazy_attributes_05() {
+    let mut p = lazy("<a b='1");
+    p.next().unwrap().unwrap();
+    assert_eq!(to_test_token(p.next().unwrap()),
+               Token::Error("invalid token 'attribute' at 1:3 cause unexpected end of stream \
+                             while parsing element 'a'".to_string()));
+}
+
+#[test]
+fn lazy_attributes_06() {
+    // Disabled by default.
+    let mut p = xml::Tokenizer::from("<a b='1'/>");
+    p.next().unwrap().unwrap();
+    assert!(p.attributes().next().is_none());
+    assert_eq!(to_test_token(p.next().unwrap()), Token::Attribute("", "b", "1"));
+}
+
+#[test]
+fn lazy_attributes_07() {
+    let attrs: Vec<_> = xml::Attributes::from(xml::StrSpan::from(" a='1'\n b='2' "))
+        .map(to_test_token).collect();
+    assert_eq!(attrs, vec![Token::Attribute("", "a", "1"), Token::Attribute("", "b", "2")]);
+}
+
+#[test]
+fn lazy_attributes_08() {
+    // Attributes are available only until the element end.
+    let mut p = lazy("<a b='1'>text</a>");
+    p.next().unwrap().unwrap();
+    assert_eq!(p.attributes().count(), 1);
+    assert_eq!(to_test_token(p.next().unwrap()), Token::ElementEnd(ElementEnd::Open));
+    assert!(p.attributes().next().is_none());
+}
+
+#[test]
+fn lazy_attributes_09() {
+    // The optional checks see the skipped attributes.
+    let mut p = lazy("<a b='1' b='2'/>");
+    p.set_strict_mode();
+    p.next().unwrap().unwrap();
+    assert_eq!(to_test_token(p.next().unwrap()),
+               Token::Error("duplicated attribute at 1:10".to_string()));
+
+    let mut p = lazy("<a xmlns:n='u' n:b='1' xml:space='preserve'></a>");
+    p.enable_namespace_recording();
+    p.enable_xml_space_tracking();
+    p.next().unwrap().unwrap();
+    p.next().unwrap().unwrap();
+    assert_eq!(p.namespace_declarations().len(), 1);
+    assert_eq!(p.xml_space(), xml::XmlSpace::Preserve);
+}
+
+#[test]
+fn lazy_attributes_10() {
+    let text = "<a b='1'><c d='2'/></a>";
+    let mut p = lazy(text);
+    for token in &mut p {
+        token.unwrap();
+    }
+
+    p.seek(9).unwrap();
+    assert_eq!(to_test_token(p.next().unwrap()), Token::ElementStart("", "c"));
+    assert_eq!(to_test_token(p.next().unwrap()), Token::ElementEnd(ElementEnd::Empty));
+}
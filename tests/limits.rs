@@ -0,0 +1,75 @@
+extern crate xmlparser as xml;
+
+use xml::Limits;
+
+fn check(text: &str, limits: Limits) -> Result<(), xml::Error> {
+    let mut p = xml::Tokenizer::from(text);
+    p.set_limits(limits);
+    for token in p {
+        token?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn limits_01() {
+    let text = "<!DOCTYPE a [<!ENTITY b 'c'>]><a b='value'><b><c/></b></a>";
+    check(text, Limits::default()).unwrap();
+    check(text, Limits {
+        max_depth: 3,
+        max_attribute_value_len: 5,
+        max_entity_declarations: 1,
+        max_token_len: 15,
+    }).unwrap();
+}
+
+#[test]
+fn depth_err_01() {
+    let err = check("<a>\n  <b>\n    <c/>\n  </b>\n</a>",
+                    Limits { max_depth: 2, ..Limits::default() }).unwrap_err();
+    assert_eq!(err.to_string(), "nesting depth limit exceeded at 3:5");
+}
+
+#[test]
+fn depth_err_02() {
+    let err = check("<a/>", Limits { max_depth: 0, ..Limits::default() }).unwrap_err();
+    assert_eq!(err.to_string(), "nesting depth limit exceeded at 1:1");
+}
+
+#[test]
+fn attribute_value_err_01() {
+    let err = check("<a b='value'/>",
+                    Limits { max_attribute_value_len: 4, ..Limits::default() }).unwrap_err();
+    assert_eq!(err.to_string(), "attribute value length limit exceeded at 1:7");
+}
+
+#[test]
+fn entity_declarations_err_01() {
+    let text = "<!DOCTYPE a [<!ENTITY b 'c'><!ENTITY c 'd'>]><a/>";
+    let err = check(text, Limits { max_entity_declarations: 1, ..Limits::default() }).unwrap_err();
+    assert_eq!(err.to_string(), "entity declarations limit exceeded at 1:29");
+}
+
+#[test]
+fn token_len_err_01() {
+    let err = check("<a>long text</a>",
+                    Limits { max_token_len: 8, ..Limits::default() }).unwrap_err();
+    assert_eq!(err.to_string(), "token length limit exceeded at 1:4");
+}
+
+#[test]
+fn token_len_err_02() {
+    let text = "<!DOCTYPE a [<!ENTITY b 'long value'>]><a/>";
+    let err = check(text, Limits { max_token_len: 20, ..Limits::default() }).unwrap_err();
+    assert_eq!(err.to_string(), "token length limit exceeded at 1:14");
+}
+
+#[test]
+fn multi_documents() {
+    // The entity declarations counter is per document.
+    let mut p = xml::Tokenizer::from("<!DOCTYPE a [<!ENTITY b 'c'>]><a/><!DOCTYPE a [<!ENTITY b 'c'>]><a/>");
+    p.set_multi_document_mode();
+    p.set_limits(Limits { max_entity_declarations: 1, ..Limits::default() });
+    assert!(p.all(|t| t.is_ok()));
+}
@@ -0,0 +1,54 @@
+extern crate xmlparser as xml;
+
+use xml::{MultiTokenizer, Token};
+
+fn collect(sources: &[&str], continue_on_error: bool) -> Vec<(usize, String)> {
+    let mut p = MultiTokenizer::new(sources.iter().cloned());
+    if continue_on_error {
+        p.set_continue_on_error();
+    }
+
+    p.map(|(idx, token)| {
+        let s = match token {
+            Ok(Token::ElementStart(_, local)) => format!("ElementStart({})", local),
+            Ok(Token::ElementEnd(_)) => "ElementEnd".to_string(),
+            Ok(Token::Text(text)) => format!("Text({})", text),
+            Ok(_) => "Other".to_string(),
+            Err(e) => e.to_string(),
+        };
+
+        (idx, s)
+    }).collect()
+}
+
+#[test]
+fn multi_01() {
+    assert_eq!(collect(&["<a/>", "<b>t</b>"], false), vec![
+        (0, "ElementStart(a)".to_string()),
+        (0, "ElementEnd".to_string()),
+        (1, "ElementStart(b)".to_string()),
+        (1, "ElementEnd".to_string()),
+        (1, "Text(t)".to_string()),
+        (1, "ElementEnd".to_string()),
+    ]);
+}
+
+#[test]
+fn multi_02() {
+    assert_eq!(collect(&["<a/><b/>", "<c/>"], false), vec![
+        (0, "ElementStart(a)".to_string()),
+        (0, "ElementEnd".to_string()),
+        (0, "unexpected token 'element start' at 1:5".to_string()),
+    ]);
+}
+
+#[test]
+fn multi_03() {
+    assert_eq!(collect(&["<a/><b/>", "", "<c/>"], true), vec![
+        (0, "ElementStart(a)".to_string()),
+        (0, "ElementEnd".to_string()),
+        (0, "unexpected token 'element start' at 1:5".to_string()),
+        (2, "ElementStart(c)".to_string()),
+        (2, "ElementEnd".to_string()),
+    ]);
+}
@@ -0,0 +1,112 @@
+extern crate xmlparser as xml;
+
+#[macro_use] mod token;
+use token::*;
+
+macro_rules! test_ns {
+    ($name:ident, $text:expr, $($token:expr),*) => (
+        #[test]
+        fn $name() {
+            let mut p = xml::Tokenizer::from($text);
+            p.set_namespace_well_formed_mode();
+            $(
+                assert_eq!(to_test_token(p.next().unwrap()), $token);
+            )*
+            assert!(p.next().is_none());
+        }
+    )
+}
+
+// XML 1.0 allows multiple colons in a name.
+test!(name_01, "<a:b:c a:b:c='1'></a:b:c>",
+    Token::ElementStart("a:b", "c"),
+    Token::Attribute("a:b", "c", "1"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::ElementEnd(ElementEnd::Close("a:b", "c"))
+);
+
+test!(name_02, "<_a.b-c/>",
+    Token::ElementStart("", "_a.b-c"),
+    Token::ElementEnd(ElementEnd::Empty)
+);
+
+test!(name_err_01, "<-a/>",
+    Token::Error("invalid token 'element start' at 1:1 cause invalid name token".to_string())
+);
+
+test!(name_err_02, "<a 1b='1'/>",
+    Token::ElementStart("", "a"),
+    Token::Error("invalid token 'attribute' at 1:3 cause invalid name token while parsing element 'a'".to_string())
+);
+
+test_ns!(name_ns_01, "<a:b c:d='1'/>",
+    Token::ElementStart("a", "b"),
+    Token::Attribute("c", "d", "1"),
+    Token::ElementEnd(ElementEnd::Empty)
+);
+
+test_ns!(name_ns_err_01, "<a:b:c/>",
+    Token::Error("invalid token 'element start' at 1:1 cause invalid name token".to_string())
+);
+
+test_ns!(name_ns_err_02, "<a\n  b:c:d='1'/>",
+    Token::ElementStart("", "a"),
+    Token::Error("invalid token 'attribute' at 2:3 cause invalid name token".to_string())
+);
+
+test_ns!(name_ns_err_03, "<:a/>",
+    Token::Error("invalid token 'element start' at 1:1 cause invalid name token".to_string())
+);
+
+test_ns!(name_ns_err_04, "<a></a:b:c>",
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Error("invalid token 'element close' at 1:4 cause invalid name token".to_string())
+);
+
+test_ns!(xmlns_01, "<a xmlns='http://a' xmlns:b='http://b'/>",
+    Token::ElementStart("", "a"),
+    Token::Attribute("", "xmlns", "http://a"),
+    Token::Attribute("xmlns", "b", "http://b"),
+    Token::ElementEnd(ElementEnd::Empty)
+);
+
+test_ns!(xmlns_02, "<a xmlns='' xmlns:xml='http://www.w3.org/XML/1998/namespace'/>",
+    Token::ElementStart("", "a"),
+    Token::Attribute("", "xmlns", ""),
+    Token::Attribute("xmlns", "xml", "http://www.w3.org/XML/1998/namespace"),
+    Token::ElementEnd(ElementEnd::Empty)
+);
+
+// Not checked by default.
+test!(xmlns_03, "<a xmlns:xmlns='http://a'/>",
+    Token::ElementStart("", "a"),
+    Token::Attribute("xmlns", "xmlns", "http://a"),
+    Token::ElementEnd(ElementEnd::Empty)
+);
+
+test_ns!(xmlns_err_01, "<a xmlns:xmlns='http://a'/>",
+    Token::ElementStart("", "a"),
+    Token::Error("invalid namespace declaration at 1:4".to_string())
+);
+
+test_ns!(xmlns_err_02, "<a xmlns:xml='http://a'/>",
+    Token::ElementStart("", "a"),
+    Token::Error("invalid namespace declaration at 1:4".to_string())
+);
+
+test_ns!(xmlns_err_03, "<a xmlns:b='http://www.w3.org/XML/1998/namespace'/>",
+    Token::ElementStart("", "a"),
+    Token::Error("invalid namespace declaration at 1:4".to_string())
+);
+
+test_ns!(xmlns_err_04, "<a x='1' xmlns:b=''/>",
+    Token::ElementStart("", "a"),
+    Token::Attribute("", "x", "1"),
+    Token::Error("invalid namespace declaration at 1:10".to_string())
+);
+
+test_ns!(xmlns_err_05, "<a xmlns='http://www.w3.org/2000/xmlns/'/>",
+    Token::ElementStart("", "a"),
+    Token::Error("invalid namespace declaration at 1:4".to_string())
+);
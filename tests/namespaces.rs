@@ -0,0 +1,52 @@
+extern crate xmlparser as xml;
+
+use xml::{Tokenizer, Token, ElementEnd};
+
+fn collect(text: &str) -> Vec<Vec<(Option<String>, String, usize)>> {
+    let mut p = Tokenizer::from(text);
+    p.enable_namespace_recording();
+
+    let mut elements = Vec::new();
+    while let Some(token) = p.next() {
+        match token.unwrap() {
+            Token::ElementEnd(ElementEnd::Open) | Token::ElementEnd(ElementEnd::Empty) => {
+                elements.push(p.namespace_declarations().iter().map(|d| {
+                    (d.prefix.map(|p| p.to_str().to_string()), d.uri.to_str().to_string(), d.uri.start())
+                }).collect());
+            }
+            _ => {}
+        }
+    }
+
+    elements
+}
+
+#[test]
+fn namespace_declarations_01() {
+    let elements = collect("<a xmlns='http://a' id='1' xmlns:c='http://c' xmlns:b='http://b'/>");
+    assert_eq!(elements, vec![vec![
+        (None, "http://a".to_string(), 10),
+        (Some("c".to_string()), "http://c".to_string(), 36),
+        (Some("b".to_string()), "http://b".to_string(), 55),
+    ]]);
+}
+
+#[test]
+fn namespace_declarations_02() {
+    let elements = collect("<a xmlns:b='http://b'><c/><d xmlns=''/></a>");
+    assert_eq!(elements, vec![
+        vec![(Some("b".to_string()), "http://b".to_string(), 12)],
+        vec![],
+        vec![(None, "".to_string(), 36)],
+    ]);
+}
+
+#[test]
+fn namespace_declarations_03() {
+    let mut p = Tokenizer::from("<a xmlns='http://a'/>");
+    for token in &mut p {
+        token.unwrap();
+    }
+
+    assert!(p.namespace_declarations().is_empty());
+}
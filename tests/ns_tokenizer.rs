@@ -0,0 +1,86 @@
+extern crate xmlparser as xml;
+
+use xml::{NsTokenizer, Token, Tokenizer};
+
+fn collect(text: &str) -> Vec<String> {
+    NsTokenizer::from(Tokenizer::from(text)).map(|res| match res {
+        Ok((token, name)) => {
+            let kind = match token {
+                Token::ElementStart(_, _) => "start",
+                Token::Attribute(_, _, _) => "attr",
+                Token::ElementEnd(_) => "end",
+                _ => "other",
+            };
+
+            match name {
+                Some(name) => format!("{} {{{}}}{}", kind, name.namespace.unwrap_or(""), name.local),
+                None => kind.to_string(),
+            }
+        }
+        Err(e) => e.to_string(),
+    }).collect()
+}
+
+#[test]
+fn ns_tokenizer_01() {
+    let text = "<svg xmlns='http://svg' xmlns:x='http://x' x:a='1' b='2'>\
+                <x:g><rect xmlns='' c='3'/>text</x:g></svg>";
+    assert_eq!(collect(text), vec![
+        "start {http://svg}svg",
+        "attr {http://www.w3.org/2000/xmlns/}xmlns",
+        "attr {http://www.w3.org/2000/xmlns/}x",
+        "attr {http://x}a",
+        "attr {}b",
+        "end",
+        "start {http://x}g",
+        "end",
+        "start {}rect",
+        "attr {http://www.w3.org/2000/xmlns/}xmlns",
+        "attr {}c",
+        "end",
+        "other",
+        "end {http://x}g",
+        "end {http://svg}svg",
+    ]);
+}
+
+#[test]
+fn ns_tokenizer_02() {
+    // Scopes are restored after an element end.
+    let text = "<a><b xmlns:p='http://p'><p:c/></b><p:d/></a>";
+    assert_eq!(collect(text), vec![
+        "start {}a",
+        "end",
+        "start {}b",
+        "attr {http://www.w3.org/2000/xmlns/}p",
+        "end",
+        "start {http://p}c",
+        "end",
+        "end {}b",
+        "unknown namespace prefix at 1:37",
+    ]);
+}
+
+#[test]
+fn ns_tokenizer_03() {
+    assert_eq!(collect("<a xml:lang='en' p:b='1'/>"), vec![
+        "start {}a",
+        "attr {http://www.w3.org/XML/1998/namespace}lang",
+        "unknown namespace prefix at 1:18",
+    ]);
+    assert_eq!(collect("<a xml:lang='en'/>"), vec![
+        "start {}a",
+        "attr {http://www.w3.org/XML/1998/namespace}lang",
+        "end",
+    ]);
+}
+
+#[test]
+fn ns_tokenizer_04() {
+    // Tokenizer errors are passed through.
+    assert_eq!(collect("<a b=c/>"), vec![
+        "start {}a",
+        "invalid token 'attribute' at 1:3 cause expected quote mark not 'c' at 1:6 \
+         while parsing element 'a'",
+    ]);
+}
@@ -0,0 +1,102 @@
+extern crate xmlparser as xml;
+
+use xml::{OwnedToken, OwnedElementEnd, OwnedExternalId, OwnedEntityDefinition, Tokenizer};
+
+fn owned(text: &str) -> Vec<OwnedToken> {
+    Tokenizer::from(text).map(|t| t.unwrap().into_owned()).collect()
+}
+
+fn s(text: &str) -> String {
+    text.to_string()
+}
+
+#[test]
+fn owned_01() {
+    let text = "\
+<?xml version='1.0' encoding='UTF-8'?>
+<!DOCTYPE a SYSTEM 'a.dtd' [<!ENTITY b 'c'>]>
+<?pi content?>
+<svg:a x:y='1'><!--c-->text<![CDATA[d]]> </svg:a>";
+
+    // Make sure the tokens outlive the text.
+    let tokens = {
+        let text = String::from(text);
+        owned(&text)
+    };
+    assert_eq!(tokens, vec![
+        OwnedToken::Declaration(s("1.0"), Some(s("UTF-8")), None),
+        OwnedToken::DtdStart(s("a"), Some(OwnedExternalId::System(s("a.dtd")))),
+        OwnedToken::EntityDeclaration(s("b"), OwnedEntityDefinition::EntityValue(s("c"))),
+        OwnedToken::DtdEnd,
+        OwnedToken::ProcessingInstruction(s("pi"), Some(s("content"))),
+        OwnedToken::ElementStart(s("svg"), s("a")),
+        OwnedToken::Attribute((s("x"), s("y")), s("1"), 0),
+        OwnedToken::ElementEnd(OwnedElementEnd::Open),
+        OwnedToken::Comment(s("c")),
+        OwnedToken::Text(s("text")),
+        OwnedToken::Cdata(s("d")),
+        OwnedToken::Whitespaces(s(" ")),
+        OwnedToken::ElementEnd(OwnedElementEnd::Close(s("svg"), s("a"))),
+    ]);
+}
+
+#[test]
+fn owned_02() {
+    // Owned values match the borrowed ones.
+    let text = "<a b='c'/>";
+    for token in Tokenizer::from(text) {
+        let token = token.unwrap();
+        match (token, token.into_owned()) {
+            (xml::Token::ElementStart(p1, l1), OwnedToken::ElementStart(p2, l2)) => {
+                assert_eq!(p1.to_str(), p2);
+                assert_eq!(l1.to_str(), l2);
+            }
+            (xml::Token::Attribute((_, l1), v1, _), OwnedToken::Attribute((_, l2), v2, _)) => {
+                assert_eq!(l1.to_str(), l2);
+                assert_eq!(v1.to_str(), v2);
+            }
+            (xml::Token::ElementEnd(_), OwnedToken::ElementEnd(OwnedElementEnd::Empty)) => {}
+            _ => panic!(),
+        }
+    }
+}
+
+#[test]
+fn owned_spans_01() {
+    let text = "<a b='cd'>text</a>";
+    let tokens: Vec<_> = Tokenizer::from(text).map(|t| t.unwrap().into_owned_spans()).collect();
+
+    match tokens[1] {
+        OwnedToken::Attribute((_, ref local), ref value, _) => {
+            assert_eq!(local.as_str(), "b");
+            assert_eq!((local.start(), local.end()), (3, 4));
+            assert_eq!(value.as_str(), "cd");
+            assert_eq!((value.start(), value.end()), (6, 8));
+        }
+        _ => panic!(),
+    }
+
+    match tokens[3] {
+        OwnedToken::Text(ref text) => assert_eq!(format!("{:?}", text), "OwnedStrSpan(\"text\" 10..14)"),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn owned_spans_02() {
+    // Tokens can be sent to another thread.
+    let (tx, rx) = std::sync::mpsc::channel();
+    let handle = std::thread::spawn(move || {
+        let text = String::from("<a>text</a>");
+        for token in Tokenizer::from(text.as_str()) {
+            tx.send(token.unwrap().into_owned_spans()).unwrap();
+        }
+    });
+
+    handle.join().unwrap();
+    let texts: Vec<_> = rx.iter().filter_map(|t| match t {
+        OwnedToken::Text(text) => Some((text.start(), text.into_string())),
+        _ => None,
+    }).collect();
+    assert_eq!(texts, vec![(3, s("text"))]);
+}
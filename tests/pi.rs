@@ -23,8 +23,24 @@ test!(pi_05, "<?xml-stylesheet?>",
     Token::PI("xml-stylesheet", None)
 );
 
+test!(pi_06, "<?xslt a ? b?>",
+    Token::PI("xslt", Some("a ? b"))
+);
+
+test!(pi_07, "<?xslt a? >b??>",
+    Token::PI("xslt", Some("a? >b?"))
+);
+
+test!(pi_08, "<?xslt >?>",
+    Token::PI("xslt", Some(">"))
+);
+
 test!(pi_err_01, "<??xml \t\n m?>",
-    Token::Error("invalid token 'Processing Instruction' at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid token 'processing instruction' at 1:1 cause invalid name token".to_string())
+);
+
+test!(pi_err_02, "<?xslt a? >",
+    Token::Error("invalid token 'processing instruction' at 1:1 cause expected '?>' not '' at 1:12".to_string())
 );
 
 test!(declaration_01, "<?xml version=\"1.0\"?>",
@@ -65,37 +81,81 @@ test!(declaration_09, "<?xml version='1.0' standalone='no'?>",
 
 // Declaration with an invalid order
 test!(declaration_err_01, "<?xml encoding='UTF-8' version='1.0'?>",
-    Token::Error("invalid token 'Declaration' at 1:1 cause expected 'version' not 'encodin' at 1:7".to_string())
+    Token::Error("invalid token 'XML declaration' at 1:1 cause expected 'version' not 'encodin' at 1:7".to_string())
 );
 
 test!(declaration_err_02, "<?xml version='1.0' encoding='*invalid*'?>",
-    Token::Error("invalid token 'Declaration' at 1:1 cause expected quote mark not '*' at 1:31".to_string())
+    Token::Error("invalid token 'XML declaration' at 1:1 cause expected quote mark not '*' at 1:31".to_string())
 );
 
 test!(declaration_err_03, "<?xml version='2.0'?>",
-    Token::Error("invalid token 'Declaration' at 1:1 cause expected '1.' not '2.' at 1:16".to_string())
+    Token::Error("invalid token 'XML declaration' at 1:1 cause expected '1.' not '2.' at 1:16".to_string())
 );
 
 test!(declaration_err_04, "<?xml version='1.0' standalone='true'?>",
-    Token::Error("invalid token 'Declaration' at 1:1 cause expected 'yes', 'no' not 'true' at 1:33".to_string())
+    Token::Error("invalid token 'XML declaration' at 1:1 cause expected 'yes', 'no' not 'true' at 1:33".to_string())
 );
 
 test!(declaration_err_05, "<?xml version='1.0' yes='true'?>",
-    Token::Error("invalid token 'Declaration' at 1:1 cause expected '?>' not 'ye' at 1:21".to_string())
+    Token::Error("invalid token 'XML declaration' at 1:1 cause unexpected pseudo-attribute 'yes' at 1:21".to_string())
 );
 
 test!(declaration_err_06, "<?xml version='1.0' encoding='UTF-8' standalone='yes' yes='true'?>",
-    Token::Error("invalid token 'Declaration' at 1:1 cause expected '?>' not 'ye' at 1:55".to_string())
+    Token::Error("invalid token 'XML declaration' at 1:1 cause unexpected pseudo-attribute 'yes' at 1:55".to_string())
 );
 
 test!(declaration_err_07, "\u{000a}<?xml\u{001d}\u{000a}\u{0000}&jg'];",
-    Token::Error("invalid token 'Processing Instruction' at 2:2 cause expected '?>' not '\u{1d}\n' at 2:6".to_string())
+    Token::Error("invalid token 'processing instruction' at 2:1 cause expected '?>' not '\u{1d}\n' at 2:6".to_string())
 );
 
 test!(declaration_err_08, "<?xml \t\n ?m?>",
-    Token::Error("invalid token 'Declaration' at 1:1 cause expected 'version' not '?m?>' at 2:2".to_string())
+    Token::Error("invalid token 'XML declaration' at 1:1 cause expected 'version' not '?m?>' at 2:2".to_string())
 );
 
 test!(declaration_err_09, "<?xml \t\n m?>",
-    Token::Error("invalid token 'Declaration' at 1:1 cause expected 'version' not 'm?>' at 2:2".to_string())
+    Token::Error("invalid token 'XML declaration' at 1:1 cause expected 'version' not 'm?>' at 2:2".to_string())
+);
+
+test!(declaration_err_10, "<?xml version=\"1.0\" foo=\"bar\"?>",
+    Token::Error("invalid token 'XML declaration' at 1:1 cause unexpected pseudo-attribute 'foo' at 1:21".to_string())
+);
+
+test!(declaration_err_11, "<?xml Version='1.0'?>",
+    Token::Error("invalid token 'XML declaration' at 1:1 cause expected 'version' not 'Version' at 1:7".to_string())
 );
+
+test!(declaration_err_12, "<?xml version='1.0' Encoding='UTF-8'?>",
+    Token::Error("invalid token 'XML declaration' at 1:1 cause unexpected pseudo-attribute 'Encoding' at 1:21".to_string())
+);
+
+test!(declaration_err_13, "<?xml version='1.0' version='1.0'?>",
+    Token::Error("invalid token 'XML declaration' at 1:1 cause duplicated pseudo-attribute 'version' at 1:21".to_string())
+);
+
+test!(declaration_err_14, "<?xml version='1.0' encoding='UTF-8' encoding='UTF-8'?>",
+    Token::Error("invalid token 'XML declaration' at 1:1 cause duplicated pseudo-attribute 'encoding' at 1:38".to_string())
+);
+
+test!(declaration_err_15, "<?xml version='1.0' standalone='yes' encoding='UTF-8'?>",
+    Token::Error("invalid token 'XML declaration' at 1:1 cause unexpected pseudo-attribute 'encoding' at 1:38".to_string())
+);
+
+fn pi_span(text: &str) -> Option<(usize, usize, String)> {
+    let token = xml::Tokenizer::from(text).next().unwrap().unwrap();
+    token.pi_content().map(|span| (span.start(), span.end(), span.to_str().to_string()))
+}
+
+#[test]
+fn pi_content_01() {
+    assert_eq!(pi_span("<?a?>"), None);
+    assert_eq!(pi_span("<?a  \n?>"), None);
+    assert_eq!(pi_span("<?a b?>"), Some((4, 5, "b".to_string())));
+    assert_eq!(pi_span("<?a \t b c ?>"), Some((6, 10, "b c ".to_string())));
+    assert_eq!(pi_span("<?a ??>"), Some((4, 5, "?".to_string())));
+}
+
+#[test]
+fn pi_content_02() {
+    let token = xml::Tokenizer::from("<!---->").next().unwrap().unwrap();
+    assert!(token.pi_content().is_none());
+}
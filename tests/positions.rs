@@ -0,0 +1,172 @@
+extern crate xmlparser as xml;
+
+use xml::{ErrorPos, Stream, Token};
+
+#[test]
+fn with_positions_01() {
+    let text = "<?xml version='1.0'?>\n<a x='1'\n   y='2'>\n  <b/>text\r\n<!--c--></a>\n";
+    let list: Vec<_> = xml::Tokenizer::from(text).with_positions().map(|t| t.unwrap()).collect();
+
+    let positions: Vec<_> = list.iter().map(|&(_, pos)| (pos.row, pos.col)).collect();
+    assert_eq!(positions, vec![
+        (1, 1), // Declaration
+        (2, 1), // ElementStart
+        (2, 4), // Attribute
+        (3, 4), // Attribute
+        (3, 9), // ElementEnd::Open
+        (3, 10), // Whitespaces
+        (4, 3), // ElementStart
+        (4, 5), // ElementEnd::Empty
+        (4, 7), // Text
+        (5, 1), // Comment
+        (5, 9), // ElementEnd::Close
+    ]);
+
+    match list[9].0 {
+        Token::Comment(_) => {}
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn with_positions_02() {
+    // Positions must be the same as produced by `Stream`.
+    let text = "<a>\n\n  <b x='1'/>\n</a>";
+    let mut p = xml::Tokenizer::from(text);
+    let stream = Stream::from(text);
+    let mut expected = Vec::new();
+    while let Some(token) = p.next() {
+        token.unwrap();
+        expected.push(stream.gen_error_pos_from(p.token_start()));
+    }
+
+    let actual: Vec<ErrorPos> = xml::Tokenizer::from(text).with_positions()
+                                    .map(|t| t.unwrap().1).collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn with_positions_err_01() {
+    let mut p = xml::Tokenizer::from("<a>\n<</a>").with_positions();
+    assert!(p.next().unwrap().is_ok());
+    assert!(p.next().unwrap().is_ok());
+    assert!(p.next().unwrap().is_ok());
+    assert_eq!(p.next().unwrap().unwrap_err().to_string(),
+               "invalid token 'element start' at 2:1 cause invalid name token");
+    assert!(p.next().is_none());
+}
+
+#[test]
+fn from_offset_01() {
+    let text = "<?xml version='1.0'?>\n<a x='1'\n   y='2'>\n  <b/>text\r\n<!--c--></a>\n";
+    let positions = xml::Tokenizer::from(text).with_positions().map(|t| t.unwrap().1);
+
+    let mut p = xml::Tokenizer::from(text);
+    for pos in positions {
+        p.next().unwrap().unwrap();
+        assert_eq!(ErrorPos::from_offset(text, p.token_start()), pos);
+    }
+}
+
+#[test]
+fn from_offset_02() {
+    // Errors positions.
+    let texts = [
+        "<a>\n  <!-- -- --></a>",
+        "<a>\r\n\u{1F600}\u{1F600}<![CDATA[</a>",
+        "\u{FEFF}<a></a>\n\n  <b/>",
+        "<a/>\n\n  text",
+    ];
+
+    for text in &texts {
+        let mut p = xml::Tokenizer::from(*text);
+        let err = p.by_ref().find_map(|t| t.err()).unwrap();
+        let pos = ErrorPos::from_offset(text, p.token_start());
+        assert!(err.to_string().contains(&format!(" at {}", pos)), "{} {}", err, pos);
+    }
+}
+
+#[test]
+fn from_offset_03() {
+    assert_eq!(ErrorPos::from_offset("", 0), ErrorPos::new(1, 1));
+    assert_eq!(ErrorPos::from_offset("ab", 10), ErrorPos::new(1, 3));
+    assert_eq!(ErrorPos::from_offset("a\nb", 2), ErrorPos::new(2, 1));
+}
+
+#[test]
+fn current_pos_01() {
+    // The incremental tracker must match a from-scratch calculation.
+    let text = "<?xml version='1.0'?>\r\n<я x='ё'\r   y='\u{1F600}'>\n\
+                \u{1F600}тест\r\n<!--ж\rж--><b/>\n\r\n</я>\n";
+    let mut tracked = xml::Tokenizer::from(text);
+    tracked.enable_position_tracking();
+    let mut untracked = xml::Tokenizer::from(text);
+    let mut count = 0;
+    while let Some(token) = tracked.next() {
+        token.unwrap();
+        untracked.next().unwrap().unwrap();
+        assert_eq!(tracked.current_pos(), untracked.current_pos());
+        count += 1;
+    }
+
+    assert_eq!(count, 11);
+    assert_eq!(tracked.current_pos(), ErrorPos::new(8, 5));
+}
+
+#[test]
+fn current_pos_02() {
+    let mut p = xml::Tokenizer::from("<a>\r\n\u{1F600}ж<b/>\rx\r\r\n<c/></a>");
+    p.enable_position_tracking();
+    let positions: Vec<_> = (0..9).map(|_| {
+        p.next().unwrap().unwrap();
+        p.current_pos()
+    }).collect();
+    assert_eq!(positions, vec![
+        ErrorPos::new(1, 3), // ElementStart
+        ErrorPos::new(1, 4), // ElementEnd::Open
+        ErrorPos::new(2, 3), // Text
+        ErrorPos::new(2, 5), // ElementStart
+        ErrorPos::new(2, 7), // ElementEnd::Empty
+        ErrorPos::new(5, 1), // Text
+        ErrorPos::new(5, 3), // ElementStart
+        ErrorPos::new(5, 5), // ElementEnd::Empty
+        ErrorPos::new(5, 9), // ElementEnd::Close
+    ]);
+}
+
+#[test]
+fn current_pos_03() {
+    // NEL and LS are line breaks only in XML 1.1.
+    fn last_pos(text: &str, xml_1_1: bool, tracking: bool) -> ErrorPos {
+        let mut p = xml::Tokenizer::from(text);
+        p.set_strictness(xml::Strictness { xml_1_1, ..xml::Strictness::default() });
+        if tracking {
+            p.enable_position_tracking();
+        }
+
+        for token in p.by_ref() {
+            token.unwrap();
+        }
+        p.current_pos()
+    }
+
+    for &tracking in &[true, false] {
+        let text = "<?xml version='1.0'?><a>\u{85}\u{2028}\r\u{85}x</a>";
+        assert_eq!(last_pos(text, true, tracking), ErrorPos::new(2, 7));
+        let text = "<?xml version='1.1'?><a>\u{85}\u{2028}\r\u{85}x</a>";
+        assert_eq!(last_pos(text, true, tracking), ErrorPos::new(4, 6));
+        assert_eq!(last_pos(text, false, tracking), ErrorPos::new(2, 7));
+    }
+}
+
+#[test]
+fn current_pos_04() {
+    // Tracking can be enabled in the middle of parsing.
+    let mut p = xml::Tokenizer::from("<a>\n<b/>\n</a>");
+    p.next().unwrap().unwrap();
+    p.next().unwrap().unwrap();
+    p.next().unwrap().unwrap();
+    p.enable_position_tracking();
+    p.next().unwrap().unwrap();
+    assert_eq!(p.current_pos(), ErrorPos::new(2, 3));
+}
@@ -0,0 +1,77 @@
+extern crate xmlparser as xml;
+
+use xml::{ExternalId, Tokenizer};
+
+#[test]
+fn prolog_01() {
+    let text = "\
+<?xml version='1.0' encoding='UTF-8' standalone='no'?>
+<!-- comment -->
+<?pi?>
+<!DOCTYPE svg PUBLIC 'id' 'svg.dtd' [<!ENTITY a 'b'>]>
+<svg><invalid</svg>";
+
+    let prolog = Tokenizer::parse_prolog(text).unwrap();
+    assert_eq!(prolog.version.unwrap().to_str(), "1.0");
+    assert_eq!(prolog.encoding.unwrap().to_str(), "UTF-8");
+    assert_eq!(prolog.standalone.unwrap().to_str(), "no");
+    assert_eq!(prolog.doctype.unwrap().to_str(), "svg");
+    match prolog.external_id {
+        Some(ExternalId::Public(id, url)) => {
+            assert_eq!(id.to_str(), "id");
+            assert_eq!(url.to_str(), "svg.dtd");
+        }
+        _ => panic!(),
+    }
+    assert!(prolog.has_internal_subset);
+    assert_eq!(&text[prolog.root_start.unwrap()..], "<svg><invalid</svg>");
+}
+
+#[test]
+fn prolog_02() {
+    let prolog = Tokenizer::parse_prolog("<!DOCTYPE svg SYSTEM 'svg.dtd'><svg/>").unwrap();
+    assert_eq!(prolog.version, None);
+    assert_eq!(prolog.doctype.unwrap().to_str(), "svg");
+    assert!(!prolog.has_internal_subset);
+    assert_eq!(prolog.root_start, Some(31));
+}
+
+#[test]
+fn prolog_03() {
+    let prolog = Tokenizer::parse_prolog("<?xml version='1.0'?><svg/>").unwrap();
+    assert_eq!(prolog.version.unwrap().to_str(), "1.0");
+    assert_eq!(prolog.encoding, None);
+    assert_eq!(prolog.doctype, None);
+    assert_eq!(prolog.external_id, None);
+    assert_eq!(prolog.root_start, Some(21));
+}
+
+#[test]
+fn prolog_04() {
+    let prolog = Tokenizer::parse_prolog("  <svg/>").unwrap();
+    assert_eq!(prolog.version, None);
+    assert_eq!(prolog.doctype, None);
+    assert_eq!(prolog.root_start, Some(2));
+}
+
+#[test]
+fn prolog_05() {
+    let prolog = Tokenizer::parse_prolog("<!-- comment -->").unwrap();
+    assert_eq!(prolog.root_start, None);
+}
+
+#[test]
+fn prolog_err_01() {
+    let err = Tokenizer::parse_prolog("<?xml version='2.0'?><svg/>").unwrap_err();
+    assert!(err.to_string().starts_with("invalid token 'XML declaration' at 1:1"));
+}
+
+#[test]
+fn prolog_seek() {
+    let text = "<?xml version='1.0'?>\n<svg/>";
+    let prolog = Tokenizer::parse_prolog(text).unwrap();
+
+    let mut p = Tokenizer::from(text);
+    p.seek(prolog.root_start.unwrap()).unwrap();
+    assert!(p.next().unwrap().unwrap().as_element_start().is_some());
+}
@@ -0,0 +1,53 @@
+extern crate xmlparser as xml;
+
+use xml::{PseudoAttributes, StrSpan};
+
+fn collect(content: &str) -> Vec<Result<(String, String), String>> {
+    PseudoAttributes::from(StrSpan::from(content)).map(|attr| {
+        attr.map(|(name, value)| (name.to_str().to_string(), value.to_str().to_string()))
+            .map_err(|e| e.to_string())
+    }).collect()
+}
+
+#[test]
+fn pseudo_attributes_01() {
+    assert_eq!(collect("type=\"text/xsl\" href='style.xsl'"), vec![
+        Ok(("type".to_string(), "text/xsl".to_string())),
+        Ok(("href".to_string(), "style.xsl".to_string())),
+    ]);
+}
+
+#[test]
+fn pseudo_attributes_02() {
+    assert_eq!(collect("  a = 'b'\n\tc=\"\"  "), vec![
+        Ok(("a".to_string(), "b".to_string())),
+        Ok(("c".to_string(), "".to_string())),
+    ]);
+}
+
+#[test]
+fn pseudo_attributes_03() {
+    assert_eq!(collect(""), vec![]);
+}
+
+#[test]
+fn pseudo_attributes_err_01() {
+    assert_eq!(collect("type='text/xsl' href='style.xsl"), vec![
+        Ok(("type".to_string(), "text/xsl".to_string())),
+        Err("invalid token 'processing instruction' at 1:17 cause unexpected end of stream".to_string()),
+    ]);
+}
+
+#[test]
+fn pseudo_attributes_err_02() {
+    assert_eq!(collect("type=text/xsl"), vec![
+        Err("invalid token 'processing instruction' at 1:1 cause expected quote mark not 't' at 1:6".to_string()),
+    ]);
+}
+
+#[test]
+fn pseudo_attributes_err_03() {
+    assert_eq!(collect("a='b'c='d'"), vec![
+        Err("invalid token 'processing instruction' at 1:1 cause expected space not 'c' at 1:6".to_string()),
+    ]);
+}
@@ -0,0 +1,284 @@
+extern crate xmlparser as xml;
+
+#[macro_use] mod token;
+use token::*;
+
+use xml::PushTokenizer;
+
+fn collect_pushed(text: &[u8], chunk_ends: &[usize]) -> Vec<String> {
+    let mut p = PushTokenizer::new();
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    for &end in chunk_ends.iter().chain(Some(&text.len())) {
+        p.feed(&text[start..end]);
+        start = end;
+
+        while let Some(t) = p.next_token() {
+            tokens.push(format!("{:?}", to_test_token(t)));
+        }
+    }
+
+    p.end_of_input();
+    while let Some(t) = p.next_token() {
+        tokens.push(format!("{:?}", to_test_token(t)));
+    }
+
+    assert!(p.is_finished());
+    tokens
+}
+
+fn collect(text: &str) -> Vec<String> {
+    xml::Tokenizer::from(text).map(|t| format!("{:?}", to_test_token(t))).collect()
+}
+
+// Splits the text at each position.
+fn check_all_splits(text: &str) {
+    let expected = collect(text);
+    for i in 0..text.len() + 1 {
+        assert_eq!(collect_pushed(text.as_bytes(), &[i]), expected, "split at {}", i);
+    }
+}
+
+#[test]
+fn push_01() {
+    check_all_splits("<?xml version='1.0' standalone='yes'?>\n\
+                      <!DOCTYPE svg [<!ENTITY a 'b'>]>\n\
+                      <svg width='10' height=\"20\"><!--c--><text>a &amp; b</text><![CDATA[d]]><?pi x?></svg>\n");
+}
+
+#[test]
+fn push_02() {
+    // Mid-tag and mid-attribute splits.
+    let text = "<svg xmlns='http://www.w3.org/2000/svg'><rect x='1'/></svg>";
+    assert_eq!(collect_pushed(text.as_bytes(), &[3, 11, 25, 47, 50]), collect(text));
+}
+
+#[test]
+fn push_03() {
+    // Byte at a time, with a multi-byte character.
+    let text = "<a b='т'>текст</a>";
+    let ends: Vec<usize> = (1..text.len()).collect();
+    assert_eq!(collect_pushed(text.as_bytes(), &ends), collect(text));
+}
+
+#[test]
+fn push_04() {
+    let mut p = PushTokenizer::new();
+    p.feed(b"<a><b");
+    assert_eq!(to_test_token(p.next_token().unwrap()), Token::ElementStart("", "a"));
+    assert_eq!(to_test_token(p.next_token().unwrap()), Token::ElementEnd(ElementEnd::Open));
+    assert!(p.next_token().is_none());
+    assert!(p.next_token().is_none());
+
+    p.feed(b" c='");
+    assert_eq!(to_test_token(p.next_token().unwrap()), Token::ElementStart("", "b"));
+    assert!(p.next_token().is_none());
+
+    p.end_of_input();
+    assert_eq!(to_test_token(p.next_token().unwrap()),
+               Token::Error("invalid token 'attribute' at 1:6 cause unexpected end of stream \
+                             while parsing element 'b'".to_string()));
+    assert!(p.next_token().is_none());
+}
+
+#[test]
+fn push_05() {
+    // A parsing error is reported only at the end of input,
+    // since more data could complete the token.
+    let mut p = PushTokenizer::new();
+    p.feed(b"<a b=c/>");
+    assert_eq!(to_test_token(p.next_token().unwrap()), Token::ElementStart("", "a"));
+    assert!(p.next_token().is_none());
+
+    p.feed(b"                ");
+    assert!(p.next_token().is_none());
+
+    p.end_of_input();
+    assert_eq!(to_test_token(p.next_token().unwrap()),
+               Token::Error("invalid token 'attribute' at 1:3 cause expected quote mark not 'c' at 1:6 \
+                             while parsing element 'a'".to_string()));
+    assert!(p.next_token().is_none());
+}
+
+#[test]
+fn push_06() {
+    let mut p = PushTokenizer::new();
+    p.feed(b"<a>\xFF</a>");
+    assert_eq!(to_test_token(p.next_token().unwrap()), Token::ElementStart("", "a"));
+    assert_eq!(to_test_token(p.next_token().unwrap()), Token::ElementEnd(ElementEnd::Open));
+    assert_eq!(to_test_token(p.next_token().unwrap()),
+               Token::Error("invalid UTF-8 at 1:4".to_string()));
+    assert!(p.next_token().is_none());
+}
+
+#[test]
+fn push_07() {
+    // An incomplete UTF-8 sequence at the end of input.
+    let mut p = PushTokenizer::new();
+    p.feed(b"<a/>\xD1");
+    p.end_of_input();
+    assert_eq!(to_test_token(p.next_token().unwrap()), Token::ElementStart("", "a"));
+    assert_eq!(to_test_token(p.next_token().unwrap()), Token::ElementEnd(ElementEnd::Empty));
+    assert_eq!(to_test_token(p.next_token().unwrap()),
+               Token::Error("invalid UTF-8 at 1:5".to_string()));
+    assert!(p.next_token().is_none());
+}
+
+#[test]
+fn push_08() {
+    // A byte at a time, with errors positions after discarded lines.
+    let texts = [
+        "<a b='т'>\r\nтекст\r\n<b c='1' d='2'/></a>",
+        "<a>\n\r\n\rтекст<b c='1' c=/></a>",
+        "<a>\r\n<b\r\n  c='1'\r\n  d='2'\r\n  e=3/></a>",
+        "<a>\n  текст\n</b>",
+    ];
+
+    for text in &texts {
+        let ends: Vec<usize> = (1..text.len()).collect();
+        assert_eq!(collect_pushed(text.as_bytes(), &ends), collect(text));
+    }
+}
+
+#[test]
+fn push_09() {
+    // The consumed input is discarded.
+    let mut p = PushTokenizer::new();
+    p.feed(b"<a>text<b x='1'");
+    for _ in 0..4 {
+        p.next_token().unwrap().unwrap();
+    }
+    assert!(p.next_token().is_none());
+    assert!(!p.is_finished());
+
+    p.feed(b"/></a>");
+    let offset = p.buffer_offset();
+    assert_eq!(offset, 8); // The element name is kept.
+    match p.next_token().unwrap().unwrap() {
+        xml::Token::Attribute((_, local), value, _) => {
+            assert_eq!(offset + local.start(), 10);
+            assert_eq!(offset + value.start(), 13);
+        }
+        _ => panic!(),
+    }
+
+    p.end_of_input();
+    assert_eq!(to_test_token(p.next_token().unwrap()), Token::ElementEnd(ElementEnd::Empty));
+    assert_eq!(to_test_token(p.next_token().unwrap()), Token::ElementEnd(ElementEnd::Close("", "a")));
+    assert!(p.next_token().is_none());
+    assert!(p.is_finished());
+}
+
+#[test]
+fn push_10() {
+    // IDs are kept after the input is discarded.
+    let mut p = PushTokenizer::new();
+    p.enable_id_checking();
+    let mut err = None;
+    for chunk in &["<a>\n<b xml:id='x", "'/>\n", "<c xml:id=' x '/>", "</a>"] {
+        p.feed(chunk.as_bytes());
+        while let Some(t) = p.next_token() {
+            err = t.err().map(|e| e.to_string());
+        }
+    }
+
+    // A check error is reported as soon as the token is complete.
+    assert_eq!(err.unwrap(), "duplicated id at 3:4, previously defined at 2:4");
+}
+
+#[test]
+fn push_11() {
+    let mut p = PushTokenizer::new();
+    p.set_strictness(xml::Strictness {
+        allow_double_hyphen_in_comments: true,
+        ..xml::Strictness::default()
+    });
+    p.set_limits(xml::Limits { max_depth: 2, ..xml::Limits::default() });
+    p.enable_xml_space_tracking();
+    p.enable_position_tracking();
+
+    p.feed(b"<a xml:space='preserve'>\n<!-- a -- b -->");
+    while let Some(t) = p.next_token() {
+        t.unwrap();
+    }
+    assert_eq!(p.xml_space(), xml::XmlSpace::Preserve);
+    assert_eq!(p.current_pos(), xml::ErrorPos::new(2, 1));
+
+    p.feed(b"<b><c/></b></a>");
+    p.end_of_input();
+    let mut err = None;
+    while let Some(t) = p.next_token() {
+        err = t.err().map(|e| e.to_string());
+    }
+    assert_eq!(err.unwrap(), "nesting depth limit exceeded at 2:19");
+}
+
+#[test]
+fn push_12() {
+    // A text is returned once it is complete.
+    let mut p = PushTokenizer::new();
+    p.feed(b"<a>t");
+    p.next_token().unwrap().unwrap();
+    p.next_token().unwrap().unwrap();
+    for _ in 0..100 {
+        p.feed(b"text ");
+        assert!(p.next_token().is_none());
+    }
+
+    p.feed(b"<");
+    match p.next_token().unwrap().unwrap() {
+        xml::Token::Text(text) => assert_eq!(text.len(), 501),
+        _ => panic!(),
+    }
+    assert!(p.next_token().is_none());
+}
+
+#[test]
+fn push_13() {
+    let text = "<a><!-- a -- b --></a><b/>";
+    let mut p = PushTokenizer::new();
+    p.set_multi_document_mode();
+    p.enable_end_of_document_token();
+    p.enable_error_recovery();
+    p.feed(text.as_bytes());
+    p.end_of_input();
+
+    // The whole text is fed at once, so offsets are the same.
+    // Unlike `PartialEq`, `Debug` includes span positions.
+    let mut tokens = Vec::new();
+    while let Some(t) = p.next_token() {
+        tokens.push(format!("{:?}", t));
+    }
+
+    let mut t = xml::Tokenizer::from(text);
+    t.set_multi_document_mode();
+    t.enable_end_of_document_token();
+    t.enable_error_recovery();
+    let expected: Vec<_> = t.map(|t| format!("{:?}", t)).collect();
+
+    assert!(expected.iter().any(|t| t.starts_with("Ok(DocumentBoundary)")));
+    assert!(expected.iter().any(|t| t.starts_with("Err(")));
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn push_14() {
+    let mut p = PushTokenizer::new();
+    p.skip_whitespace_text();
+
+    let mut tokens = Vec::new();
+    for chunk in "<a>\n  <b/>\n</a>".as_bytes().chunks(1) {
+        p.feed(chunk);
+        while let Some(t) = p.next_token() {
+            tokens.push(format!("{:?}", to_test_token(t)));
+        }
+    }
+
+    p.end_of_input();
+    while let Some(t) = p.next_token() {
+        tokens.push(format!("{:?}", to_test_token(t)));
+    }
+
+    assert_eq!(tokens.len(), 5);
+    assert!(!tokens.iter().any(|t| t.starts_with("Whitespaces")));
+}
@@ -0,0 +1,76 @@
+extern crate xmlparser as xml;
+
+#[macro_use] mod token;
+use token::*;
+
+macro_rules! test {
+    ($name:ident, $text:expr, $($token:expr),*) => (
+        #[test]
+        fn $name() {
+            let mut p = xml::Tokenizer::from($text);
+            p.enable_error_recovery();
+            $(
+                assert_eq!(to_test_token(p.next().unwrap()), $token);
+            )*
+            assert!(p.next().is_none());
+        }
+    )
+}
+
+test!(recovery_01, "<a><b =/><c/></a>",
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::ElementStart("", "b"),
+    Token::Error("invalid token 'attribute' at 1:6 cause \
+                  invalid name token while parsing element 'b'".to_string()),
+    Token::ElementStart("", "c"),
+    Token::ElementEnd(ElementEnd::Empty),
+    Token::ElementEnd(ElementEnd::Close("", "a"))
+);
+
+test!(recovery_02, "<a><!-- a -- b --><b/><![CDATA[x]><c/></a>",
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Error("invalid token 'comment' at 1:4".to_string()),
+    Token::ElementStart("", "b"),
+    Token::ElementEnd(ElementEnd::Empty),
+    Token::Error("invalid token 'CDATA' at 1:23 cause \
+                  expected ']]>' not '' at 1:43".to_string()),
+    Token::ElementStart("", "c"),
+    Token::ElementEnd(ElementEnd::Empty),
+    Token::ElementEnd(ElementEnd::Close("", "a"))
+);
+
+test!(recovery_03, "<!DOCTYPE a [<!ENTITY x>]><a/>",
+    Token::DtdStart("a", None),
+    Token::Error("invalid token 'entity declaration' at 1:14 cause \
+                  expected space not '>' at 1:24".to_string()),
+    Token::DtdEnd,
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Empty)
+);
+
+test!(recovery_04, "<a/>text<b/>",
+    Token::ElementStart("", "a"),
+    Token::ElementEnd(ElementEnd::Empty),
+    Token::Error("unexpected token 'character data' at 1:5".to_string()),
+    Token::Error("unexpected token 'element start' at 1:9".to_string())
+);
+
+#[test]
+fn not_balanced() {
+    let mut p = xml::Tokenizer::from("<a><b =/></a></a>");
+    p.enable_error_recovery();
+    p.by_ref().count();
+    assert!(!p.is_balanced());
+}
+
+#[test]
+fn strict() {
+    // Checks don't skip anything.
+    let mut p = xml::Tokenizer::from("<a><b x='1' x='2'><c/></b><d/></a>");
+    p.enable_error_recovery();
+    p.set_strict_mode();
+    let errors: Vec<_> = p.filter_map(|t| t.err()).map(|e| e.to_string()).collect();
+    assert_eq!(errors, vec!["duplicated attribute at 1:13".to_string()]);
+}
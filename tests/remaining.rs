@@ -0,0 +1,37 @@
+extern crate xmlparser as xml;
+
+use xml::Tokenizer;
+
+#[test]
+fn remaining_01() {
+    let text = "<a x='1'><payload/>\u{1}\u{2} binary</a>";
+    let mut p = Tokenizer::from(text);
+    assert_eq!(p.remaining(), text);
+
+    p.next().unwrap().unwrap(); // ElementStart
+    assert_eq!(p.remaining(), " x='1'><payload/>\u{1}\u{2} binary</a>");
+    p.next().unwrap().unwrap(); // Attribute
+    p.next().unwrap().unwrap(); // ElementEnd::Open
+    p.next().unwrap().unwrap(); // ElementStart
+    p.next().unwrap().unwrap(); // ElementEnd::Empty
+    assert_eq!(p.remaining(), "\u{1}\u{2} binary</a>");
+}
+
+#[test]
+fn remaining_02() {
+    let mut p = Tokenizer::from("<a/>");
+    for token in &mut p {
+        token.unwrap();
+    }
+
+    assert_eq!(p.remaining(), "");
+}
+
+#[test]
+fn remaining_03() {
+    let mut p = Tokenizer::from("<a><<b/></a>");
+    p.next().unwrap().unwrap();
+    p.next().unwrap().unwrap();
+    assert!(p.next().unwrap().is_err());
+    assert_eq!(p.remaining(), "");
+}
@@ -0,0 +1,99 @@
+extern crate xmlparser as xml;
+
+use xml::{parse_with, Control, ElementEnd, Error, Handler, StrSpan, Token};
+
+#[derive(Default)]
+struct Log {
+    events: Vec<String>,
+    stop_at: Option<&'static str>,
+}
+
+impl<'a> Handler<'a> for Log {
+    fn start_element(&mut self, _: StrSpan<'a>, local: StrSpan<'a>) -> Control {
+        self.events.push(format!("start {}", local));
+        if self.stop_at == Some(local.to_str()) {
+            return Control::Stop;
+        }
+
+        Control::Continue
+    }
+
+    fn attribute(&mut self, _: StrSpan<'a>, local: StrSpan<'a>, value: StrSpan<'a>) -> Control {
+        self.events.push(format!("attribute {}={}", local, value));
+        Control::Continue
+    }
+
+    fn end_element(&mut self, end: ElementEnd<'a>) -> Control {
+        let event = match end {
+            ElementEnd::Open => "open".to_string(),
+            ElementEnd::Close(_, local) => format!("close {}", local),
+            ElementEnd::Empty => "empty".to_string(),
+        };
+
+        self.events.push(event);
+        Control::Continue
+    }
+
+    fn characters(&mut self, text: StrSpan<'a>) -> Control {
+        self.events.push(format!("characters {:?}", text.to_str()));
+        Control::Continue
+    }
+
+    fn comment(&mut self, text: StrSpan<'a>) -> Control {
+        self.events.push(format!("comment {}", text));
+        Control::Continue
+    }
+
+    fn pi(&mut self, target: StrSpan<'a>, _: Option<StrSpan<'a>>) -> Control {
+        self.events.push(format!("pi {}", target));
+        Control::Continue
+    }
+
+    fn other(&mut self, token: Token<'a>) -> Control {
+        if let Token::Declaration(..) = token {
+            self.events.push("declaration".to_string());
+        }
+
+        Control::Continue
+    }
+
+    fn error(&mut self, error: Error) {
+        self.events.push(format!("error {}", error));
+    }
+}
+
+#[test]
+fn sax_01() {
+    let mut log = Log::default();
+    parse_with("<?xml version='1.0'?><?pi?><a x='1'><!--c--><b/> text </a>", &mut log);
+    assert_eq!(log.events, vec![
+        "declaration",
+        "pi pi",
+        "start a",
+        "attribute x=1",
+        "open",
+        "comment c",
+        "start b",
+        "empty",
+        "characters \" text \"",
+        "close a",
+    ]);
+}
+
+#[test]
+fn sax_02() {
+    let mut log = Log { stop_at: Some("b"), ..Log::default() };
+    parse_with("<a><b/><c/></a>", &mut log);
+    assert_eq!(log.events, vec!["start a", "open", "start b"]);
+}
+
+#[test]
+fn sax_03() {
+    let mut log = Log::default();
+    parse_with("<a><</a>", &mut log);
+    assert_eq!(log.events, vec![
+        "start a",
+        "open",
+        "error invalid token 'element start' at 1:4 cause invalid name token",
+    ]);
+}
@@ -0,0 +1,207 @@
+extern crate xmlparser as xml;
+
+use xml::{Tokenizer, Token};
+
+const TEXT: &str = "<?xml version='1.0'?>\n<a x='1' y='2'>text<b/><!--c--></a>";
+
+fn starts(text: &str) -> Vec<usize> {
+    let mut p = Tokenizer::from(text);
+    let mut starts = Vec::new();
+    while let Some(token) = p.next() {
+        token.unwrap();
+        starts.push(p.token_start());
+    }
+
+    starts
+}
+
+#[test]
+fn seek_01() {
+    // Unlike `PartialEq`, `Debug` includes span positions.
+    let tokens: Vec<_> = Tokenizer::from(TEXT).map(|t| format!("{:?}", t.unwrap())).collect();
+    let token_starts = starts(TEXT);
+
+    // Seek to each token start from the end of the document.
+    for (idx, start) in starts(TEXT).into_iter().enumerate() {
+        let mut p = Tokenizer::from(TEXT);
+        for token in &mut p {
+            token.unwrap();
+        }
+
+        p.seek(start).unwrap();
+        let mut rest = Vec::new();
+        let mut rest_starts = Vec::new();
+        while let Some(token) = p.next() {
+            rest.push(format!("{:?}", token.unwrap()));
+            rest_starts.push(p.token_start());
+        }
+
+        assert_eq!(rest, &tokens[idx..]);
+        assert_eq!(rest_starts, &token_starts[idx..]);
+    }
+}
+
+#[test]
+fn seek_02() {
+    let mut p = Tokenizer::from(TEXT);
+    p.next().unwrap().unwrap();
+    p.next().unwrap().unwrap();
+    let start = p.token_start();
+    assert_eq!(start, 22);
+
+    p.seek(start).unwrap();
+    match p.next().unwrap().unwrap() {
+        Token::ElementStart(_, local) => {
+            assert_eq!(local.to_str(), "a");
+            assert_eq!(local.start(), 23);
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn seek_03() {
+    let mut p = Tokenizer::from(TEXT);
+    p.next().unwrap().unwrap();
+
+    p.seek(TEXT.len()).unwrap();
+    assert!(p.next().is_none());
+}
+
+#[test]
+fn seek_04() {
+    let mut p = Tokenizer::from(TEXT);
+    p.next().unwrap().unwrap();
+    p.next().unwrap().unwrap();
+
+    // Inside the element name.
+    assert_eq!(p.seek(23).unwrap_err().to_string(), "invalid seek offset at 2:2");
+    // The tokenizer is unchanged.
+    match p.next().unwrap().unwrap() {
+        Token::Attribute((_, local), _, _) => {
+            assert_eq!(local.to_str(), "x");
+            assert_eq!(local.start(), 25);
+        }
+        _ => panic!(),
+    }
+
+    assert!(p.seek(TEXT.len() + 1).is_err());
+}
+
+#[test]
+fn seek_05() {
+    let mut p = Tokenizer::from("<a><b></b></a>");
+    p.set_strict_mode();
+    for token in &mut p {
+        token.unwrap();
+    }
+
+    // Strict mode state must be restored too,
+    // otherwise `</a>` will be reported as unexpected.
+    p.seek(3).unwrap();
+    for token in p {
+        token.unwrap();
+    }
+}
+
+#[test]
+fn seek_06() {
+    // Trailing spaces are consumed after the last token.
+    let text = "<a/>\n";
+    let mut p = Tokenizer::from(text);
+    p.seek(text.len()).unwrap();
+    assert!(p.next().is_none());
+
+    assert!(p.seek(text.len() - 1).is_err());
+}
+
+#[test]
+fn seek_07() {
+    // The replay uses the same options.
+    let text = "<a><!-- a -- b --><b/></a>";
+    let mut p = Tokenizer::from(text);
+    p.set_strictness(xml::Strictness {
+        allow_double_hyphen_in_comments: true,
+        .. xml::Strictness::default()
+    });
+    for token in &mut p {
+        token.unwrap();
+    }
+
+    p.seek(18).unwrap();
+    match p.next().unwrap().unwrap() {
+        Token::ElementStart(_, local) => {
+            assert_eq!(local.to_str(), "b");
+            assert_eq!(local.start(), 19);
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn seek_08() {
+    let text = "<!DOCTYPE a [<!ELEMENT a EMPTY><!NOTATION n SYSTEM 'x'>]><a/>";
+    let mut p = Tokenizer::from(text);
+    p.enable_dtd_declaration_tokens();
+    for token in &mut p {
+        token.unwrap();
+    }
+
+    p.seek(text.find("<!NOTATION").unwrap()).unwrap();
+    match p.next().unwrap().unwrap() {
+        Token::NotationDeclaration(name, _, _) => {
+            assert_eq!(name.to_str(), "n");
+            assert_eq!(name.start(), 42);
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn seek_09() {
+    // Offsets after a recovered error are valid.
+    let text = "<a><b =/><c/></a>";
+    let mut p = Tokenizer::from(text);
+    p.enable_error_recovery();
+    p.set_strict_mode();
+    for _ in &mut p {}
+
+    p.seek(text.find("<c").unwrap()).unwrap();
+    match p.next().unwrap().unwrap() {
+        Token::ElementStart(_, local) => assert_eq!(local.start(), 10),
+        _ => panic!(),
+    }
+
+    // `</b>` is missing, so the strict mode reports `</a>`.
+    assert!(p.any(|t| t.is_err()));
+}
+
+#[test]
+fn seek_10() {
+    // An offset after an error is unreachable.
+    let text = "<a><b =/><c/></a>";
+    let mut p = Tokenizer::from(text);
+    p.next().unwrap().unwrap();
+    assert!(p.seek(text.find("<c").unwrap()).is_err());
+
+    // The tokenizer is unchanged.
+    assert_eq!(p.next().unwrap().unwrap(), Token::ElementEnd(xml::ElementEnd::Open));
+    assert_eq!(p.token_start(), 2);
+}
+
+#[test]
+fn seek_11() {
+    // Backtracking over each token.
+    let mut p = Tokenizer::from(TEXT);
+    p.enable_id_checking();
+    let tokens = starts(TEXT);
+    for (idx, &start) in tokens.iter().enumerate() {
+        p.seek(start).unwrap();
+        p.next().unwrap().unwrap();
+        assert_eq!(p.token_start(), start);
+        if let Some(&next) = tokens.get(idx + 1) {
+            p.next().unwrap().unwrap();
+            assert_eq!(p.token_start(), next);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+extern crate xmlparser as xml;
+
+fn check(text: &str) -> Result<(), String> {
+    let mut p = xml::Tokenizer::from(text);
+    p.enable_standalone_checking();
+    for token in p {
+        token.map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn standalone_01() {
+    check("<?xml version='1.0' standalone='yes'?>\n\
+           <!DOCTYPE a SYSTEM 'a.dtd' [<!ENTITY b 'c'>]>\n\
+           <a v='&b;'>&b; &amp; &#x20;</a>").unwrap();
+}
+
+#[test]
+fn standalone_02() {
+    // Not a standalone document.
+    check("<?xml version='1.0' standalone='no'?>\n\
+           <!DOCTYPE a SYSTEM 'a.dtd'>\n\
+           <a>&b;</a>").unwrap();
+    check("<!DOCTYPE a SYSTEM 'a.dtd'><a>&b;</a>").unwrap();
+}
+
+#[test]
+fn standalone_03() {
+    // An entity can be declared only in the external subset.
+    assert_eq!(check("<?xml version='1.0' standalone='yes'?>\n\
+                      <!DOCTYPE a SYSTEM 'a.dtd'>\n\
+                      <a>text &b;</a>").unwrap_err(),
+               "undeclared entity in a standalone document at 3:9");
+}
+
+#[test]
+fn standalone_04() {
+    assert_eq!(check("<?xml version='1.0' standalone='yes'?>\n\
+                      <!DOCTYPE a [<!ENTITY b 'c'>]>\n\
+                      <a v='&b;&c;'/>").unwrap_err(),
+               "undeclared entity in a standalone document at 3:10");
+}
+
+#[test]
+fn standalone_05() {
+    // A parameter entity can't be referenced from the content.
+    assert_eq!(check("<?xml version='1.0' standalone='yes'?>\n\
+                      <!DOCTYPE a [<!ENTITY % b 'c'>]>\n\
+                      <a>&b;</a>").unwrap_err(),
+               "undeclared entity in a standalone document at 3:4");
+}
+
+#[test]
+fn standalone_06() {
+    // Disabled by default.
+    let text = "<?xml version='1.0' standalone='yes'?><a>&b;</a>";
+    assert!(xml::Tokenizer::from(text).all(|t| t.is_ok()));
+}
@@ -0,0 +1,88 @@
+extern crate xmlparser as xml;
+
+use xml::{Stream, Reference};
+
+fn reference(text: &str) -> String {
+    match Stream::from(text).consume_reference() {
+        Ok(Reference::CharRef(c)) => c.to_string(),
+        Ok(Reference::EntityRef(name)) => name.to_str().to_string(),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[test]
+fn reference_01() {
+    assert_eq!(reference("&#65;"), "A");
+    assert_eq!(reference("&#x41;"), "A");
+    assert_eq!(reference("&#0000000000000000000000000065;"), "A");
+    assert_eq!(reference("&#x00000000000000000000000041;"), "A");
+    assert_eq!(reference("&lt;"), "<");
+    assert_eq!(reference("&name;"), "name");
+}
+
+#[test]
+fn reference_02() {
+    assert_eq!(reference("&#99999999999999;"), "invalid reference at 1:1");
+    assert_eq!(reference("&#99999999999999999999999999999999999999999;"), "invalid reference at 1:1");
+    assert_eq!(reference("&#xFFFFFFFFFFFFFFFFFFFF;"), "invalid reference at 1:1");
+}
+
+#[test]
+fn reference_03() {
+    // Out of the Unicode range.
+    assert_eq!(reference("&#x110000;"), "invalid reference at 1:1");
+    // Surrogate.
+    assert_eq!(reference("&#xD800;"), "invalid reference at 1:1");
+    // Not an XML char.
+    assert_eq!(reference("&#0;"), "invalid reference at 1:1");
+    assert_eq!(reference("&#xFFFE;"), "invalid reference at 1:1");
+}
+
+#[test]
+fn reference_04() {
+    assert_eq!(reference("&#;"), "invalid reference at 1:1");
+    assert_eq!(reference("&#x;"), "invalid reference at 1:1");
+    assert_eq!(reference("&#65"), "invalid reference at 1:1");
+    assert_eq!(reference("&"), "invalid reference at 1:1");
+    assert_eq!(reference("&;"), "invalid reference at 1:1");
+    assert_eq!(reference(""), "invalid reference at 1:1");
+}
+
+#[test]
+fn reference_05() {
+    let text = "a\n  &#99999999999999;";
+    let mut s = Stream::from(text);
+    s.advance(4);
+    assert_eq!(s.consume_reference().unwrap_err().to_string(), "invalid reference at 2:3");
+}
+
+#[test]
+fn consume_quoted_01() {
+    let mut s = Stream::from("\"a'b\"");
+    assert_eq!(s.consume_quoted().unwrap().to_str(), "a'b");
+    assert!(s.at_end());
+
+    assert_eq!(Stream::from("a").consume_quoted().unwrap_err().to_string(),
+               "expected quote mark not 'a' at 1:1");
+    assert_eq!(Stream::from("'a").consume_quoted().unwrap_err().to_string(),
+               "unexpected end of stream");
+}
+
+#[test]
+fn set_pos_01() {
+    // Backtracking.
+    let mut s = Stream::from("name:value");
+    let start = s.pos();
+    assert!(s.consume_byte(b'x').is_err());
+    s.set_pos(start);
+    assert_eq!(s.consume_name().unwrap().to_str(), "name:value");
+}
+
+#[test]
+fn span_error_pos_01() {
+    // Errors positions are relative to the original text.
+    let text = "<a d='M 10 x'/>";
+    let mut s = Stream::from(xml::StrSpan::from_substr(text, 6, 12));
+    s.skip_bytes(|_, c| c != b'x');
+    assert_eq!(s.consume_byte(b'0').unwrap_err().to_string(), "expected '0' but found 'x' at 1:12");
+}
@@ -0,0 +1,80 @@
+extern crate xmlparser as xml;
+
+use xml::{Strictness, Tokenizer};
+
+fn errors(text: &str, strictness: Strictness) -> Vec<String> {
+    let mut p = Tokenizer::from(text);
+    p.set_strict_mode();
+    p.set_strictness(strictness);
+    p.filter_map(|t| t.err()).map(|e| e.to_string()).collect()
+}
+
+#[test]
+fn default_01() {
+    let opt = Strictness::default();
+    assert_eq!(errors("<a><!-- a -- b --></a>", opt), vec!["invalid token 'comment' at 1:4"]);
+    assert_eq!(errors("<a b='1' b='2'/>", opt), vec!["duplicated attribute at 1:10"]);
+    assert_eq!(errors("<a>\u{1}</a>", opt),
+               vec!["invalid token 'character data' at 1:4 cause unexpected character '\\u{1}' at 1:4"]);
+}
+
+#[test]
+fn comments_01() {
+    let opt = Strictness { allow_double_hyphen_in_comments: true, ..Strictness::default() };
+    let mut p = Tokenizer::from("<a><!-- a -- b --></a>");
+    p.set_strictness(opt);
+    match p.nth(2).unwrap().unwrap() {
+        xml::Token::Comment(text) => assert_eq!(text.to_str(), " a -- b "),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn duplicated_attributes_01() {
+    let opt = Strictness { allow_duplicated_attributes: true, ..Strictness::default() };
+    assert!(errors("<a b='1' b='2'/>", opt).is_empty());
+}
+
+#[test]
+fn control_chars_01() {
+    let opt = Strictness { allow_control_chars: true, ..Strictness::default() };
+    assert!(errors("<a b='\u{2}'>\u{1}<![CDATA[\u{1F}]]></a>", opt).is_empty());
+    // NUL is still an error.
+    assert_eq!(errors("<a>\0</a>", opt),
+               vec!["invalid token 'character data' at 1:4 cause unexpected character '\\0' at 1:4"]);
+}
+
+#[test]
+fn xml_1_1_01() {
+    let text = "<?xml version='1.1'?><!DOCTYPE a [<!ENTITY e '&#x1;'>]><a/>";
+    assert_eq!(errors(text, Strictness::default()).len(), 1);
+
+    let opt = Strictness { xml_1_1: true, ..Strictness::default() };
+    assert!(errors(text, opt).is_empty());
+}
+
+#[test]
+fn xml_1_1_02() {
+    // Only documents declared as XML 1.1 are affected.
+    let opt = Strictness { xml_1_1: true, ..Strictness::default() };
+    let text = "<?xml version='1.0'?><!DOCTYPE a [<!ENTITY e '&#x1;'>]><a/>";
+    assert_eq!(errors(text, opt).len(), 1);
+    // NUL is never allowed.
+    let text = "<?xml version='1.1'?><!DOCTYPE a [<!ENTITY e '&#x0;'>]><a/>";
+    assert_eq!(errors(text, opt).len(), 1);
+}
+
+#[test]
+fn xml_1_1_03() {
+    // Character references in text and attribute values are checked in the strict mode.
+    let text = "<?xml version='1.1'?><a b='&#x1;'>&#1;&#x20;</a>";
+    assert_eq!(errors(text, Strictness::default()),
+               vec!["invalid token 'attribute' at 1:25 cause invalid reference at 1:28"]);
+
+    let opt = Strictness { xml_1_1: true, ..Strictness::default() };
+    assert!(errors(text, opt).is_empty());
+
+    let text = "<?xml version='1.0'?><a>&#1;</a>";
+    assert_eq!(errors(text, opt),
+               vec!["invalid token 'character data' at 1:25 cause invalid reference at 1:25"]);
+}
@@ -0,0 +1,70 @@
+extern crate xmlparser as xml;
+
+use xml::{Tokenizer, Token};
+
+const TEXT: &str = "<?xml version='1.0'?>\n<a x='1' y='2'>text<b/><!--c--></a>";
+
+#[test]
+fn take_bytes_01() {
+    // No token crosses the budget for any budget.
+    for n in 0..TEXT.len() + 1 {
+        let mut p = Tokenizer::from(TEXT).take_bytes(n);
+        for token in &mut p {
+            let token = token.unwrap();
+            let end = match token {
+                Token::Declaration(..) => 21,
+                Token::ElementStart(_, local) => local.end(),
+                Token::Attribute(_, value, _) => value.end() + 1,
+                Token::Text(text) => text.end(),
+                Token::Comment(text) => text.end() + 3,
+                _ => continue,
+            };
+
+            assert!(end <= n);
+        }
+
+        // Only spaces between tokens can be skipped after the budget.
+        let consumed = p.consumed();
+        assert!(consumed <= n || TEXT[n..consumed].trim().is_empty());
+    }
+}
+
+#[test]
+fn take_bytes_02() {
+    let mut p = Tokenizer::from(TEXT).take_bytes(30);
+    let tokens: Vec<_> = p.by_ref().map(|t| t.unwrap()).collect();
+    assert_eq!(tokens.len(), 3); // Declaration, ElementStart and Attribute
+    assert_eq!(p.consumed(), 31);
+}
+
+#[test]
+fn take_bytes_03() {
+    let mut p = Tokenizer::from(TEXT).take_bytes(TEXT.len());
+    assert_eq!(p.by_ref().count(), Tokenizer::from(TEXT).count());
+    assert_eq!(p.consumed(), TEXT.len());
+}
+
+#[test]
+fn take_bytes_04() {
+    // Resume the parsing with more data.
+    let expected: Vec<_> = Tokenizer::from(TEXT).map(|t| t.unwrap()).collect();
+
+    // Ends in the middle of the text.
+    let mut p = Tokenizer::from(TEXT).take_bytes(40);
+    let mut tokens: Vec<_> = p.by_ref().map(|t| format!("{:?}", t.unwrap())).collect();
+
+    let mut p2 = Tokenizer::from(TEXT);
+    p2.seek(p.consumed()).unwrap();
+    tokens.extend(p2.map(|t| format!("{:?}", t.unwrap())));
+
+    let expected: Vec<_> = expected.iter().map(|t| format!("{:?}", t)).collect();
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn take_bytes_05() {
+    // An error after the budget is not reported.
+    let mut p = Tokenizer::from("<a>text</a><b").take_bytes(11);
+    assert!(p.by_ref().all(|t| t.is_ok()));
+    assert_eq!(p.consumed(), 11);
+}
@@ -44,3 +44,49 @@ test!(whitespaces_04, "<p>&#x9;&#xA;&#xD;&#x20;</p>",
     Token::Whitespaces("&#x9;&#xA;&#xD;&#x20;"),
     Token::ElementEnd(ElementEnd::Close("", "p"))
 );
+
+test!(whitespaces_05, "<p>&#32;&#9;\n</p>",
+    Token::ElementStart("", "p"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Whitespaces("&#32;&#9;\n"),
+    Token::ElementEnd(ElementEnd::Close("", "p"))
+);
+
+// Not a space.
+test!(whitespaces_06, "<p>&#x20;&#xA0;</p>",
+    Token::ElementStart("", "p"),
+    Token::ElementEnd(ElementEnd::Open),
+    Token::Text("&#x20;&#xA0;"),
+    Token::ElementEnd(ElementEnd::Close("", "p"))
+);
+
+#[test]
+fn skip_whitespace_text_01() {
+    let mut p = xml::Tokenizer::from("<a>\n  <b> </b>\n  text\n</a>");
+    p.skip_whitespace_text();
+    let tokens: Vec<_> = p.map(to_test_token).collect();
+    assert_eq!(tokens, vec![
+        Token::ElementStart("", "a"),
+        Token::ElementEnd(ElementEnd::Open),
+        Token::ElementStart("", "b"),
+        Token::ElementEnd(ElementEnd::Open),
+        Token::ElementEnd(ElementEnd::Close("", "b")),
+        Token::Text("\n  text\n"),
+        Token::ElementEnd(ElementEnd::Close("", "a")),
+    ]);
+}
+
+#[test]
+fn skip_whitespace_text_02() {
+    // Character references and `xml:space="preserve"` scopes are kept.
+    let mut p = xml::Tokenizer::from("<a> <b xml:space='preserve'> </b>&#x20;</a>");
+    p.skip_whitespace_text();
+    p.enable_xml_space_tracking();
+    let tokens: Vec<_> = p.map(to_test_token)
+                          .filter(|t| matches!(*t, Token::Whitespaces(_)))
+                          .collect();
+    assert_eq!(tokens, vec![
+        Token::Whitespaces(" "),
+        Token::Whitespaces("&#x20;"),
+    ]);
+}
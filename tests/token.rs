@@ -8,6 +8,9 @@ pub enum Token<'a> {
     DtdStart(&'a str, Option<ExternalId<'a>>),
     EmptyDtd(&'a str, Option<ExternalId<'a>>),
     EntityDecl(&'a str, EntityDefinition<'a>),
+    ElementDecl(&'a str, &'a str),
+    AttlistDecl(&'a str, Vec<(&'a str, &'a str, AttributeDefault<'a>)>),
+    NotationDecl(&'a str, Option<&'a str>, Option<&'a str>),
     DtdEnd,
     ElementStart(&'a str, &'a str),
     Attribute(&'a str, &'a str, &'a str),
@@ -15,6 +18,8 @@ pub enum Token<'a> {
     Text(&'a str),
     Whitespaces(&'a str),
     Cdata(&'a str),
+    DocumentBoundary,
+    EndOfDocument(usize),
     Error(String),
 }
 
@@ -37,6 +42,14 @@ pub enum EntityDefinition<'a> {
     ExternalId(ExternalId<'a>),
 }
 
+#[derive(PartialEq, Debug)]
+pub enum AttributeDefault<'a> {
+    Required,
+    Implied,
+    Value(&'a str),
+    Fixed(&'a str),
+}
+
 #[macro_export]
 macro_rules! test {
     ($name:ident, $text:expr, $($token:expr),*) => (
@@ -93,6 +106,31 @@ pub fn to_test_token(token: Result<xml::Token, xml::Error>) -> Token {
                 }
             )
         }
+        Ok(xml::Token::ElementDeclaration(name, spec)) => {
+            Token::ElementDecl(name.to_str(), spec.to_str())
+        }
+        Ok(xml::Token::AttlistDeclaration(element, defs)) => {
+            Token::AttlistDecl(
+                element.to_str(),
+                defs.map(|def| {
+                    let default = match def.default {
+                        xml::AttributeDefault::Required => AttributeDefault::Required,
+                        xml::AttributeDefault::Implied => AttributeDefault::Implied,
+                        xml::AttributeDefault::Value(v) => AttributeDefault::Value(v.to_str()),
+                        xml::AttributeDefault::Fixed(v) => AttributeDefault::Fixed(v.to_str()),
+                    };
+
+                    (def.name.to_str(), def.att_type.to_str(), default)
+                }).collect(),
+            )
+        }
+        Ok(xml::Token::NotationDeclaration(name, public_id, system_id)) => {
+            Token::NotationDecl(
+                name.to_str(),
+                public_id.map(|v| v.to_str()),
+                system_id.map(|v| v.to_str()),
+            )
+        }
         Ok(xml::Token::DtdEnd) => Token::DtdEnd,
         Ok(xml::Token::ElementStart(prefix, local)) => {
             Token::ElementStart(
@@ -100,7 +138,7 @@ pub fn to_test_token(token: Result<xml::Token, xml::Error>) -> Token {
                 local.to_str(),
             )
         }
-        Ok(xml::Token::Attribute((prefix, local), value)) => {
+        Ok(xml::Token::Attribute((prefix, local), value, _)) => {
             Token::Attribute(
                 prefix.to_str(), local.to_str(), value.to_str()
             )
@@ -119,6 +157,8 @@ pub fn to_test_token(token: Result<xml::Token, xml::Error>) -> Token {
         Ok(xml::Token::Text(text)) => Token::Text(text.to_str()),
         Ok(xml::Token::Whitespaces(text)) => Token::Whitespaces(text.to_str()),
         Ok(xml::Token::Cdata(text)) => Token::Cdata(text.to_str()),
+        Ok(xml::Token::DocumentBoundary) => Token::DocumentBoundary,
+        Ok(xml::Token::EndOfDocument(offset)) => Token::EndOfDocument(offset),
         Err(ref e) => Token::Error(e.to_string()),
     }
 }
@@ -0,0 +1,38 @@
+extern crate xmlparser as xml;
+
+use xml::TokenType;
+
+#[test]
+fn token_type_display() {
+    let list = [
+        (TokenType::XMLDecl, "XML declaration"),
+        (TokenType::Comment, "comment"),
+        (TokenType::PI, "processing instruction"),
+        (TokenType::DoctypeDecl, "DOCTYPE declaration"),
+        (TokenType::ElementDecl, "element declaration"),
+        (TokenType::AttlistDecl, "attribute list declaration"),
+        (TokenType::EntityDecl, "entity declaration"),
+        (TokenType::NotationDecl, "notation declaration"),
+        (TokenType::DoctypeEnd, "DOCTYPE end"),
+        (TokenType::ElementStart, "element start"),
+        (TokenType::ElementClose, "element close"),
+        (TokenType::Attribute, "attribute"),
+        (TokenType::CDSect, "CDATA"),
+        (TokenType::Whitespace, "whitespace"),
+        (TokenType::CharData, "character data"),
+        (TokenType::Unknown, "unknown"),
+    ];
+
+    for &(token_type, name) in list.iter() {
+        assert_eq!(token_type.to_string(), name);
+    }
+}
+
+#[test]
+fn token_type_in_error() {
+    let err = xml::Tokenizer::from("<a><!DOCTYPE a></a>").nth(2).unwrap().unwrap_err();
+    assert_eq!(err.to_string(), "unexpected token 'DOCTYPE declaration' at 1:4");
+
+    let err = xml::Tokenizer::from("<a/><?xml version='1.0'?>").nth(2).unwrap().unwrap_err();
+    assert_eq!(err.to_string(), "unexpected token 'XML declaration' at 1:5");
+}
@@ -0,0 +1,43 @@
+extern crate xmlparser as xml;
+
+const TEXT: &str = "<?xml version='1.0'?><a x='1'><!--c--><b>text</b><![CDATA[d]]></a>";
+
+#[test]
+fn tokenize_into_01() {
+    let expected: Vec<_> = xml::Tokenizer::from(TEXT).map(|t| t.unwrap()).collect();
+
+    let mut tokens = Vec::new();
+    xml::Tokenizer::from(TEXT).tokenize_into(&mut tokens).unwrap();
+    assert_eq!(tokens, expected);
+
+    // Unlike `PartialEq`, `Debug` includes span positions.
+    let tokens: Vec<_> = tokens.iter().map(|t| format!("{:?}", t)).collect();
+    let expected: Vec<_> = expected.iter().map(|t| format!("{:?}", t)).collect();
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn tokenize_into_02() {
+    let mut tokens = Vec::new();
+    xml::Tokenizer::from(TEXT).tokenize_into(&mut tokens).unwrap();
+    let len = tokens.len();
+    let capacity = tokens.capacity();
+    let ptr = tokens.as_ptr();
+
+    // A document of the same size must not cause a reallocation.
+    let text = TEXT.replace("text", "word");
+    xml::Tokenizer::from(text.as_str()).tokenize_into(&mut tokens).unwrap();
+    assert_eq!(tokens.len(), len);
+    assert_eq!(tokens.capacity(), capacity);
+    assert_eq!(tokens.as_ptr(), ptr);
+}
+
+#[test]
+fn tokenize_into_03() {
+    let mut tokens = Vec::new();
+    xml::Tokenizer::from(TEXT).tokenize_into(&mut tokens).unwrap();
+
+    let err = xml::Tokenizer::from("<a><</a>").tokenize_into(&mut tokens).unwrap_err();
+    assert_eq!(err.to_string(), "invalid token 'element start' at 1:4 cause invalid name token");
+    assert_eq!(tokens.len(), 2);
+}
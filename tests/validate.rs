@@ -0,0 +1,78 @@
+extern crate xmlparser as xml;
+
+macro_rules! test_ok {
+    ($name:ident, $text:expr) => (
+        #[test]
+        fn $name() {
+            xml::validate($text).unwrap();
+        }
+    )
+}
+
+macro_rules! test_err {
+    ($name:ident, $text:expr, $err:expr) => (
+        #[test]
+        fn $name() {
+            assert_eq!(xml::validate($text).unwrap_err().to_string(), $err);
+        }
+    )
+}
+
+test_ok!(validate_01, "<a/>");
+test_ok!(validate_02, "<?xml version='1.0'?>\n<!-- comment -->\n<a><b x='1' y='2'/>text<c></c></a>\n");
+test_ok!(validate_03, "<svg:a><svg:b></svg:b></svg:a>");
+test_ok!(validate_04, "<a x='1' svg:x='2'/>");
+
+test_err!(validate_err_01, "<a><b></a>",
+    "unexpected close tag at 1:7");
+
+test_err!(validate_err_02, "<a>\n  <b>\n</a>",
+    "unexpected close tag at 3:1");
+
+test_err!(validate_err_03, "<a><b>",
+    "unclosed element at 1:4");
+
+test_err!(validate_err_04, "<a x='1' y='2' x='3'/>",
+    "duplicated attribute at 1:16");
+
+test_err!(validate_err_05, "",
+    "the root element is missing at 1:1");
+
+test_err!(validate_err_06, "<!-- comment -->",
+    "the root element is missing at 1:17");
+
+test_err!(validate_err_07, "<a/><b/>",
+    "unexpected token 'element start' at 1:5");
+
+test_err!(validate_err_08, "<a/>text",
+    "unexpected token 'character data' at 1:5");
+
+test_err!(validate_err_09, "<svg:a></a>",
+    "unexpected close tag at 1:8");
+
+#[test]
+fn strict_fragment_01() {
+    let mut p = xml::Tokenizer::from("<a/><b></b>text");
+    p.set_fragment_mode();
+    p.set_strict_mode();
+    for token in p {
+        token.unwrap();
+    }
+}
+
+#[test]
+fn strict_fragment_02() {
+    let mut p = xml::Tokenizer::from("<a/></b>");
+    p.set_fragment_mode();
+    p.set_strict_mode();
+    let err = p.last().unwrap().unwrap_err();
+    assert_eq!(err.to_string(), "unexpected close tag at 1:5");
+}
+
+#[test]
+fn non_strict_01() {
+    // Without the strict mode, the tree structure is not checked.
+    for token in xml::Tokenizer::from("<a x='1' x='2'><b></a>") {
+        token.unwrap();
+    }
+}
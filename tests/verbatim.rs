@@ -0,0 +1,69 @@
+extern crate xmlparser as xml;
+
+fn concat(text: &str, lazy_attributes: bool) -> String {
+    let mut p = xml::Tokenizer::from(text);
+    p.enable_end_of_document_token();
+    p.set_multi_document_mode();
+    if lazy_attributes {
+        p.set_lazy_attributes_mode();
+    }
+
+    let mut s = String::new();
+    let mut end = 0;
+    while let Some(token) = p.next() {
+        token.unwrap();
+        let span = p.verbatim_span();
+        assert_eq!(span.start(), end);
+        end = span.end();
+        s.push_str(span.to_str());
+    }
+
+    s
+}
+
+const DOCUMENTS: &[&str] = &[
+    "<a/>",
+    "\u{FEFF}<?xml version = \"1.0\"  encoding='UTF-8' ?>\r\n<!-- comment -->\n<a/>",
+    "<!DOCTYPE a SYSTEM 'a.dtd' [\n  <!ENTITY b 'c'>\n  <!ELEMENT a ANY>\n  <!ATTLIST a x CDATA #IMPLIED>\n]>\n<a/>\n",
+    "<!DOCTYPE a>\n\n<a/>",
+    "<a  x = 'y'\n\ty=\"&amp; z\"  >text &lt; &#x20;<b/><![CDATA[<c>]]><?pi  data ?></a >  \n",
+    "<svg:a xmlns:svg='http://www.w3.org/2000/svg'>\n  <svg:b/>\n</svg:a>\n<!--after-->\n",
+    "<a/>\n<?xml version='1.0'?>\n<b/>  ",
+];
+
+#[test]
+fn verbatim_01() {
+    for text in DOCUMENTS {
+        assert_eq!(concat(text, false), *text);
+    }
+}
+
+#[test]
+fn verbatim_02() {
+    for text in DOCUMENTS {
+        assert_eq!(concat(text, true), *text);
+    }
+}
+
+#[test]
+fn verbatim_03() {
+    let mut p = xml::Tokenizer::from("<!--c-->\n<a x='1'>text</a>");
+    let spans: Vec<_> = (0..6).map(|_| {
+        p.next().unwrap().unwrap();
+        p.verbatim_span().to_str()
+    }).collect();
+    assert_eq!(spans, vec!["<!--c-->", "\n<a", " x='1'", ">", "text", "</a>"]);
+}
+
+#[test]
+fn verbatim_04() {
+    // An error span ends where the error was detected.
+    let text = "<a><!-- a -- b --></a>";
+    let mut p = xml::Tokenizer::from(text);
+    p.next().unwrap().unwrap();
+    p.next().unwrap().unwrap();
+    assert!(p.next().unwrap().is_err());
+    let span = p.verbatim_span();
+    assert_eq!(span.to_str(), "<!-- a -- b ");
+    assert_eq!(xml::ErrorPos::from_offset(text, span.start()), xml::ErrorPos::new(1, 4));
+}
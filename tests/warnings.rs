@@ -0,0 +1,39 @@
+extern crate xmlparser as xml;
+
+use xml::{Tokenizer, Warning, ErrorPos};
+
+fn collect(text: &str) -> Vec<Warning> {
+    let mut p = Tokenizer::from(text);
+    p.enable_warnings();
+    for token in &mut p {
+        token.unwrap();
+    }
+
+    p.warnings().to_vec()
+}
+
+#[test]
+fn warnings_01() {
+    assert_eq!(collect("\u{FEFF}<a/>"), vec![Warning::BomStripped(ErrorPos::new(1, 1))]);
+}
+
+#[test]
+fn warnings_02() {
+    assert_eq!(collect("<a/>"), vec![]);
+}
+
+#[test]
+fn warnings_03() {
+    let mut p = Tokenizer::from("\u{FEFF}<a/>");
+    for token in &mut p {
+        token.unwrap();
+    }
+
+    assert!(p.warnings().is_empty());
+}
+
+#[test]
+fn warnings_04() {
+    assert_eq!(Warning::BomStripped(ErrorPos::new(1, 1)).to_string(),
+               "byte order mark stripped at 1:1");
+}
@@ -0,0 +1,75 @@
+extern crate xmlparser as xml;
+
+use xml::{QuoteChar, TokenWriter};
+
+fn rewrite(text: &str) -> String {
+    let mut p = xml::Tokenizer::from(text);
+    p.enable_dtd_declaration_tokens();
+    let mut w = TokenWriter::new(String::new());
+    for token in p {
+        w.write_token(&token.unwrap()).unwrap();
+    }
+
+    w.into_inner()
+}
+
+macro_rules! test {
+    ($name:ident, $text:expr) => (
+        #[test]
+        fn $name() {
+            assert_eq!(rewrite($text), $text);
+        }
+    );
+    ($name:ident, $text:expr, $result:expr) => (
+        #[test]
+        fn $name() {
+            assert_eq!(rewrite($text), $result);
+        }
+    )
+}
+
+test!(declaration_01, "<?xml version='1.0' encoding=\"UTF-8\" standalone='no'?><a/>");
+test!(declaration_02, "<?xml version = '1.0' ?><a/>", "<?xml version='1.0'?><a/>");
+test!(pi_01, "<?pi data?><?pi?><a/>");
+test!(comment_01, "<!-- c --><a><!--d--></a>");
+test!(dtd_01, "<!DOCTYPE a SYSTEM 'a.dtd'><a/>");
+test!(dtd_02, "<!DOCTYPE a PUBLIC \"pub\" 'sys' [<!ENTITY b 'c'><!ENTITY % d SYSTEM \"e\">]><a/>");
+test!(dtd_03, "<!DOCTYPE a [<!ELEMENT a (#PCDATA|b)*><!ATTLIST a x CDATA #IMPLIED  y (1|2) '1'>]><a/>");
+test!(dtd_04, "<!DOCTYPE a [<!NOTATION n SYSTEM 'n'><!NOTATION m PUBLIC 'm'><!ATTLIST a>]><a/>");
+test!(elements_01, "<svg:a xmlns:svg='http://www.w3.org/2000/svg' x=\"&amp;\">\n  <b/></svg:a>");
+test!(elements_02, "<a  x = 'y' ><b/></a >", "<a x='y'><b/></a>");
+test!(text_01, "<a>text &lt; &#x20;<![CDATA[<b>]]></a>");
+
+#[test]
+fn verbatim_01() {
+    let text = "<?xml version = \"1.0\"?>\n<a  x = 'y'>\n  <b z='1'/>text\n</a>\n";
+    let mut p = xml::Tokenizer::from(text);
+    p.enable_end_of_document_token();
+    let mut w = TokenWriter::new(String::new());
+    while let Some(token) = p.next() {
+        match token.unwrap() {
+            xml::Token::Attribute((_, local), _, _) if local.to_str() == "z" => {
+                w.write_attribute("", "z", "<2>", QuoteChar::Double).unwrap();
+            }
+            xml::Token::Text(_) => {
+                w.write_text("a & b").unwrap();
+            }
+            _ => {
+                w.write_verbatim(p.verbatim_span()).unwrap();
+            }
+        }
+    }
+
+    assert_eq!(w.into_inner(),
+               "<?xml version = \"1.0\"?>\n<a  x = 'y'>\n  <b z=\"&lt;2&gt;\"/>a &amp; b</a>\n");
+}
+
+#[test]
+fn new_tokens_01() {
+    let mut w = TokenWriter::new(String::new());
+    w.write_token(&xml::Token::ElementStart("".into(), "a".into())).unwrap();
+    w.write_token(&xml::Token::Attribute(("p".into(), "x".into()), "1".into(), 0)).unwrap();
+    w.write_attribute("", "y", "'", QuoteChar::Single).unwrap();
+    w.write_token(&xml::Token::ElementEnd(xml::ElementEnd::Empty)).unwrap();
+    assert_eq!(w.get_ref(), "<a p:x=\"1\" y='&apos;'/>");
+}